@@ -0,0 +1,125 @@
+//! The checked-in compute-unit baseline and the comparison against it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Compute units per benchmark case, keyed by case name. A `BTreeMap` keeps
+/// the file sorted so re-recording it produces minimal diffs.
+pub type Baseline = BTreeMap<String, u64>;
+
+pub fn load(path: &Path) -> Result<Baseline> {
+    let contents = fs::read_to_string(path).with_context(|| format!("reading baseline {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("parsing baseline {}", path.display()))
+}
+
+pub fn save(path: &Path, baseline: &Baseline) -> Result<()> {
+    let contents = serde_json::to_string_pretty(baseline)?;
+    fs::write(path, contents + "\n").with_context(|| format!("writing baseline {}", path.display()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// No recorded value yet
+    New,
+    /// Recorded, but the case no longer runs
+    Removed,
+    /// Within the threshold, including improvements
+    Ok,
+    Regressed,
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub name: String,
+    pub baseline: Option<u64>,
+    pub current: Option<u64>,
+    pub verdict: Verdict,
+}
+
+impl Comparison {
+    /// Change against the baseline in percent, `None` for new and removed
+    /// cases.
+    pub fn delta_percent(&self) -> Option<f64> {
+        let baseline = self.baseline.filter(|baseline| *baseline > 0)?;
+        self.current
+            .map(|current| (current as f64 - baseline as f64) * 100.0 / baseline as f64)
+    }
+}
+
+/// Compares measured compute units against the baseline, in case name
+/// order. A case regresses when it uses more than `threshold_percent` above
+/// its recorded value.
+pub fn compare(baseline: &Baseline, measured: &Baseline, threshold_percent: f64) -> Vec<Comparison> {
+    let names: BTreeSet<&String> = baseline.keys().chain(measured.keys()).collect();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let recorded = baseline.get(name).copied();
+            let current = measured.get(name).copied();
+            let verdict = match (recorded, current) {
+                (None, _) => Verdict::New,
+                (_, None) => Verdict::Removed,
+                (Some(recorded), Some(current)) if current as f64 > recorded as f64 * (1.0 + threshold_percent / 100.0) => {
+                    Verdict::Regressed
+                }
+                _ => Verdict::Ok,
+            };
+
+            Comparison {
+                name: name.clone(),
+                baseline: recorded,
+                current,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(entries: &[(&str, u64)]) -> Baseline {
+        entries.iter().map(|(name, units)| (name.to_string(), *units)).collect()
+    }
+
+    #[test]
+    fn flags_increases_beyond_the_threshold_and_uncovered_cases() {
+        let recorded = baseline(&[("cheaper", 1_000), ("within", 1_000), ("edge", 1_000), ("worse", 1_000), ("gone", 1)]);
+        let measured = baseline(&[("cheaper", 900), ("within", 1_020), ("edge", 1_050), ("worse", 1_051), ("added", 5)]);
+
+        let verdicts: Vec<_> = compare(&recorded, &measured, 5.0)
+            .into_iter()
+            .map(|comparison| (comparison.name, comparison.verdict))
+            .collect();
+
+        assert_eq!(
+            verdicts,
+            [
+                ("added".to_string(), Verdict::New),
+                ("cheaper".to_string(), Verdict::Ok),
+                ("edge".to_string(), Verdict::Ok),
+                ("gone".to_string(), Verdict::Removed),
+                ("within".to_string(), Verdict::Ok),
+                ("worse".to_string(), Verdict::Regressed),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_change_in_percent() {
+        let comparisons = compare(
+            &baseline(&[("swap", 2_000), ("removed", 1)]),
+            &baseline(&[("swap", 2_100), ("new", 1)]),
+            10.0,
+        );
+
+        assert_eq!(comparisons[0].delta_percent(), None);
+        assert_eq!(comparisons[1].delta_percent(), None);
+        assert_eq!(comparisons[2].delta_percent(), Some(5.0));
+    }
+}
@@ -0,0 +1,93 @@
+mod baseline;
+mod scenarios;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use baseline::{Comparison, Verdict};
+
+#[derive(Parser)]
+#[command(
+    name = "minidex-bench",
+    about = "Measure the compute units of every minidex instruction against the recorded baseline"
+)]
+struct Cli {
+    /// SBF build of the program, as produced by `anchor build`
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/minidex.so"))]
+    program: PathBuf,
+
+    /// Baseline file holding the compute units per case
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/compute_units.json"))]
+    baseline: PathBuf,
+
+    /// Allowed increase over the baseline, in percent, before a case fails
+    #[arg(long, default_value_t = 2.0)]
+    threshold: f64,
+
+    /// Overwrite the baseline with the measured values instead of checking them
+    #[arg(long)]
+    update: bool,
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    let program = fs::read(&cli.program)
+        .with_context(|| format!("reading {}, build the program with `anchor build` first", cli.program.display()))?;
+    let measured = scenarios::run(program)?;
+
+    if cli.update {
+        baseline::save(&cli.baseline, &measured)?;
+        println!("Recorded {} cases in {}", measured.len(), cli.baseline.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let comparisons = baseline::compare(&baseline::load(&cli.baseline)?, &measured, cli.threshold);
+    print_report(&comparisons);
+
+    let count = |verdict| comparisons.iter().filter(|comparison| comparison.verdict == verdict).count();
+    let (regressed, new, removed) = (count(Verdict::Regressed), count(Verdict::New), count(Verdict::Removed));
+
+    if regressed > 0 {
+        eprintln!("{regressed} case(s) regressed by more than {}%", cli.threshold);
+    }
+    // A baseline that does not cover every case would let those cases regress unnoticed
+    if new > 0 {
+        eprintln!("{new} case(s) have no baseline");
+    }
+    if removed > 0 {
+        eprintln!("{removed} case(s) in the baseline no longer run");
+    }
+
+    if regressed + new + removed > 0 {
+        eprintln!("Re-record the baseline with --update if intended");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_report(comparisons: &[Comparison]) {
+    let width = comparisons.iter().map(|comparison| comparison.name.len()).max().unwrap_or(0);
+
+    println!("{:<width$}  {:>10}  {:>10}  {:>8}", "case", "baseline", "current", "change");
+    for comparison in comparisons {
+        let baseline = comparison.baseline.map_or("-".to_string(), |units| units.to_string());
+        let current = comparison.current.map_or("-".to_string(), |units| units.to_string());
+        let change = match comparison.verdict {
+            Verdict::New => "new".to_string(),
+            Verdict::Removed => "removed".to_string(),
+            _ => comparison.delta_percent().map_or("-".to_string(), |delta| format!("{delta:+.2}%")),
+        };
+        let marker = if comparison.verdict == Verdict::Regressed { "  REGRESSED" } else { "" };
+
+        println!(
+            "{:<width$}  {:>10}  {:>10}  {:>8}{marker}",
+            comparison.name, baseline, current, change
+        );
+    }
+}
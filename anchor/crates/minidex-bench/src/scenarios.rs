@@ -0,0 +1,351 @@
+//! Every minidex instruction executed under representative states.
+//!
+//! Cases run in order against one account store, so each starts from the
+//! state the previous ones left behind: the pool is created, seeded, traded
+//! against and reconfigured the way it would be on chain.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{bail, Context, Result};
+use minidex_sdk::constants::{GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS, POOL_SWAPS_PAUSED};
+use minidex_sdk::instructions::{self, SwapOptions};
+use minidex_sdk::{find_dca_address, find_limit_order_address, find_permission_address, Dca, FeeDiscountTier, LimitOrder, PoolKeys};
+use minidex_svm::Svm;
+
+use crate::baseline::Baseline;
+
+const LAMPORTS_PER_USER: u64 = 10_000_000_000;
+const INITIAL_BALANCE: u64 = 1_000_000_000_000;
+/// Size of a pool account created before any field was appended to `Pool`.
+const ORIGINAL_POOL_LEN: usize = 8 + 6 * 32 + 2 + 1 + 3 * 8;
+
+struct User {
+    key: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+}
+
+struct Bench {
+    svm: Svm,
+    measured: Baseline,
+}
+
+impl Bench {
+    fn new(program: Vec<u8>) -> Self {
+        Self {
+            svm: Svm::with_program(program),
+            measured: Baseline::new(),
+        }
+    }
+
+    fn create_wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.svm.airdrop(&key, LAMPORTS_PER_USER);
+        key
+    }
+
+    fn create_mint(&mut self, authority: &Pubkey) -> Pubkey {
+        self.svm.create_mint(authority, 6)
+    }
+
+    /// The associated token account of `owner` for `mint`, holding `INITIAL_BALANCE`.
+    fn create_token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        self.svm.create_associated_token_account(mint, owner, INITIAL_BALANCE)
+    }
+
+    fn create_user(&mut self, keys: &PoolKeys) -> User {
+        let key = self.create_wallet();
+
+        User {
+            key,
+            token_a: self.create_token_account(&key, &keys.token_a_mint),
+            token_b: self.create_token_account(&key, &keys.token_b_mint),
+        }
+    }
+
+    fn warp_to_slot(&mut self, slot: u64) {
+        self.svm.warp_to_slot(slot);
+    }
+
+    fn set_time(&mut self, unix_timestamp: i64) {
+        self.svm.set_unix_timestamp(unix_timestamp);
+    }
+
+    fn anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.svm
+            .anchor_account(address)
+            .with_context(|| format!("account {address} does not exist"))
+    }
+
+    /// Runs `instruction` in a transaction of its own, which must succeed, and
+    /// records its compute units under `name`.
+    fn run(&mut self, name: &str, instruction: Instruction) -> Result<()> {
+        let signers: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect();
+
+        match self.svm.process_transaction(&[instruction], &signers) {
+            Ok(metadata) => {
+                self.measured.insert(name.to_string(), metadata.compute_units_consumed);
+                Ok(())
+            }
+            Err(failed) => bail!("{name} failed with {:?}\n{}", failed.error, failed.logs.join("\n")),
+        }
+    }
+}
+
+/// Executes every case and returns the compute units each consumed.
+pub fn run(program: Vec<u8>) -> Result<Baseline> {
+    let mut bench = Bench::new(program);
+
+    let authority = bench.create_wallet();
+    let keys = PoolKeys::derive(&bench.create_mint(&authority), &bench.create_mint(&authority));
+    let pool = keys.pool;
+
+    bench.run("initialize_pool", instructions::initialize_pool(&authority, &keys, 30))?;
+    bench.run("initialize_vault_a", instructions::initialize_vault_a(&authority, &keys))?;
+    bench.run("initialize_vault_b", instructions::initialize_vault_b(&authority, &keys))?;
+    bench.run("initialize_lp_mint", instructions::initialize_lp_mint(&authority, &keys))?;
+
+    let provider = bench.create_user(&keys);
+    let trader = bench.create_user(&keys);
+    let deposit = |user: &User, amount_a, amount_b| {
+        instructions::add_liquidity(&user.key, &keys, &user.token_a, &user.token_b, amount_a, amount_b, 0, None)
+    };
+    let swap = |user: &User, amount_in, token_a_to_b| {
+        instructions::swap_tokens(&user.key, &keys, &user.token_a, &user.token_b, amount_in, 0, token_a_to_b, None)
+    };
+
+    // The first deposit takes the square root path and creates the LP account
+    bench.run("add_liquidity/first_deposit", deposit(&provider, 1_000_000_000, 4_000_000_000))?;
+    bench.run("add_liquidity/new_provider", deposit(&trader, 100_000_000, 400_000_000))?;
+    bench.run("add_liquidity/existing_provider", deposit(&provider, 100_000_000, 400_000_000))?;
+
+    bench.run("add_liquidity_single_sided", {
+        instructions::add_liquidity_single_sided(&trader.key, &keys, &trader.token_a, &trader.token_b, 10_000_000, true, 0, None)
+    })?;
+
+    bench.run("swap_tokens/a_to_b", swap(&trader, 1_000_000, true))?;
+    bench.run("swap_tokens/b_to_a", swap(&trader, 4_000_000, false))?;
+
+    bench.run("quote_swap", instructions::quote_swap(&pool, 1_000_000, true))?;
+    bench.run("quote_add_liquidity", instructions::quote_add_liquidity(&pool, 1_000_000, 4_000_000))?;
+    bench.run("quote_remove_liquidity", instructions::quote_remove_liquidity(&pool, 1_000_000))?;
+
+    bench.run("remove_liquidity/partial", {
+        instructions::remove_liquidity(&trader.key, &keys, &trader.token_a, &trader.token_b, 1_000_000, 0, 0, None)
+    })?;
+
+    bench.run("remove_liquidity_single_sided", {
+        instructions::remove_liquidity_single_sided(&trader.key, &keys, &trader.token_a, &trader.token_b, 1_000_000, false, 0, None)
+    })?;
+
+    bench.run("set_guardian", instructions::set_guardian(&authority, &pool, &Pubkey::new_unique()))?;
+    bench.run("set_pool_status", instructions::set_pool_status(&authority, &pool, POOL_SWAPS_PAUSED))?;
+    bench.run("set_pool_status/resume", instructions::set_pool_status(&authority, &pool, 0))?;
+    bench.run("set_price_limits", instructions::set_price_limits(&authority, &pool, 500, 1_000))?;
+
+    // With limits set, swaps also evaluate price impact and the per-slot move
+    bench.run("swap_tokens/price_limits", swap(&trader, 1_000_000, true))?;
+    bench.warp_to_slot(2);
+    bench.run("swap_tokens/price_limits_new_slot", swap(&trader, 1_000_000, true))?;
+
+    // Referrals add an optional account and a transfer of part of the fee
+    let referrer = bench.create_wallet();
+    let referrer_token_a = bench.create_token_account(&referrer, &keys.token_a_mint);
+    bench.run("set_max_referral_bps", instructions::set_max_referral_bps(&authority, &pool, 5_000))?;
+    bench.run(
+        "swap_tokens/referral",
+        instructions::swap_tokens_with_referral(
+            &trader.key,
+            &keys,
+            &trader.token_a,
+            &trader.token_b,
+            1_000_000,
+            0,
+            true,
+            &referrer,
+            &referrer_token_a,
+            5_000,
+            None,
+        ),
+    )?;
+
+    // The dynamic fee mode decays the accumulator before pricing the swap
+    bench.run("set_dynamic_fee", instructions::set_dynamic_fee(&authority, &pool, 10, 1_000))?;
+    bench.warp_to_slot(1_000);
+    bench.run("swap_tokens/dynamic_fee", swap(&trader, 1_000_000, true))?;
+
+    // Fee discounts read the config and the trader's stake on top of the usual swap
+    let governance_mint = bench.create_mint(&authority);
+    let trader_governance = bench.create_token_account(&trader.key, &governance_mint);
+    let tiers = vec![
+        FeeDiscountTier { min_stake: 1_000_000, discount_bps: 2_000 },
+        FeeDiscountTier { min_stake: 10_000_000, discount_bps: 5_000 },
+    ];
+    let stake = |amount| instructions::stake_governance(&trader.key, &governance_mint, &trader_governance, amount);
+
+    bench.svm.set_upgrade_authority(Some(authority));
+    bench.run("initialize_config", instructions::initialize_config(&authority, &governance_mint))?;
+    bench.run("set_fee_discounts", instructions::set_fee_discounts(&authority, tiers))?;
+    bench.run("stake_governance/new_stake", stake(10_000_000))?;
+    bench.run("stake_governance/existing_stake", stake(1_000_000))?;
+    // Stake only counts from the slot after it was added
+    bench.warp_to_slot(1_001);
+    bench.run(
+        "swap_tokens/fee_discount",
+        instructions::swap_tokens_with_options(
+            &trader.key,
+            &keys,
+            &trader.token_a,
+            &trader.token_b,
+            1_000_000,
+            0,
+            true,
+            SwapOptions {
+                fee_discount: true,
+                ..SwapOptions::default()
+            },
+        ),
+    )?;
+    bench.warp_to_slot(1_001 + GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS);
+    bench.run("unstake_governance", instructions::unstake_governance(&trader.key, &trader_governance, 1_000_000))?;
+
+    // With the allowlist enabled, trades also check the wallet's permission
+    bench.run("set_allowlist", instructions::set_allowlist(&authority, &pool, true))?;
+    bench.run("grant_access", instructions::grant_access(&authority, &pool, &trader.key))?;
+    bench.run(
+        "swap_tokens/allowlist",
+        instructions::swap_tokens_with_options(
+            &trader.key,
+            &keys,
+            &trader.token_a,
+            &trader.token_b,
+            1_000_000,
+            0,
+            true,
+            SwapOptions {
+                permission: Some(find_permission_address(&pool, &trader.key).0),
+                ..SwapOptions::default()
+            },
+        ),
+    )?;
+    bench.run("revoke_access", instructions::revoke_access(&authority, &pool, &trader.key))?;
+    bench.run("set_allowlist/disable", instructions::set_allowlist(&authority, &pool, false))?;
+
+    // Pool creation through the token registry reads both mints' registrations
+    let registered_keys = PoolKeys::derive(&bench.create_mint(&authority), &bench.create_mint(&authority));
+    bench.run("set_token_registry", instructions::set_token_registry(&authority, true))?;
+    bench.run("register_token", instructions::register_token(&authority, &registered_keys.token_a_mint, false))?;
+    bench.run("register_token/override", instructions::register_token(&authority, &registered_keys.token_b_mint, true))?;
+    bench.run(
+        "initialize_pool/registered",
+        instructions::initialize_registered_pool(&authority, &registered_keys, 30),
+    )?;
+    bench.run("deregister_token", instructions::deregister_token(&authority, &registered_keys.token_b_mint))?;
+    bench.run("set_token_registry/disable", instructions::set_token_registry(&authority, false))?;
+
+    // Limit orders, one filled by a keeper and one cancelled by its maker
+    let keeper = bench.create_wallet();
+    let keeper_token_a = bench.create_token_account(&keeper, &keys.token_a_mint);
+    let place_order = |order_id, min_amount_out| {
+        instructions::place_limit_order(
+            &trader.key,
+            &keys,
+            &trader.token_a,
+            &trader.token_b,
+            order_id,
+            true,
+            1_000_000,
+            min_amount_out,
+            1_000,
+            None,
+        )
+    };
+
+    bench.run("place_limit_order", place_order(1, 1))?;
+    let (order_address, _) = find_limit_order_address(&pool, &trader.key, 1);
+    let order: LimitOrder = bench.anchor_account(&order_address)?;
+    bench.run(
+        "fill_limit_order",
+        instructions::fill_limit_order(&keeper, &keys, &order_address, &order, &keeper_token_a, None),
+    )?;
+
+    bench.run("place_limit_order/unfillable", place_order(2, u64::MAX))?;
+    bench.run("cancel_limit_order", instructions::cancel_limit_order(&trader.key, &pool, 2, &trader.token_a))?;
+
+    // Farming, with two reward streams so updates walk more than one slot
+    let reward_mints = [bench.create_mint(&authority), bench.create_mint(&authority)];
+    bench.set_time(1_000);
+
+    bench.run("initialize_farm", instructions::initialize_farm(&authority, &keys))?;
+    for (index, mint) in reward_mints.iter().enumerate() {
+        let account = bench.create_token_account(&authority, mint);
+        bench.run(
+            &format!("fund_farm_reward/new_reward_{index}"),
+            instructions::fund_farm_reward(&authority, &pool, mint, &account, 1_000, 2_000, 1_000_000),
+        )?;
+    }
+
+    bench.run("stake_lp/new_position", instructions::stake_lp(&provider.key, &keys, 1_000_000))?;
+    bench.set_time(1_100);
+    bench.run("stake_lp/existing_position", instructions::stake_lp(&provider.key, &keys, 1_000_000))?;
+
+    let reward_account = bench.create_token_account(&provider.key, &reward_mints[0]);
+    bench.set_time(1_200);
+    bench.run("claim_rewards", instructions::claim_rewards(&provider.key, &pool, &reward_mints[0], &reward_account, 0))?;
+    bench.run("unstake_lp", instructions::unstake_lp(&provider.key, &keys, 1_000_000))?;
+    bench.run("emergency_unstake_lp", instructions::emergency_unstake_lp(&provider.key, &keys))?;
+
+    let funding = get_associated_token_address(&authority, &reward_mints[0]);
+    bench.run(
+        "fund_farm_reward/extend",
+        instructions::fund_farm_reward(&authority, &pool, &reward_mints[0], &funding, 1_200, 3_000, 1_000_000),
+    )?;
+
+    // A two slice DCA schedule run to completion, and one cancelled by its owner
+    let open_dca = |dca_id| {
+        instructions::open_dca(&trader.key, &keys, &trader.token_a, &trader.token_b, dca_id, true, 1_000_000, 2, 100, 1, None)
+    };
+    let execute_slice = |bench: &mut Bench, name: &str, dca_id| -> Result<()> {
+        let (dca_address, _) = find_dca_address(&pool, &trader.key, dca_id);
+        let dca: Dca = bench.anchor_account(&dca_address)?;
+        bench.run(name, instructions::execute_dca_slice(&keeper, &keys, &dca_address, &dca, None))
+    };
+
+    bench.run("open_dca", open_dca(1))?;
+    execute_slice(&mut bench, "execute_dca_slice", 1)?;
+    bench.set_time(1_300);
+    execute_slice(&mut bench, "execute_dca_slice/last_slice", 1)?;
+    let (dca_address, _) = find_dca_address(&pool, &trader.key, 1);
+    let dca: Dca = bench.anchor_account(&dca_address)?;
+    bench.run("close_dca", instructions::close_dca(&keeper, &keys, &dca_address, &dca))?;
+
+    bench.run("open_dca/cancelled", open_dca(2))?;
+    bench.run("cancel_dca", instructions::cancel_dca(&trader.key, &pool, 2, &trader.token_a))?;
+
+    // Cut the pool back to the layout it had before fields were appended to it
+    let mut account = bench.svm.account(&pool).context("pool exists")?;
+    account.data.truncate(ORIGINAL_POOL_LEN);
+    account.lamports = bench.svm.rent().minimum_balance(ORIGINAL_POOL_LEN);
+    bench.svm.set_account(pool, account);
+    bench.run("migrate_pool", instructions::migrate_pool(&authority, &pool))?;
+
+    // The registered pool is never seeded, so it can be closed once set up
+    bench.run("initialize_vault_a/registered", instructions::initialize_vault_a(&authority, &registered_keys))?;
+    bench.run("initialize_vault_b/registered", instructions::initialize_vault_b(&authority, &registered_keys))?;
+    bench.run("initialize_lp_mint/registered", instructions::initialize_lp_mint(&authority, &registered_keys))?;
+    let authority_token_a = bench.create_token_account(&authority, &registered_keys.token_a_mint);
+    let authority_token_b = bench.create_token_account(&authority, &registered_keys.token_b_mint);
+    bench.run(
+        "close_pool",
+        instructions::close_pool(&authority, &registered_keys, &authority_token_a, &authority_token_b),
+    )?;
+
+    Ok(bench.measured)
+}
@@ -0,0 +1,263 @@
+mod output;
+mod rpc;
+
+use std::path::PathBuf;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use minidex_sdk::{deserialize_pool, instructions, pool_permission, Pool, PoolKeys};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use rpc::RpcClient;
+
+#[derive(Parser)]
+#[command(name = "minidex-cli", version, about = "Create and operate minidex pools")]
+struct Cli {
+    /// JSON-RPC endpoint of the cluster
+    #[arg(long, short = 'u', env = "MINIDEX_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair file that signs and pays for transactions
+    #[arg(long, short = 'k', env = "MINIDEX_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a pool together with its vaults and LP mint
+    CreatePool {
+        #[arg(long)]
+        mint_a: Pubkey,
+        #[arg(long)]
+        mint_b: Pubkey,
+        /// Swap fee in basis points
+        #[arg(long, default_value_t = 30)]
+        fee_rate: u16,
+        /// Pass the mints' token registry entries, needed while the registry is enabled
+        #[arg(long)]
+        registered: bool,
+    },
+    /// Deposit both tokens and receive LP tokens
+    AddLiquidity {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        amount_a: u64,
+        #[arg(long)]
+        amount_b: u64,
+        #[arg(long, default_value_t = 0)]
+        min_lp_tokens: u64,
+    },
+    /// Burn LP tokens and withdraw both tokens
+    RemoveLiquidity {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        lp_tokens: u64,
+        #[arg(long, default_value_t = 0)]
+        min_amount_a: u64,
+        #[arg(long, default_value_t = 0)]
+        min_amount_b: u64,
+    },
+    /// Swap one pool token for the other
+    Swap {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        amount_in: u64,
+        #[arg(long, default_value_t = 0)]
+        min_amount_out: u64,
+        /// Which token is sold
+        #[arg(long, value_enum)]
+        sell: Side,
+    },
+    /// Print the state of a pool
+    Show {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Side {
+    A,
+    B,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = RpcClient::new(&cli.url);
+
+    match cli.command {
+        Command::CreatePool {
+            mint_a,
+            mint_b,
+            fee_rate,
+            registered,
+        } => {
+            let payer = load_keypair(&cli.keypair)?;
+            let keys = PoolKeys::derive(&mint_a, &mint_b);
+            let create_pool = if registered {
+                instructions::create_registered_pool
+            } else {
+                instructions::create_pool
+            };
+
+            send(&client, &payer, &create_pool(&payer.pubkey(), &keys, fee_rate))?;
+
+            println!("Pool: {}", keys.pool);
+            println!("Token A vault: {}", keys.token_a_vault);
+            println!("Token B vault: {}", keys.token_b_vault);
+            println!("LP mint: {}", keys.lp_mint);
+        }
+        Command::AddLiquidity {
+            pool,
+            amount_a,
+            amount_b,
+            min_lp_tokens,
+        } => {
+            let payer = load_keypair(&cli.keypair)?;
+            let state = fetch_pool(&client, &pool)?;
+            let keys = PoolKeys::from_pool(&pool, &state);
+            let user = payer.pubkey();
+            let permission = pool_permission(&pool, &state, &user);
+
+            send(
+                &client,
+                &payer,
+                &[instructions::add_liquidity(
+                    &user,
+                    &keys,
+                    &get_associated_token_address(&user, &keys.token_a_mint),
+                    &get_associated_token_address(&user, &keys.token_b_mint),
+                    amount_a,
+                    amount_b,
+                    min_lp_tokens,
+                    permission,
+                )],
+            )?;
+        }
+        Command::RemoveLiquidity {
+            pool,
+            lp_tokens,
+            min_amount_a,
+            min_amount_b,
+        } => {
+            let payer = load_keypair(&cli.keypair)?;
+            let state = fetch_pool(&client, &pool)?;
+            let keys = PoolKeys::from_pool(&pool, &state);
+            let user = payer.pubkey();
+            let permission = pool_permission(&pool, &state, &user);
+
+            let mut ixs = create_token_accounts(&user, &[keys.token_a_mint, keys.token_b_mint]);
+            ixs.push(instructions::remove_liquidity(
+                &user,
+                &keys,
+                &get_associated_token_address(&user, &keys.token_a_mint),
+                &get_associated_token_address(&user, &keys.token_b_mint),
+                lp_tokens,
+                min_amount_a,
+                min_amount_b,
+                permission,
+            ));
+
+            send(&client, &payer, &ixs)?;
+        }
+        Command::Swap {
+            pool,
+            amount_in,
+            min_amount_out,
+            sell,
+        } => {
+            let payer = load_keypair(&cli.keypair)?;
+            let state = fetch_pool(&client, &pool)?;
+            let keys = PoolKeys::from_pool(&pool, &state);
+            let user = payer.pubkey();
+            let permission = pool_permission(&pool, &state, &user);
+
+            let mint_out = match sell {
+                Side::A => keys.token_b_mint,
+                Side::B => keys.token_a_mint,
+            };
+
+            let mut ixs = create_token_accounts(&user, &[mint_out]);
+            ixs.push(instructions::swap_tokens(
+                &user,
+                &keys,
+                &get_associated_token_address(&user, &keys.token_a_mint),
+                &get_associated_token_address(&user, &keys.token_b_mint),
+                amount_in,
+                min_amount_out,
+                matches!(sell, Side::A),
+                permission,
+            ));
+
+            send(&client, &payer, &ixs)?;
+        }
+        Command::Show { pool, format } => {
+            let state = fetch_pool(&client, &pool)?;
+
+            match format {
+                Format::Table => output::print_pool_table(&pool, &state),
+                Format::Json => println!("{}", serde_json::to_string_pretty(&output::pool_json(&pool, &state))?),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn load_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = std::env::var("HOME").context("HOME is not set")?;
+            PathBuf::from(home).join(rest)
+        }
+        None => PathBuf::from(path),
+    };
+
+    read_keypair_file(&path).map_err(|e| anyhow!("failed to read keypair {}: {e}", path.display()))
+}
+
+fn fetch_pool(client: &RpcClient, address: &Pubkey) -> Result<Pool> {
+    let data = client
+        .get_account_data(address)?
+        .ok_or_else(|| anyhow!("pool {address} does not exist"))?;
+
+    deserialize_pool(&data).map_err(|e| anyhow!("{address} is not a minidex pool: {e}"))
+}
+
+/// Idempotently creates the user's associated token accounts so tokens paid
+/// out by the pool have somewhere to land.
+fn create_token_accounts(user: &Pubkey, mints: &[Pubkey]) -> Vec<Instruction> {
+    mints
+        .iter()
+        .map(|mint| create_associated_token_account_idempotent(user, user, mint, &anchor_spl::token::ID))
+        .collect()
+}
+
+fn send(client: &RpcClient, payer: &Keypair, ixs: &[Instruction]) -> Result<()> {
+    let blockhash = client.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(ixs, Some(&payer.pubkey()), &[payer], blockhash);
+
+    let signature = client.send_and_confirm_transaction(&transaction)?;
+    println!("Signature: {signature}");
+
+    Ok(())
+}
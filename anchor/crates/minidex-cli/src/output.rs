@@ -0,0 +1,68 @@
+use minidex_sdk::constants::{POOL_DEPOSITS_PAUSED, POOL_SWAPS_PAUSED, POOL_WITHDRAWALS_PAUSED};
+use minidex_sdk::Pool;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+fn status_flags(status: u8) -> Vec<&'static str> {
+    [
+        (POOL_SWAPS_PAUSED, "swaps_paused"),
+        (POOL_DEPOSITS_PAUSED, "deposits_paused"),
+        (POOL_WITHDRAWALS_PAUSED, "withdrawals_paused"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| status & flag != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+fn rows(address: &Pubkey, pool: &Pool) -> Vec<(&'static str, String)> {
+    let flags = status_flags(pool.status);
+
+    vec![
+        ("address", address.to_string()),
+        ("authority", pool.authority.to_string()),
+        ("guardian", pool.guardian.to_string()),
+        ("token_a_mint", pool.token_a_mint.to_string()),
+        ("token_b_mint", pool.token_b_mint.to_string()),
+        ("token_a_vault", pool.token_a_vault.to_string()),
+        ("token_b_vault", pool.token_b_vault.to_string()),
+        ("lp_mint", pool.lp_mint.to_string()),
+        ("fee_rate_bps", pool.fee_rate.to_string()),
+        ("status", if flags.is_empty() { "active".to_string() } else { flags.join(", ") }),
+        ("reserve_a", pool.reserve_a.to_string()),
+        ("reserve_b", pool.reserve_b.to_string()),
+        ("total_lp_supply", pool.total_lp_supply.to_string()),
+        ("max_price_impact_bps", pool.max_price_impact_bps.to_string()),
+        ("max_slot_price_move_bps", pool.max_slot_price_move_bps.to_string()),
+    ]
+}
+
+pub fn print_pool_table(address: &Pubkey, pool: &Pool) {
+    let rows = rows(address, pool);
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in rows {
+        println!("{name:<width$}  {value}");
+    }
+}
+
+pub fn pool_json(address: &Pubkey, pool: &Pool) -> Value {
+    json!({
+        "address": address.to_string(),
+        "authority": pool.authority.to_string(),
+        "guardian": pool.guardian.to_string(),
+        "tokenAMint": pool.token_a_mint.to_string(),
+        "tokenBMint": pool.token_b_mint.to_string(),
+        "tokenAVault": pool.token_a_vault.to_string(),
+        "tokenBVault": pool.token_b_vault.to_string(),
+        "lpMint": pool.lp_mint.to_string(),
+        "feeRate": pool.fee_rate,
+        "status": pool.status,
+        "statusFlags": status_flags(pool.status),
+        "reserveA": pool.reserve_a,
+        "reserveB": pool.reserve_b,
+        "totalLpSupply": pool.total_lp_supply,
+        "maxPriceImpactBps": pool.max_price_impact_bps,
+        "maxSlotPriceMoveBps": pool.max_slot_price_move_bps,
+    })
+}
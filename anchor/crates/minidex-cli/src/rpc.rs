@@ -0,0 +1,139 @@
+//! Minimal blocking JSON-RPC client covering the calls the CLI makes.
+
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct BlockhashValue {
+    blockhash: String,
+}
+
+#[derive(Deserialize)]
+struct AccountValue {
+    data: (String, String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    err: Option<Value>,
+    confirmation_status: Option<String>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: RpcResponse<T> = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} request to {} failed", self.url))?
+            .into_json()
+            .with_context(|| format!("invalid {method} response"))?;
+
+        if let Some(error) = response.error {
+            bail!("{method} failed ({}): {}", error.code, error.message);
+        }
+
+        response.result.ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let response: WithContext<BlockhashValue> =
+            self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+
+        Hash::from_str(&response.value.blockhash).map_err(|e| anyhow!("invalid blockhash: {e}"))
+    }
+
+    /// Returns `None` when the account does not exist.
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let response: WithContext<Option<AccountValue>> = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+
+        response
+            .value
+            .map(|account| BASE64.decode(account.data.0).context("invalid account data"))
+            .transpose()
+    }
+
+    pub fn send_and_confirm_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        let encoded = BASE64.encode(bincode::serialize(transaction)?);
+
+        let signature: String = self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = Signature::from_str(&signature)?;
+
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let statuses: WithContext<Vec<Option<SignatureStatus>>> =
+                self.call("getSignatureStatuses", json!([[signature.to_string()]]))?;
+
+            if let Some(Some(status)) = statuses.value.into_iter().next() {
+                if let Some(err) = status.err {
+                    bail!("transaction {signature} failed: {err}");
+                }
+                if matches!(status.confirmation_status.as_deref(), Some("confirmed" | "finalized")) {
+                    return Ok(signature);
+                }
+            }
+
+            sleep(CONFIRM_POLL_INTERVAL);
+        }
+
+        bail!("transaction {signature} was not confirmed within {CONFIRM_TIMEOUT:?}")
+    }
+}
@@ -0,0 +1,255 @@
+//! Decoding of `getBlock` and `getTransaction` results in `json` encoding.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Context, Result};
+use minidex::instruction;
+use minidex_sdk::events::{parse_logs, MinidexEvent};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedTransaction {
+    /// Only present in `getTransaction` results
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+    pub transaction: UiTransaction,
+    pub meta: Option<UiTransactionMeta>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UiTransaction {
+    pub signatures: Vec<String>,
+    pub message: UiMessage,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMessage {
+    pub account_keys: Vec<String>,
+    pub instructions: Vec<UiInstruction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiInstruction {
+    pub program_id_index: usize,
+    pub accounts: Vec<usize>,
+    /// Base58 encoded
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTransactionMeta {
+    pub err: Option<Value>,
+    pub log_messages: Option<Vec<String>>,
+    pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    pub loaded_addresses: Option<UiLoadedAddresses>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UiInnerInstructions {
+    /// Index of the top-level instruction that made these calls
+    pub index: usize,
+    pub instructions: Vec<UiInstruction>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct UiLoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncodedBlock {
+    slot: Option<u64>,
+    block_time: Option<i64>,
+    transactions: Vec<EncodedTransaction>,
+}
+
+/// The transactions of one slot, in block order.
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transactions: Vec<EncodedTransaction>,
+}
+
+impl Block {
+    /// Reads a `getBlock` or `getTransaction` result, optionally still wrapped
+    /// in its JSON-RPC response. `getBlock` results do not contain their slot,
+    /// so it has to come from `slot` unless the document has a `slot` field.
+    pub fn from_json(document: Value, slot: Option<u64>) -> Result<Self> {
+        let document = match document {
+            Value::Object(mut response) if response.contains_key("jsonrpc") => {
+                if let Some(error) = response.get("error") {
+                    bail!("RPC error response: {error}");
+                }
+                match response.remove("result") {
+                    Some(Value::Null) | None => bail!("RPC response has no result"),
+                    Some(result) => result,
+                }
+            }
+            document => document,
+        };
+
+        if document.get("transactions").is_some() {
+            let block: EncodedBlock = serde_json::from_value(document).context("invalid block, expected json encoding")?;
+
+            Ok(Self {
+                slot: block.slot.or(slot).ok_or_else(|| anyhow!("block has no slot"))?,
+                block_time: block.block_time,
+                transactions: block.transactions,
+            })
+        } else {
+            let transaction: EncodedTransaction =
+                serde_json::from_value(document).context("invalid transaction, expected json encoding")?;
+
+            Ok(Self {
+                slot: transaction.slot.or(slot).ok_or_else(|| anyhow!("transaction has no slot"))?,
+                block_time: transaction.block_time,
+                transactions: vec![transaction],
+            })
+        }
+    }
+}
+
+/// Minidex instructions that change pool state the events do not carry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoolInstruction {
+    InitializePool {
+        pool: Pubkey,
+        authority: Pubkey,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        token_a_vault: Pubkey,
+        token_b_vault: Pubkey,
+        lp_mint: Pubkey,
+        fee_rate: u16,
+    },
+    SetPoolStatus {
+        pool: Pubkey,
+        status: u8,
+    },
+    ClosePool {
+        pool: Pubkey,
+    },
+}
+
+/// Everything minidex did in one successful transaction.
+#[derive(Clone, Debug)]
+pub struct DecodedTransaction {
+    pub signature: String,
+    /// Top-level and inner instructions in execution order
+    pub instructions: Vec<PoolInstruction>,
+    pub events: Vec<MinidexEvent>,
+}
+
+/// Decodes a transaction. Failed transactions changed nothing and decode to
+/// `None`.
+pub fn decode_transaction(transaction: &EncodedTransaction) -> Result<Option<DecodedTransaction>> {
+    let Some(meta) = &transaction.meta else {
+        bail!("transaction has no status metadata");
+    };
+    if meta.err.is_some() {
+        return Ok(None);
+    }
+
+    let signature = transaction
+        .transaction
+        .signatures
+        .first()
+        .ok_or_else(|| anyhow!("transaction has no signature"))?
+        .clone();
+
+    // Address lookup table entries follow the static keys, writable ones first
+    let loaded = meta.loaded_addresses.clone().unwrap_or_default();
+    let account_keys = transaction
+        .transaction
+        .message
+        .account_keys
+        .iter()
+        .chain(&loaded.writable)
+        .chain(&loaded.readonly)
+        .map(|key| Pubkey::from_str(key).map_err(|e| anyhow!("invalid account key {key}: {e}")))
+        .collect::<Result<Vec<_>>>()?;
+
+    let inner_instructions = meta.inner_instructions.as_deref().unwrap_or_default();
+    let mut instructions = Vec::new();
+
+    for (index, top_level) in transaction.transaction.message.instructions.iter().enumerate() {
+        let inner = inner_instructions
+            .iter()
+            .filter(|inner| inner.index == index)
+            .flat_map(|inner| &inner.instructions);
+
+        for ui_instruction in std::iter::once(top_level).chain(inner) {
+            let decoded = decode_instruction(&account_keys, ui_instruction)
+                .with_context(|| format!("instruction {index} of {signature}"))?;
+            instructions.extend(decoded);
+        }
+    }
+
+    Ok(Some(DecodedTransaction {
+        events: parse_logs(meta.log_messages.as_deref().unwrap_or_default()),
+        signature,
+        instructions,
+    }))
+}
+
+fn decode_instruction(account_keys: &[Pubkey], ui_instruction: &UiInstruction) -> Result<Option<PoolInstruction>> {
+    let key = |index: usize| {
+        account_keys
+            .get(index)
+            .copied()
+            .ok_or_else(|| anyhow!("account index {index} out of range"))
+    };
+    let account = |position: usize| {
+        let index = ui_instruction
+            .accounts
+            .get(position)
+            .ok_or_else(|| anyhow!("missing account {position}"))?;
+        key(*index)
+    };
+
+    if key(ui_instruction.program_id_index)? != minidex::ID {
+        return Ok(None);
+    }
+
+    let data = bs58::decode(&ui_instruction.data).into_vec().context("invalid instruction data")?;
+
+    if let Some(mut args) = data.strip_prefix(instruction::InitializePool::DISCRIMINATOR) {
+        let args = instruction::InitializePool::deserialize(&mut args).context("invalid initialize_pool data")?;
+
+        return Ok(Some(PoolInstruction::InitializePool {
+            authority: account(0)?,
+            token_a_mint: account(1)?,
+            token_b_mint: account(2)?,
+            pool: account(3)?,
+            token_a_vault: args.token_a_vault,
+            token_b_vault: args.token_b_vault,
+            lp_mint: args.lp_mint,
+            fee_rate: args.fee_rate,
+        }));
+    }
+
+    if let Some(mut args) = data.strip_prefix(instruction::SetPoolStatus::DISCRIMINATOR) {
+        let args = instruction::SetPoolStatus::deserialize(&mut args).context("invalid set_pool_status data")?;
+
+        return Ok(Some(PoolInstruction::SetPoolStatus {
+            pool: account(1)?,
+            status: args.status,
+        }));
+    }
+
+    if data.starts_with(instruction::ClosePool::DISCRIMINATOR) {
+        return Ok(Some(PoolInstruction::ClosePool { pool: account(1)? }));
+    }
+
+    Ok(None)
+}
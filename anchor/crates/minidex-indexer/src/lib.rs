@@ -0,0 +1,114 @@
+//! Indexer that rebuilds minidex pool history from transactions.
+//!
+//! Blocks come from a validator's JSON-RPC endpoint or from `getBlock` and
+//! `getTransaction` results saved as files. Pool creations and status changes
+//! are decoded from minidex instructions, swaps, deposits and withdrawals from
+//! the events the program emits. Everything is written to SQLite together with
+//! the last indexed slot, so a restarted indexer carries on where it stopped.
+
+pub mod decode;
+pub mod rpc;
+pub mod store;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use decode::{decode_transaction, Block};
+use rpc::RpcClient;
+use store::{BlockSummary, Store};
+
+/// Slots requested per `getBlocks` call.
+pub const BLOCK_RANGE: u64 = 500;
+
+/// Decodes `block` and stores it in one database transaction.
+pub fn index_block(store: &mut Store, block: &Block) -> Result<BlockSummary> {
+    let mut transactions = Vec::new();
+
+    for (index, transaction) in block.transactions.iter().enumerate() {
+        if let Some(decoded) = decode_transaction(transaction).with_context(|| format!("slot {}", block.slot))? {
+            if !decoded.instructions.is_empty() || !decoded.events.is_empty() {
+                transactions.push((index, decoded));
+            }
+        }
+    }
+
+    store.insert_block(block.slot, block.block_time, &transactions)
+}
+
+/// Reads `getBlock` or `getTransaction` results from `paths`. A block file
+/// without a `slot` field has to be named after its slot, e.g. `1234.json`.
+///
+/// Documents of the same slot are merged in the order given, then indexed in
+/// slot order. Slots at or below the cursor are skipped.
+pub fn index_files(store: &mut Store, paths: &[PathBuf]) -> Result<Vec<(u64, BlockSummary)>> {
+    let mut blocks: BTreeMap<u64, Block> = BTreeMap::new();
+
+    for path in paths {
+        let block = read_block_file(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+        match blocks.get_mut(&block.slot) {
+            Some(merged) => {
+                merged.block_time = merged.block_time.or(block.block_time);
+                merged.transactions.extend(block.transactions);
+            }
+            None => {
+                blocks.insert(block.slot, block);
+            }
+        }
+    }
+
+    let last_slot = store.last_slot()?;
+    let mut summaries = Vec::new();
+
+    for block in blocks.into_values() {
+        if last_slot.is_some_and(|last_slot| block.slot <= last_slot) {
+            continue;
+        }
+        summaries.push((block.slot, index_block(store, &block)?));
+    }
+
+    Ok(summaries)
+}
+
+fn read_block_file(path: &Path) -> Result<Block> {
+    let document = serde_json::from_slice(&std::fs::read(path)?)?;
+    let slot = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse().ok());
+
+    Block::from_json(document, slot)
+}
+
+/// Indexes the blocks after the cursor, or from `start_slot` for a new
+/// database, up to the current slot of `rpc`. `on_block` sees every indexed
+/// block. Returns the new cursor.
+pub fn index_rpc(
+    store: &mut Store,
+    rpc: &RpcClient,
+    start_slot: u64,
+    mut on_block: impl FnMut(u64, &BlockSummary),
+) -> Result<Option<u64>> {
+    let mut next_slot = store.last_slot()?.map_or(start_slot, |last_slot| (last_slot + 1).max(start_slot));
+    let tip = rpc.get_slot()?;
+
+    while next_slot <= tip {
+        let end_slot = tip.min(next_slot + BLOCK_RANGE - 1);
+
+        for slot in rpc.get_blocks(next_slot, end_slot)? {
+            let document = rpc.get_block(slot)?;
+            let block = Block::from_json(document, Some(slot)).with_context(|| format!("invalid block {slot}"))?;
+            let summary = index_block(store, &block)?;
+
+            on_block(slot, &summary);
+        }
+
+        // Slots without a block in the range are done as well
+        store.advance_cursor(end_slot)?;
+        next_slot = end_slot + 1;
+    }
+
+    store.last_slot()
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use minidex_indexer::rpc::RpcClient;
+use minidex_indexer::store::{BlockSummary, Store};
+use minidex_indexer::{index_files, index_rpc};
+
+#[derive(Parser)]
+#[command(name = "minidex-indexer", version, about = "Index minidex pool history into SQLite")]
+struct Cli {
+    /// SQLite database, created on first use
+    #[arg(long, env = "MINIDEX_INDEX_DB", default_value = "minidex-index.sqlite")]
+    db: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index blocks from a JSON-RPC endpoint, resuming after the last indexed slot
+    Rpc {
+        #[arg(long, short = 'u', env = "MINIDEX_RPC_URL", default_value = "http://127.0.0.1:8899")]
+        url: String,
+        #[arg(long, value_enum, default_value_t = Commitment::Finalized)]
+        commitment: Commitment,
+        /// First slot to index when the database is new
+        #[arg(long, default_value_t = 0)]
+        start_slot: u64,
+        /// Keep polling for new blocks instead of exiting once caught up
+        #[arg(long)]
+        follow: bool,
+        #[arg(long, default_value_t = 2_000)]
+        poll_interval_ms: u64,
+    },
+    /// Index getBlock or getTransaction results saved as JSON files
+    Files {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Commitment {
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+fn report(slot: u64, summary: &BlockSummary) {
+    if !summary.is_empty() {
+        println!(
+            "slot {slot}: {} pools, {} swaps, {} deposits, {} withdrawals",
+            summary.pools, summary.swaps, summary.deposits, summary.withdrawals
+        );
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.db)?;
+
+    match cli.command {
+        Command::Rpc {
+            url,
+            commitment,
+            start_slot,
+            follow,
+            poll_interval_ms,
+        } => {
+            let rpc = RpcClient::new(&url, commitment.as_str());
+
+            loop {
+                let last_slot = index_rpc(&mut store, &rpc, start_slot, report)?;
+
+                if !follow {
+                    match last_slot {
+                        Some(last_slot) => println!("indexed up to slot {last_slot}"),
+                        None => println!("no blocks to index"),
+                    }
+                    break;
+                }
+                sleep(Duration::from_millis(poll_interval_ms));
+            }
+        }
+        Command::Files { paths } => {
+            for (slot, summary) in index_files(&mut store, &paths)? {
+                report(slot, &summary);
+            }
+            if let Some(last_slot) = store.last_slot()? {
+                println!("indexed up to slot {last_slot}");
+            }
+        }
+    }
+
+    Ok(())
+}
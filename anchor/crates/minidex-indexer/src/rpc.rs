@@ -0,0 +1,83 @@
+//! Minimal blocking JSON-RPC client covering the calls the indexer makes.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub struct RpcClient {
+    url: String,
+    commitment: String,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcClient {
+    /// `commitment` is `confirmed` or `finalized`, the levels `getBlock` accepts.
+    pub fn new(url: &str, commitment: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            commitment: commitment.to_string(),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: RpcResponse<T> = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} request to {} failed", self.url))?
+            .into_json()
+            .with_context(|| format!("invalid {method} response"))?;
+
+        if let Some(error) = response.error {
+            bail!("{method} failed ({}): {}", error.code, error.message);
+        }
+
+        response.result.ok_or_else(|| anyhow!("{method} returned no result"))
+    }
+
+    pub fn get_slot(&self) -> Result<u64> {
+        self.call("getSlot", json!([{ "commitment": self.commitment }]))
+    }
+
+    /// Slots between `start_slot` and `end_slot` inclusive that have a block.
+    pub fn get_blocks(&self, start_slot: u64, end_slot: u64) -> Result<Vec<u64>> {
+        self.call("getBlocks", json!([start_slot, end_slot, { "commitment": self.commitment }]))
+    }
+
+    /// The block at `slot` with full transactions in `json` encoding.
+    pub fn get_block(&self, slot: u64) -> Result<Value> {
+        self.call(
+            "getBlock",
+            json!([slot, {
+                "commitment": self.commitment,
+                "encoding": "json",
+                "transactionDetails": "full",
+                "maxSupportedTransactionVersion": 0,
+                "rewards": false,
+            }]),
+        )
+    }
+}
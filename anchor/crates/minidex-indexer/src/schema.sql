@@ -0,0 +1,108 @@
+-- Pool history decoded from minidex transactions.
+--
+-- Addresses and signatures are base58 TEXT. Token amounts are u64 values,
+-- which can exceed SQLite's signed 64-bit INTEGER, so they are stored as
+-- decimal TEXT. Parse them as u64; `CAST(amount AS INTEGER)` is only exact for
+-- amounts up to 9223372036854775807. Databases created while amounts were
+-- INTEGER columns have to be rebuilt.
+-- Event rows are keyed by the transaction signature and the position of the
+-- event among the transaction's minidex events, so indexing a block twice
+-- leaves the tables unchanged.
+
+-- One row per pool address, from its latest initialize_pool instruction.
+CREATE TABLE IF NOT EXISTS pools (
+    address TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    token_a_mint TEXT NOT NULL,
+    token_b_mint TEXT NOT NULL,
+    token_a_vault TEXT NOT NULL,
+    token_b_vault TEXT NOT NULL,
+    lp_mint TEXT NOT NULL,
+    -- Swap fee in basis points
+    fee_rate INTEGER NOT NULL,
+    -- Last status set through set_pool_status, 0 while active
+    status INTEGER NOT NULL DEFAULT 0,
+    created_slot INTEGER NOT NULL,
+    created_signature TEXT NOT NULL,
+    -- Slot of the close_pool instruction, NULL while the pool exists
+    closed_slot INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS swaps (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    -- Position of the transaction in its block. Transactions read from
+    -- separate files count in the order the files were given.
+    transaction_index INTEGER NOT NULL,
+    -- Unix timestamp of the block, when the source provides one
+    block_time INTEGER,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    -- 1 when token A was sold for token B
+    token_a_to_b INTEGER NOT NULL,
+    amount_in TEXT NOT NULL,
+    amount_out TEXT NOT NULL,
+    -- Swap fee taken from amount_in, in the input token
+    fee_amount TEXT NOT NULL,
+    -- Token account paid a share of the fee, the default key when none was
+    referrer TEXT NOT NULL,
+    -- Part of fee_amount paid to the referrer instead of the pool
+    referral_amount TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    block_time INTEGER,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    amount_a TEXT NOT NULL,
+    amount_b TEXT NOT NULL,
+    -- LP tokens minted to the user, without the minimum liquidity locked by the first deposit
+    lp_tokens TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    block_time INTEGER,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    lp_tokens TEXT NOT NULL,
+    amount_a TEXT NOT NULL,
+    amount_b TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- Pool state after every swap, deposit and withdrawal. A pool's latest row by
+-- (slot, transaction_index, event_index) holds its current reserves.
+CREATE TABLE IF NOT EXISTS reserve_snapshots (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    block_time INTEGER,
+    pool TEXT NOT NULL,
+    reserve_a TEXT NOT NULL,
+    reserve_b TEXT NOT NULL,
+    total_lp_supply TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- The last slot whose block has been fully indexed. Holds at most one row.
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    last_slot INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS swaps_by_pool ON swaps (pool, slot);
+CREATE INDEX IF NOT EXISTS deposits_by_pool ON deposits (pool, slot);
+CREATE INDEX IF NOT EXISTS withdrawals_by_pool ON withdrawals (pool, slot);
+CREATE INDEX IF NOT EXISTS reserve_snapshots_by_pool ON reserve_snapshots (pool, slot);
@@ -0,0 +1,290 @@
+//! SQLite storage of the decoded pool history. The schema is in `schema.sql`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use minidex_sdk::events::MinidexEvent;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::decode::{DecodedTransaction, PoolInstruction};
+
+pub const SCHEMA: &str = include_str!("schema.sql");
+
+/// Rows written for one block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub pools: usize,
+    pub swaps: usize,
+    pub deposits: usize,
+    pub withdrawals: usize,
+}
+
+impl BlockSummary {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Opens the database at `path`, creating it and the schema if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        Self::with_connection(connection)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA).context("failed to create the schema")?;
+        Ok(Self { connection })
+    }
+
+    /// Read access for queries and reports.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// The last fully indexed slot, `None` for a new database.
+    pub fn last_slot(&self) -> Result<Option<u64>> {
+        let last_slot = self
+            .connection
+            .query_row("SELECT last_slot FROM cursor WHERE id = 0", [], |row| row.get(0))
+            .optional()?;
+        Ok(last_slot)
+    }
+
+    /// Records that every block up to `slot` has been indexed. The cursor
+    /// never moves backwards.
+    pub fn advance_cursor(&mut self, slot: u64) -> Result<()> {
+        advance_cursor(&self.connection, slot)
+    }
+
+    /// Writes the decoded transactions of the block at `slot` and advances the
+    /// cursor to it, all in one database transaction.
+    pub fn insert_block(
+        &mut self,
+        slot: u64,
+        block_time: Option<i64>,
+        transactions: &[(usize, DecodedTransaction)],
+    ) -> Result<BlockSummary> {
+        let db = self.connection.transaction()?;
+        let mut summary = BlockSummary::default();
+
+        for (transaction_index, transaction) in transactions {
+            let position = Position {
+                signature: &transaction.signature,
+                slot,
+                transaction_index: *transaction_index,
+                block_time,
+            };
+
+            for instruction in &transaction.instructions {
+                summary.pools += insert_instruction(&db, &position, instruction)?;
+            }
+
+            for (event_index, event) in transaction.events.iter().enumerate() {
+                insert_event(&db, &position, event_index, event, &mut summary)?;
+            }
+        }
+
+        advance_cursor(&db, slot)?;
+        db.commit()?;
+
+        Ok(summary)
+    }
+}
+
+/// Token amounts are stored as decimal text, see `schema.sql`.
+fn amount(value: u64) -> String {
+    value.to_string()
+}
+
+#[derive(Clone, Copy)]
+struct Position<'a> {
+    signature: &'a str,
+    slot: u64,
+    transaction_index: usize,
+    block_time: Option<i64>,
+}
+
+fn advance_cursor(connection: &Connection, slot: u64) -> Result<()> {
+    connection.execute(
+        "INSERT INTO cursor (id, last_slot) VALUES (0, ?1)
+         ON CONFLICT (id) DO UPDATE SET last_slot = MAX(last_slot, excluded.last_slot)",
+        params![slot],
+    )?;
+    Ok(())
+}
+
+/// Returns the number of pools created.
+fn insert_instruction(db: &Transaction, position: &Position, instruction: &PoolInstruction) -> Result<usize> {
+    match instruction {
+        PoolInstruction::InitializePool {
+            pool,
+            authority,
+            token_a_mint,
+            token_b_mint,
+            token_a_vault,
+            token_b_vault,
+            lp_mint,
+            fee_rate,
+        } => {
+            let inserted = db.execute(
+                "INSERT INTO pools (
+                    address, authority, token_a_mint, token_b_mint, token_a_vault, token_b_vault, lp_mint, fee_rate,
+                    created_slot, created_signature
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                -- A closed pool's address is free for a new pool of the same mints
+                ON CONFLICT (address) DO UPDATE SET
+                    authority = excluded.authority, token_a_vault = excluded.token_a_vault,
+                    token_b_vault = excluded.token_b_vault, lp_mint = excluded.lp_mint, fee_rate = excluded.fee_rate,
+                    status = 0, created_slot = excluded.created_slot, created_signature = excluded.created_signature,
+                    closed_slot = NULL
+                WHERE pools.closed_slot < excluded.created_slot",
+                params![
+                    pool.to_string(),
+                    authority.to_string(),
+                    token_a_mint.to_string(),
+                    token_b_mint.to_string(),
+                    token_a_vault.to_string(),
+                    token_b_vault.to_string(),
+                    lp_mint.to_string(),
+                    fee_rate,
+                    position.slot,
+                    position.signature,
+                ],
+            )?;
+            Ok(inserted)
+        }
+        PoolInstruction::SetPoolStatus { pool, status } => {
+            db.execute(
+                "UPDATE pools SET status = ?2 WHERE address = ?1",
+                params![pool.to_string(), status],
+            )?;
+            Ok(0)
+        }
+        PoolInstruction::ClosePool { pool } => {
+            db.execute(
+                "UPDATE pools SET closed_slot = ?2 WHERE address = ?1",
+                params![pool.to_string(), position.slot],
+            )?;
+            Ok(0)
+        }
+    }
+}
+
+fn insert_event(
+    db: &Transaction,
+    position: &Position,
+    event_index: usize,
+    event: &MinidexEvent,
+    summary: &mut BlockSummary,
+) -> Result<()> {
+    let Position {
+        signature,
+        slot,
+        transaction_index,
+        block_time,
+    } = *position;
+
+    let (pool, reserve_a, reserve_b, total_lp_supply) = match event {
+        MinidexEvent::Swap(swap) => {
+            summary.swaps += db
+                .execute(
+                    "INSERT OR IGNORE INTO swaps (
+                        signature, event_index, slot, transaction_index, block_time,
+                        pool, user, token_a_to_b, amount_in, amount_out, fee_amount, referrer, referral_amount
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                    params![
+                        signature,
+                        event_index,
+                        slot,
+                        transaction_index,
+                        block_time,
+                        swap.pool.to_string(),
+                        swap.user.to_string(),
+                        swap.token_a_to_b,
+                        amount(swap.amount_in),
+                        amount(swap.amount_out),
+                        amount(swap.fee_amount),
+                        swap.referrer.to_string(),
+                        amount(swap.referral_amount),
+                    ],
+                )
+                .context("failed to store swap")?;
+            (swap.pool, swap.reserve_a, swap.reserve_b, swap.total_lp_supply)
+        }
+        MinidexEvent::AddLiquidity(deposit) => {
+            summary.deposits += db
+                .execute(
+                    "INSERT OR IGNORE INTO deposits (
+                        signature, event_index, slot, transaction_index, block_time,
+                        pool, user, amount_a, amount_b, lp_tokens
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        signature,
+                        event_index,
+                        slot,
+                        transaction_index,
+                        block_time,
+                        deposit.pool.to_string(),
+                        deposit.user.to_string(),
+                        amount(deposit.amount_a),
+                        amount(deposit.amount_b),
+                        amount(deposit.lp_tokens),
+                    ],
+                )
+                .context("failed to store deposit")?;
+            (deposit.pool, deposit.reserve_a, deposit.reserve_b, deposit.total_lp_supply)
+        }
+        MinidexEvent::RemoveLiquidity(withdrawal) => {
+            summary.withdrawals += db
+                .execute(
+                    "INSERT OR IGNORE INTO withdrawals (
+                        signature, event_index, slot, transaction_index, block_time,
+                        pool, user, lp_tokens, amount_a, amount_b
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        signature,
+                        event_index,
+                        slot,
+                        transaction_index,
+                        block_time,
+                        withdrawal.pool.to_string(),
+                        withdrawal.user.to_string(),
+                        amount(withdrawal.lp_tokens),
+                        amount(withdrawal.amount_a),
+                        amount(withdrawal.amount_b),
+                    ],
+                )
+                .context("failed to store withdrawal")?;
+            (withdrawal.pool, withdrawal.reserve_a, withdrawal.reserve_b, withdrawal.total_lp_supply)
+        }
+    };
+
+    db.execute(
+        "INSERT OR IGNORE INTO reserve_snapshots (
+            signature, event_index, slot, transaction_index, block_time, pool, reserve_a, reserve_b, total_lp_supply
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            signature,
+            event_index,
+            slot,
+            transaction_index,
+            block_time,
+            pool.to_string(),
+            amount(reserve_a),
+            amount(reserve_b),
+            amount(total_lp_supply),
+        ],
+    )
+    .context("failed to store reserve snapshot")?;
+
+    Ok(())
+}
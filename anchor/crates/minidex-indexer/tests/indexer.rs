@@ -0,0 +1,474 @@
+//! Indexes blocks built from transactions run on LiteSVM, in the `json`
+//! encoding a validator returns from `getBlock`.
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token::get_associated_token_address;
+use minidex_indexer::decode::{decode_transaction, Block, DecodedTransaction, EncodedTransaction, PoolInstruction};
+use minidex_indexer::store::{BlockSummary, Store};
+use minidex_indexer::{index_block, index_files};
+use minidex_sdk::constants::POOL_SWAPS_PAUSED;
+use minidex_sdk::events::{MinidexEvent, SwapEvent};
+use minidex_sdk::{instructions, Pool, PoolKeys};
+use minidex_svm::Svm;
+use serde_json::{json, Value};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Runs transactions on LiteSVM and records them as blocks.
+struct Chain {
+    svm: Svm,
+    blocks: Vec<(u64, Vec<Value>)>,
+    signatures: u64,
+}
+
+struct User {
+    key: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+}
+
+impl Chain {
+    fn new() -> Self {
+        Self {
+            svm: Svm::new(),
+            blocks: Vec::new(),
+            signatures: 0,
+        }
+    }
+
+    fn signature(&mut self) -> String {
+        self.signatures += 1;
+        bs58::encode([self.signatures as u8; 64]).into_string()
+    }
+
+    fn user(&mut self, keys: &PoolKeys, amount_a: u64, amount_b: u64) -> User {
+        let key = Pubkey::new_unique();
+        self.svm.airdrop(&key, LAMPORTS_PER_SOL);
+
+        User {
+            key,
+            token_a: self.svm.create_associated_token_account(&keys.token_a_mint, &key, amount_a),
+            token_b: self.svm.create_associated_token_account(&keys.token_b_mint, &key, amount_b),
+        }
+    }
+
+    /// Executes the transaction and appends it to the block at `slot`.
+    fn send(&mut self, slot: u64, instructions: &[Instruction], signer: &Pubkey) {
+        self.svm.warp_to_slot(slot);
+        let metadata = self.svm.process_transaction(instructions, &[*signer]).expect("transaction succeeds");
+        let transaction = transaction_json(&self.signature(), instructions, &metadata.logs, Value::Null);
+
+        self.push(slot, transaction);
+    }
+
+    /// Appends a transaction that ran like `instructions` would but failed.
+    fn send_failed(&mut self, slot: u64, instructions: &[Instruction], signer: &Pubkey) {
+        let metadata = self.svm.simulate_transaction(instructions, &[*signer]).expect("simulation succeeds");
+        let error = json!({ "InstructionError": [0, { "Custom": 6000 }] });
+        let transaction = transaction_json(&self.signature(), instructions, &metadata.logs, error);
+
+        self.push(slot, transaction);
+    }
+
+    fn push(&mut self, slot: u64, transaction: Value) {
+        match self.blocks.last_mut() {
+            Some((last, transactions)) if *last == slot => transactions.push(transaction),
+            _ => self.blocks.push((slot, vec![transaction])),
+        }
+    }
+
+    /// `getBlock` results, which do not contain their slot.
+    fn block_documents(&self) -> Vec<(u64, Value)> {
+        self.blocks
+            .iter()
+            .map(|(slot, transactions)| {
+                let document = json!({
+                    "blockHeight": slot,
+                    "blockTime": 1_700_000_000 + *slot as i64,
+                    "blockhash": Pubkey::new_from_array([*slot as u8; 32]).to_string(),
+                    "parentSlot": slot - 1,
+                    "previousBlockhash": Pubkey::new_from_array([*slot as u8 - 1; 32]).to_string(),
+                    "transactions": transactions,
+                });
+                (*slot, document)
+            })
+            .collect()
+    }
+
+    fn blocks(&self) -> Vec<Block> {
+        self.block_documents()
+            .into_iter()
+            .map(|(slot, document)| Block::from_json(document, Some(slot)).unwrap())
+            .collect()
+    }
+}
+
+fn transaction_json(signature: &str, instructions: &[Instruction], logs: &[String], err: Value) -> Value {
+    let mut account_keys: Vec<Pubkey> = Vec::new();
+    let mut index_of = |key: Pubkey| match account_keys.iter().position(|known| *known == key) {
+        Some(index) => index,
+        None => {
+            account_keys.push(key);
+            account_keys.len() - 1
+        }
+    };
+
+    let instructions: Vec<Value> = instructions
+        .iter()
+        .map(|instruction| {
+            json!({
+                "programIdIndex": index_of(instruction.program_id),
+                "accounts": instruction.accounts.iter().map(|meta| index_of(meta.pubkey)).collect::<Vec<_>>(),
+                "data": bs58::encode(&instruction.data).into_string(),
+                "stackHeight": null,
+            })
+        })
+        .collect();
+
+    json!({
+        "meta": {
+            "err": err,
+            "fee": 5000,
+            "innerInstructions": [],
+            "loadedAddresses": { "readonly": [], "writable": [] },
+            "logMessages": logs,
+        },
+        "transaction": {
+            "message": {
+                "accountKeys": account_keys.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+                "instructions": instructions,
+                "recentBlockhash": Pubkey::default().to_string(),
+            },
+            "signatures": [signature],
+        },
+        "version": "legacy",
+    })
+}
+
+/// A pool created at slot 10, seeded at 11, traded at 12, partly withdrawn at
+/// 13 and paused at 14.
+fn pool_history() -> (Chain, PoolKeys) {
+    let mut chain = Chain::new();
+    let authority = Pubkey::new_unique();
+    chain.svm.airdrop(&authority, 10 * LAMPORTS_PER_SOL);
+    let mint_a = chain.svm.create_mint(&authority, 6);
+    let mint_b = chain.svm.create_mint(&authority, 6);
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+    chain.send(10, &instructions::create_pool(&authority, &keys, 30), &authority);
+
+    let provider = chain.user(&keys, 1_000_000, 4_000_000);
+    chain.send(11, &[add_liquidity(&provider, &keys, 1_000_000, 4_000_000)], &provider.key);
+
+    let seller = chain.user(&keys, 10_000, 0);
+    let buyer = chain.user(&keys, 0, 40_000);
+    chain.send(12, &[swap(&seller, &keys, 10_000, true)], &seller.key);
+    chain.send_failed(12, &[swap(&buyer, &keys, 40_000, false)], &buyer.key);
+    chain.send(12, &[swap(&buyer, &keys, 40_000, false)], &buyer.key);
+
+    let lp_tokens = chain.svm.token_balance(&get_associated_token_address(&provider.key, &keys.lp_mint)) / 4;
+    let withdrawal = instructions::remove_liquidity(&provider.key, &keys, &provider.token_a, &provider.token_b, lp_tokens, 0, 0, None);
+    chain.send(13, &[withdrawal], &provider.key);
+
+    let pause = instructions::set_pool_status(&authority, &keys.pool, POOL_SWAPS_PAUSED);
+    chain.send(14, &[pause], &authority);
+
+    (chain, keys)
+}
+
+fn add_liquidity(user: &User, keys: &PoolKeys, amount_a: u64, amount_b: u64) -> Instruction {
+    instructions::add_liquidity(&user.key, keys, &user.token_a, &user.token_b, amount_a, amount_b, 0, None)
+}
+
+fn swap(user: &User, keys: &PoolKeys, amount_in: u64, token_a_to_b: bool) -> Instruction {
+    instructions::swap_tokens(&user.key, keys, &user.token_a, &user.token_b, amount_in, 0, token_a_to_b, None)
+}
+
+fn count(store: &Store, table: &str) -> u64 {
+    store
+        .connection()
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn indexes_the_pool_history() {
+    let (chain, keys) = pool_history();
+    let mut store = Store::open_in_memory().unwrap();
+
+    for block in chain.blocks() {
+        index_block(&mut store, &block).unwrap();
+    }
+
+    let pool: Pool = chain.svm.anchor_account(&keys.pool).unwrap();
+    let (address, lp_mint, fee_rate, status, created_slot): (String, String, u16, u8, u64) = store
+        .connection()
+        .query_row("SELECT address, lp_mint, fee_rate, status, created_slot FROM pools", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })
+        .unwrap();
+    assert_eq!(address, keys.pool.to_string());
+    assert_eq!(lp_mint, keys.lp_mint.to_string());
+    assert_eq!((fee_rate, status, created_slot), (30, POOL_SWAPS_PAUSED, 10));
+
+    // The failed swap in slot 12 is left out
+    assert_eq!(count(&store, "swaps"), 2);
+    assert_eq!(count(&store, "deposits"), 1);
+    assert_eq!(count(&store, "withdrawals"), 1);
+    assert_eq!(count(&store, "reserve_snapshots"), 4);
+
+    let swaps: Vec<(u64, u64, bool, String)> = store
+        .connection()
+        .prepare("SELECT slot, transaction_index, token_a_to_b, amount_in FROM swaps ORDER BY slot, transaction_index")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(swaps, vec![(12, 0, true, "10000".into()), (12, 2, false, "40000".into())]);
+
+    let latest: (String, String, String, i64) = store
+        .connection()
+        .query_row(
+            "SELECT reserve_a, reserve_b, total_lp_supply, block_time FROM reserve_snapshots
+             WHERE pool = ?1 ORDER BY slot DESC, transaction_index DESC, event_index DESC LIMIT 1",
+            [keys.pool.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        latest,
+        (pool.reserve_a.to_string(), pool.reserve_b.to_string(), pool.total_lp_supply.to_string(), 1_700_000_013)
+    );
+
+    assert_eq!(store.last_slot().unwrap(), Some(14));
+}
+
+#[test]
+fn stores_amounts_beyond_the_signed_integer_range() {
+    let mut store = Store::open_in_memory().unwrap();
+    let swap = SwapEvent {
+        pool: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        token_a_to_b: true,
+        amount_in: u64::MAX,
+        amount_out: u64::MAX - 1,
+        fee_amount: i64::MAX as u64 + 1,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: u64::MAX,
+        reserve_b: 1,
+        total_lp_supply: u64::MAX,
+    };
+    let transaction = DecodedTransaction {
+        signature: "large".to_string(),
+        instructions: Vec::new(),
+        events: vec![MinidexEvent::Swap(swap.clone())],
+    };
+
+    store.insert_block(20, None, &[(0, transaction)]).unwrap();
+
+    let amounts: (String, String, String) = store
+        .connection()
+        .query_row("SELECT amount_in, amount_out, fee_amount FROM swaps", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap();
+    let parsed = (amounts.0.parse::<u64>(), amounts.1.parse::<u64>(), amounts.2.parse::<u64>());
+    assert_eq!(parsed, (Ok(swap.amount_in), Ok(swap.amount_out), Ok(swap.fee_amount)));
+
+    let reserve_a: String = store
+        .connection()
+        .query_row("SELECT reserve_a FROM reserve_snapshots", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(reserve_a.parse::<u64>(), Ok(u64::MAX));
+    assert_eq!(store.last_slot().unwrap(), Some(20));
+}
+
+#[test]
+fn indexing_a_block_again_changes_nothing() {
+    let (chain, _) = pool_history();
+    let mut store = Store::open_in_memory().unwrap();
+    let blocks = chain.blocks();
+
+    for block in &blocks {
+        index_block(&mut store, block).unwrap();
+    }
+    let summary = index_block(&mut store, &blocks[2]).unwrap();
+
+    assert_eq!(summary, BlockSummary::default());
+    assert_eq!(count(&store, "swaps"), 2);
+    assert_eq!(count(&store, "reserve_snapshots"), 4);
+    assert_eq!(store.last_slot().unwrap(), Some(14));
+}
+
+#[test]
+fn resumes_from_the_last_indexed_slot() {
+    let (chain, _) = pool_history();
+    let directory = tempfile::tempdir().unwrap();
+    let database = directory.path().join("index.sqlite");
+
+    let paths: Vec<PathBuf> = chain
+        .block_documents()
+        .into_iter()
+        .map(|(slot, document)| {
+            // Saved the way `curl` would, still wrapped in the JSON-RPC response
+            let path = directory.path().join(format!("{slot}.json"));
+            let response = json!({ "jsonrpc": "2.0", "result": document, "id": 1 });
+            std::fs::write(&path, response.to_string()).unwrap();
+            path
+        })
+        .collect();
+
+    let mut store = Store::open(&database).unwrap();
+    let indexed = index_files(&mut store, &paths[..2]).unwrap();
+    assert_eq!(indexed.iter().map(|(slot, _)| *slot).collect::<Vec<_>>(), vec![10, 11]);
+    drop(store);
+
+    // A restart only picks up the slots after the cursor
+    let mut store = Store::open(&database).unwrap();
+    let indexed = index_files(&mut store, &paths).unwrap();
+
+    assert_eq!(
+        indexed,
+        vec![
+            (12, BlockSummary { swaps: 2, ..BlockSummary::default() }),
+            (13, BlockSummary { withdrawals: 1, ..BlockSummary::default() }),
+            (14, BlockSummary::default()),
+        ]
+    );
+    assert_eq!(count(&store, "pools"), 1);
+    assert_eq!(count(&store, "deposits"), 1);
+    assert_eq!(store.last_slot().unwrap(), Some(14));
+}
+
+#[test]
+fn records_closed_pools_until_they_are_created_again() {
+    let mut chain = Chain::new();
+    let authority = Pubkey::new_unique();
+    chain.svm.airdrop(&authority, 10 * LAMPORTS_PER_SOL);
+    let mint_a = chain.svm.create_mint(&authority, 6);
+    let mint_b = chain.svm.create_mint(&authority, 6);
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+    let authority_a = chain.svm.create_associated_token_account(&mint_a, &authority, 0);
+    let authority_b = chain.svm.create_associated_token_account(&mint_b, &authority, 0);
+
+    chain.send(10, &instructions::create_pool(&authority, &keys, 30), &authority);
+    chain.send(11, &[instructions::close_pool(&authority, &keys, &authority_a, &authority_b)], &authority);
+    // The LP mint outlives the pool
+    let recreate = [
+        instructions::initialize_pool(&authority, &keys, 100),
+        instructions::initialize_vault_a(&authority, &keys),
+        instructions::initialize_vault_b(&authority, &keys),
+    ];
+    chain.send(12, &recreate, &authority);
+
+    let pool_row = |store: &Store| -> (u16, u64, Option<u64>) {
+        store
+            .connection()
+            .query_row("SELECT fee_rate, created_slot, closed_slot FROM pools", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap()
+    };
+
+    let mut store = Store::open_in_memory().unwrap();
+    let blocks = chain.blocks();
+    index_block(&mut store, &blocks[0]).unwrap();
+    index_block(&mut store, &blocks[1]).unwrap();
+    assert_eq!(pool_row(&store), (30, 10, Some(11)));
+
+    // Re-indexing the creation does not reopen the closed pool
+    index_block(&mut store, &blocks[0]).unwrap();
+    assert_eq!(pool_row(&store), (30, 10, Some(11)));
+
+    let summary = index_block(&mut store, &blocks[2]).unwrap();
+    assert_eq!(summary.pools, 1);
+    assert_eq!(pool_row(&store), (100, 12, None));
+    assert_eq!(count(&store, "pools"), 1);
+}
+
+#[test]
+fn reads_get_transaction_results() {
+    let (chain, _) = pool_history();
+    let (_, transactions) = &chain.blocks[2];
+
+    let mut document = transactions[0].clone();
+    document["slot"] = json!(12);
+    document["blockTime"] = json!(1_700_000_012);
+    let block = Block::from_json(document, None).unwrap();
+
+    let mut store = Store::open_in_memory().unwrap();
+    let summary = index_block(&mut store, &block).unwrap();
+
+    assert_eq!(summary, BlockSummary { swaps: 1, ..BlockSummary::default() });
+    assert_eq!(store.last_slot().unwrap(), Some(12));
+
+    // Block files need their slot from the file name
+    let (_, block_document) = chain.block_documents().remove(0);
+    assert!(Block::from_json(block_document, None).is_err());
+}
+
+#[test]
+fn decodes_pool_creation_invoked_through_another_program() {
+    let authority = Pubkey::new_unique();
+    let keys = PoolKeys::derive(&Pubkey::new_unique(), &Pubkey::new_unique());
+    let router = Pubkey::new_unique();
+    let data = minidex::instruction::InitializePool {
+        fee_rate: 100,
+        token_a_vault: keys.token_a_vault,
+        token_b_vault: keys.token_b_vault,
+        lp_mint: keys.lp_mint,
+    }
+    .data();
+
+    // The pool and mints come from an address lookup table
+    let document = json!({
+        "slot": 7,
+        "meta": {
+            "err": null,
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{
+                    "programIdIndex": 2,
+                    "accounts": [0, 4, 5, 3, 6],
+                    "data": bs58::encode(data).into_string(),
+                    "stackHeight": 2,
+                }],
+            }],
+            "loadedAddresses": {
+                "writable": [keys.pool.to_string()],
+                "readonly": [keys.token_a_mint.to_string(), keys.token_b_mint.to_string(), Pubkey::default().to_string()],
+            },
+            "logMessages": [],
+        },
+        "transaction": {
+            "message": {
+                "accountKeys": [authority.to_string(), router.to_string(), minidex::ID.to_string()],
+                "instructions": [{ "programIdIndex": 1, "accounts": [0, 2], "data": "", "stackHeight": null }],
+            },
+            "signatures": ["1111111111111111111111111111111111111111111111111111111111111111"],
+        },
+    });
+    let transaction: EncodedTransaction = serde_json::from_value(document).unwrap();
+
+    let decoded = decode_transaction(&transaction).unwrap().unwrap();
+
+    assert_eq!(
+        decoded.instructions,
+        vec![PoolInstruction::InitializePool {
+            pool: keys.pool,
+            authority,
+            token_a_mint: keys.token_a_mint,
+            token_b_mint: keys.token_b_mint,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            fee_rate: 100,
+        }]
+    );
+}
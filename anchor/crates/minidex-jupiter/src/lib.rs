@@ -0,0 +1,173 @@
+//! [`Amm`] implementation that lets the Jupiter aggregator route through
+//! minidex pools.
+//!
+//! Quotes come from the program's own `calculate_swap_quote`, so they apply
+//! the same fee, rounding, pause flags and price limits as `swap_tokens`.
+//! Swaps go back to the aggregator as `Swap::TokenSwap` with the
+//! `swap_tokens` account metas, as the interface has no minidex variant.
+
+use std::sync::atomic::Ordering;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anyhow::{anyhow, bail, ensure, Result};
+use jupiter_amm_interface::{
+    try_get_account_data, AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, SingleProgramAmm,
+    Swap, SwapAndAccountMetas, SwapMode, SwapParams,
+};
+use minidex_sdk::math::{calculate_swap_quote, decay_volatility};
+use minidex_sdk::{deserialize_pool, instructions, Pool, PoolKeys, PROGRAM_ID};
+use rust_decimal::Decimal;
+
+pub const LABEL: &str = "Minidex";
+
+/// A minidex pool as seen by the aggregator. All swap state lives in the
+/// `Pool` account, so it is the only account that needs refreshing.
+#[derive(Clone)]
+pub struct MinidexAmm {
+    keys: PoolKeys,
+    pool: Pool,
+    clock_ref: ClockRef,
+}
+
+impl MinidexAmm {
+    pub fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    /// Accounts for a `swap_tokens` instruction filling `swap_params`,
+    /// preceded by the program so the aggregator knows where to invoke.
+    pub fn swap_account_metas(&self, swap_params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        let token_a_to_b = self.token_a_to_b(&swap_params.source_mint, &swap_params.destination_mint)?;
+        let (user_token_a_account, user_token_b_account) = if token_a_to_b {
+            (swap_params.source_token_account, swap_params.destination_token_account)
+        } else {
+            (swap_params.destination_token_account, swap_params.source_token_account)
+        };
+
+        let instruction = instructions::swap_tokens(
+            &swap_params.token_transfer_authority,
+            &self.keys,
+            &user_token_a_account,
+            &user_token_b_account,
+            swap_params.in_amount,
+            swap_params.out_amount,
+            token_a_to_b,
+            // Pools with the allowlist enabled are never active for routing
+            None,
+        );
+
+        let mut account_metas = vec![AccountMeta::new_readonly(PROGRAM_ID, false)];
+        account_metas.extend(instruction.accounts);
+        Ok(account_metas)
+    }
+
+    /// Swap direction for a mint pair, failing for mints the pool does not trade.
+    fn token_a_to_b(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Result<bool> {
+        let (mint_a, mint_b) = (self.keys.token_a_mint, self.keys.token_b_mint);
+
+        match (*input_mint, *output_mint) {
+            (input, output) if input == mint_a && output == mint_b => Ok(true),
+            (input, output) if input == mint_b && output == mint_a => Ok(false),
+            (input, output) => bail!("pool {} does not trade {input} for {output}", self.keys.pool),
+        }
+    }
+}
+
+impl SingleProgramAmm for MinidexAmm {
+    const PROGRAM_ID: Pubkey = PROGRAM_ID;
+    const LABEL: &'static str = LABEL;
+}
+
+impl Amm for MinidexAmm {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self> {
+        ensure!(
+            keyed_account.account.owner == PROGRAM_ID,
+            "account {} is not owned by minidex",
+            keyed_account.key
+        );
+
+        let pool = deserialize_pool(&keyed_account.account.data).map_err(|error| anyhow!("decoding pool: {error}"))?;
+
+        Ok(Self {
+            keys: PoolKeys::from_pool(&keyed_account.key, &pool),
+            pool,
+            clock_ref: amm_context.clock_ref.clone(),
+        })
+    }
+
+    fn label(&self) -> String {
+        LABEL.to_string()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        PROGRAM_ID
+    }
+
+    fn key(&self) -> Pubkey {
+        self.keys.pool
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.keys.token_a_mint, self.keys.token_b_mint]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        vec![self.keys.pool]
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let data = try_get_account_data(account_map, &self.keys.pool)?;
+        self.pool = deserialize_pool(data).map_err(|error| anyhow!("decoding pool: {error}"))?;
+        Ok(())
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        ensure!(quote_params.swap_mode == SwapMode::ExactIn, "minidex only supports exact-in swaps");
+
+        let token_a_to_b = self.token_a_to_b(&quote_params.input_mint, &quote_params.output_mint)?;
+        let slot = self.clock_ref.slot.load(Ordering::Relaxed);
+        let quote = calculate_swap_quote(&self.pool, quote_params.amount, token_a_to_b, slot)
+            .map_err(|error| anyhow!("quoting swap: {error}"))?;
+
+        let mut pool = self.pool.clone();
+        decay_volatility(&mut pool, slot);
+
+        Ok(Quote {
+            in_amount: quote_params.amount,
+            out_amount: quote.amount_out,
+            fee_amount: quote.fee_amount,
+            // The fee is taken from the input before pricing
+            fee_mint: quote_params.input_mint,
+            // The fee rate is in basis points
+            fee_pct: Decimal::new(pool.effective_fee_rate().into(), 4),
+        })
+    }
+
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        // jupiter-amm-interface has no variant for minidex yet, so the swap is
+        // tagged with the generic constant-product one and the metas carry the
+        // `swap_tokens` layout
+        Ok(SwapAndAccountMetas {
+            swap: Swap::TokenSwap,
+            account_metas: self.swap_account_metas(swap_params)?,
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn get_accounts_len(&self) -> usize {
+        // Program id plus the `swap_tokens` accounts, the unused referrer, fee
+        // discount and permission accounts included
+        13
+    }
+
+    fn is_active(&self) -> bool {
+        // Permissioned pools reject swaps from anyone without a permission
+        !self.pool.swaps_paused()
+            && !self.pool.allowlist_enabled
+            && self.pool.reserve_a > 0
+            && self.pool.reserve_b > 0
+    }
+}
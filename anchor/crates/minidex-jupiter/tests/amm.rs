@@ -0,0 +1,263 @@
+//! Offline tests against `Pool` account data for a SOL/USDC pool.
+
+use anchor_lang::prelude::{pubkey, AccountMeta, Pubkey};
+use anchor_lang::AccountSerialize;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, Swap, SwapMode, SwapParams,
+};
+use minidex_jupiter::MinidexAmm;
+use minidex_sdk::constants::POOL_SWAPS_PAUSED;
+use minidex_sdk::math::{calculate_swap_fee, calculate_swap_output};
+use minidex_sdk::test_utils::PoolTestDefault;
+use minidex_sdk::{deserialize_pool, Pool, PROGRAM_ID};
+use rust_decimal::Decimal;
+use solana_account::Account;
+
+const POOL_ADDRESS: Pubkey = pubkey!("AcSiuCCdPe1p9ipEzDDWbX8LWBJ5b2cbpN8gX8t8iH7f");
+
+/// 1,000 SOL against 150,000 USDC at a 30 bps fee.
+fn fixture() -> KeyedAccount {
+    let pool = Pool {
+        authority: pubkey!("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"),
+        token_a_mint: pubkey!("So11111111111111111111111111111111111111112"),
+        token_b_mint: pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+        token_a_vault: pubkey!("9tit7viyM2PwGhJmGrzejaQEU8JrSQXdVU6WyBcwz8VP"),
+        token_b_vault: pubkey!("7vbHcFezd1gmTMwymaLwEpNf39eWrg2zQ2ac94fDKEqv"),
+        lp_mint: pubkey!("6GxgxXPFcnodqSbQBqo9793XKfgPa7nERMNXkVeoV263"),
+        reserve_a: 1_000_000_000_000,
+        reserve_b: 150_000_000_000,
+        total_lp_supply: 387_298_334_620,
+        ..Pool::test_default()
+    };
+
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+
+    KeyedAccount {
+        key: POOL_ADDRESS,
+        account: Account {
+            lamports: 3_055_440,
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        },
+        params: None,
+    }
+}
+
+fn load() -> MinidexAmm {
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    MinidexAmm::from_keyed_account(&fixture(), &context).unwrap()
+}
+
+/// Feeds `pool` back through `update` as the refreshed pool account.
+fn update(amm: &mut MinidexAmm, pool: &Pool) {
+    let mut account = fixture().account;
+    account.data.clear();
+    pool.try_serialize(&mut account.data).unwrap();
+
+    let mut account_map = AccountMap::default();
+    account_map.insert(amm.key(), account);
+    amm.update(&account_map).unwrap();
+}
+
+fn quote_params(amm: &MinidexAmm, amount: u64, token_a_to_b: bool) -> QuoteParams {
+    let (input_mint, output_mint) = if token_a_to_b {
+        (amm.pool().token_a_mint, amm.pool().token_b_mint)
+    } else {
+        (amm.pool().token_b_mint, amm.pool().token_a_mint)
+    };
+
+    QuoteParams {
+        amount,
+        input_mint,
+        output_mint,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+fn swap_params<'a>(
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+    source_token_account: Pubkey,
+    destination_token_account: Pubkey,
+    user: Pubkey,
+    jupiter_program_id: &'a Pubkey,
+) -> SwapParams<'a, 'a> {
+    SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: 1_000,
+        out_amount: 1,
+        source_mint,
+        destination_mint,
+        source_token_account,
+        destination_token_account,
+        token_transfer_authority: user,
+        quote_mint_to_referrer: None,
+        jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    }
+}
+
+#[test]
+fn loads_the_pool_from_its_account() {
+    let fixture = fixture();
+    let amm = load();
+    let pool = deserialize_pool(&fixture.account.data).unwrap();
+
+    assert_eq!(amm.key(), fixture.key);
+    assert_eq!(amm.program_id(), PROGRAM_ID);
+    assert_eq!(amm.label(), "Minidex");
+    assert_eq!(amm.get_reserve_mints(), vec![pool.token_a_mint, pool.token_b_mint]);
+    assert_eq!(amm.get_accounts_to_update(), vec![fixture.key]);
+    assert!(amm.is_active());
+}
+
+#[test]
+fn rejects_accounts_of_other_programs() {
+    let mut fixture = fixture();
+    fixture.account.owner = Pubkey::new_unique();
+
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    assert!(MinidexAmm::from_keyed_account(&fixture, &context).is_err());
+}
+
+#[test]
+fn quotes_with_the_program_math_in_both_directions() {
+    let amm = load();
+
+    for token_a_to_b in [true, false] {
+        let params = quote_params(&amm, 1_000_000_000, token_a_to_b);
+        let quote = amm.quote(&params).unwrap();
+
+        assert_eq!(quote.in_amount, 1_000_000_000);
+        assert_eq!(
+            quote.out_amount,
+            calculate_swap_output(amm.pool(), 1_000_000_000, token_a_to_b).unwrap()
+        );
+        assert_eq!(quote.fee_amount, calculate_swap_fee(amm.pool(), 1_000_000_000).unwrap());
+        assert_eq!(quote.fee_mint, params.input_mint);
+        assert_eq!(quote.fee_pct, Decimal::new(3, 3));
+    }
+
+    // 1 SOL buys a little under 150 USDC once the fee and price impact are paid
+    let quote = amm.quote(&quote_params(&amm, 1_000_000_000, true)).unwrap();
+    assert_eq!(quote.out_amount, 149_401_047);
+}
+
+#[test]
+fn rejects_unsupported_quotes() {
+    let amm = load();
+
+    let mut params = quote_params(&amm, 1_000, true);
+    params.output_mint = Pubkey::new_unique();
+    assert!(amm.quote(&params).is_err());
+
+    let mut params = quote_params(&amm, 1_000, true);
+    params.swap_mode = SwapMode::ExactOut;
+    assert!(amm.quote(&params).is_err());
+
+    assert!(amm.quote(&quote_params(&amm, 0, true)).is_err());
+}
+
+#[test]
+fn update_refreshes_reserves_and_status() {
+    let mut amm = load();
+    let before = amm.quote(&quote_params(&amm, 1_000_000_000, true)).unwrap();
+
+    let mut pool = amm.pool().clone();
+    pool.reserve_b *= 2;
+    update(&mut amm, &pool);
+
+    let after = amm.quote(&quote_params(&amm, 1_000_000_000, true)).unwrap();
+    assert!(after.out_amount > before.out_amount);
+
+    pool.status = POOL_SWAPS_PAUSED;
+    update(&mut amm, &pool);
+
+    assert!(!amm.is_active());
+    assert!(amm.quote(&quote_params(&amm, 1_000_000_000, true)).is_err());
+}
+
+#[test]
+fn permissioned_pools_are_inactive() {
+    let mut amm = load();
+
+    let mut pool = amm.pool().clone();
+    pool.allowlist_enabled = true;
+    update(&mut amm, &pool);
+
+    assert!(!amm.is_active());
+}
+
+#[test]
+fn quotes_respect_the_pool_price_limits() {
+    let mut amm = load();
+
+    let mut pool = amm.pool().clone();
+    pool.max_price_impact_bps = 100;
+    update(&mut amm, &pool);
+
+    // About 0.2% impact passes, about 9% does not
+    assert!(amm.quote(&quote_params(&amm, 1_000_000_000, true)).is_ok());
+    assert!(amm.quote(&quote_params(&amm, 50_000_000_000, true)).is_err());
+}
+
+#[test]
+fn builds_swap_tokens_accounts_for_either_direction() {
+    let amm = load();
+    let pool = amm.pool().clone();
+    let jupiter = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let (user_a, user_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let expected = vec![
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new(user, true),
+        AccountMeta::new(amm.key(), false),
+        AccountMeta::new(user_a, false),
+        AccountMeta::new(user_b, false),
+        AccountMeta::new(pool.token_a_vault, false),
+        AccountMeta::new(pool.token_b_vault, false),
+        // No referrer, fee discount or allowlist permission
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new_readonly(PROGRAM_ID, false),
+        AccountMeta::new_readonly(anchor_spl::token::ID, false),
+    ];
+
+    let sell_a = swap_params(pool.token_a_mint, pool.token_b_mint, user_a, user_b, user, &jupiter);
+    let sell_b = swap_params(pool.token_b_mint, pool.token_a_mint, user_b, user_a, user, &jupiter);
+
+    for params in [sell_a, sell_b] {
+        let account_metas = amm.swap_account_metas(&params).unwrap();
+
+        assert_eq!(account_metas, expected);
+        assert_eq!(account_metas.len(), amm.get_accounts_len());
+    }
+
+    let unknown = swap_params(Pubkey::new_unique(), pool.token_b_mint, user_a, user_b, user, &jupiter);
+    assert!(amm.swap_account_metas(&unknown).is_err());
+}
+
+#[test]
+fn routes_through_the_swap_tokens_accounts() {
+    let amm = load();
+    let pool = amm.pool().clone();
+    let jupiter = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let params = swap_params(pool.token_a_mint, pool.token_b_mint, user, user, user, &jupiter);
+
+    let swap = amm.get_swap_and_account_metas(&params).unwrap();
+
+    assert_eq!(swap.swap, Swap::TokenSwap);
+    assert_eq!(swap.account_metas, amm.swap_account_metas(&params).unwrap());
+    assert_eq!(swap.account_metas.len(), amm.get_accounts_len());
+}
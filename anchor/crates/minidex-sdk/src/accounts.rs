@@ -0,0 +1,63 @@
+use anchor_lang::{AccountDeserialize, Result};
+
+use crate::{Config, Dca, Farm, FarmPosition, LimitOrder, Pool, PoolPermission, RegisteredToken, TokenRegistry, UserStake};
+
+/// Decodes raw `Pool` account data, checking the Anchor discriminator.
+pub fn deserialize_pool(data: &[u8]) -> Result<Pool> {
+    let mut data = data;
+    Pool::try_deserialize(&mut data)
+}
+
+/// Decodes raw `Farm` account data, checking the Anchor discriminator.
+pub fn deserialize_farm(data: &[u8]) -> Result<Farm> {
+    let mut data = data;
+    Farm::try_deserialize(&mut data)
+}
+
+/// Decodes raw `FarmPosition` account data, checking the Anchor discriminator.
+pub fn deserialize_farm_position(data: &[u8]) -> Result<FarmPosition> {
+    let mut data = data;
+    FarmPosition::try_deserialize(&mut data)
+}
+
+/// Decodes raw `LimitOrder` account data, checking the Anchor discriminator.
+pub fn deserialize_limit_order(data: &[u8]) -> Result<LimitOrder> {
+    let mut data = data;
+    LimitOrder::try_deserialize(&mut data)
+}
+
+/// Decodes raw `Dca` account data, checking the Anchor discriminator.
+pub fn deserialize_dca(data: &[u8]) -> Result<Dca> {
+    let mut data = data;
+    Dca::try_deserialize(&mut data)
+}
+
+/// Decodes raw `Config` account data, checking the Anchor discriminator.
+pub fn deserialize_config(data: &[u8]) -> Result<Config> {
+    let mut data = data;
+    Config::try_deserialize(&mut data)
+}
+
+/// Decodes raw `UserStake` account data, checking the Anchor discriminator.
+pub fn deserialize_user_stake(data: &[u8]) -> Result<UserStake> {
+    let mut data = data;
+    UserStake::try_deserialize(&mut data)
+}
+
+/// Decodes raw `PoolPermission` account data, checking the Anchor discriminator.
+pub fn deserialize_pool_permission(data: &[u8]) -> Result<PoolPermission> {
+    let mut data = data;
+    PoolPermission::try_deserialize(&mut data)
+}
+
+/// Decodes raw `TokenRegistry` account data, checking the Anchor discriminator.
+pub fn deserialize_token_registry(data: &[u8]) -> Result<TokenRegistry> {
+    let mut data = data;
+    TokenRegistry::try_deserialize(&mut data)
+}
+
+/// Decodes raw `RegisteredToken` account data, checking the Anchor discriminator.
+pub fn deserialize_registered_token(data: &[u8]) -> Result<RegisteredToken> {
+    let mut data = data;
+    RegisteredToken::try_deserialize(&mut data)
+}
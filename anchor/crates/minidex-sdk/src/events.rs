@@ -0,0 +1,68 @@
+//! Decoding of the events the program emits into `Program data:` log lines.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+pub use minidex::events::{AddLiquidityEvent, RemoveLiquidityEvent, SwapEvent};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinidexEvent {
+    Swap(SwapEvent),
+    AddLiquidity(AddLiquidityEvent),
+    RemoveLiquidity(RemoveLiquidityEvent),
+}
+
+/// Decodes one event from its discriminator-prefixed bytes. Returns `None`
+/// for data that is not a known minidex event.
+pub fn decode_event(data: &[u8]) -> Option<MinidexEvent> {
+    fn decode<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+        let mut payload = data.strip_prefix(T::DISCRIMINATOR)?;
+        T::deserialize(&mut payload).ok()
+    }
+
+    decode(data)
+        .map(MinidexEvent::Swap)
+        .or_else(|| decode(data).map(MinidexEvent::AddLiquidity))
+        .or_else(|| decode(data).map(MinidexEvent::RemoveLiquidity))
+}
+
+/// Decodes the events in a transaction's log messages, in emission order.
+///
+/// Only `Program data:` lines logged while minidex is the running program
+/// count, so another program cannot forge events by logging the same bytes.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<MinidexEvent> {
+    let program_id = minidex::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+
+            // Each field of `sol_log_data` is encoded separately, `emit!` logs one
+            let event = data
+                .split(' ')
+                .map(|field| BASE64.decode(field).ok())
+                .collect::<Option<Vec<_>>>()
+                .and_then(|fields| decode_event(&fields.concat()));
+            events.extend(event);
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => stack.push(program),
+                (Some(program), Some("success" | "failed:")) if stack.last() == Some(&program) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
@@ -0,0 +1,968 @@
+//! Builders for every minidex instruction.
+//!
+//! User token accounts for the pool's mints are passed in explicitly, the
+//! program only requires them to be owned by the user. The LP token account is
+//! always the user's associated token account, as the program expects.
+//!
+//! Builders of the instructions a pool's allowlist gates take `permission`,
+//! the trading wallet's `PoolPermission` address as `pool_permission` finds
+//! it, or `None` for pools without the allowlist.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use minidex::{accounts, instruction};
+
+use crate::{
+    find_config_address, find_dca_address, find_dca_escrow_address, find_farm_address, find_farm_lp_vault_address,
+    find_farm_position_address, find_farm_reward_vault_address, find_limit_order_address,
+    find_limit_order_escrow_address, find_permission_address, find_program_data_address, find_registered_token_address,
+    find_token_registry_address, find_user_stake_address,
+    find_user_stake_vault_address, Dca, FeeDiscountTier, LimitOrder, PoolKeys,
+};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: minidex::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Creates the pool account. The vault and LP mint addresses are the PDAs the
+/// follow-up setup instructions create.
+pub fn initialize_pool(authority: &Pubkey, keys: &PoolKeys, fee_rate: u16) -> Instruction {
+    build_initialize_pool(authority, keys, fee_rate, false)
+}
+
+/// `initialize_pool` passing both mints' registrations, as pools need while
+/// the token registry is enabled.
+pub fn initialize_registered_pool(authority: &Pubkey, keys: &PoolKeys, fee_rate: u16) -> Instruction {
+    build_initialize_pool(authority, keys, fee_rate, true)
+}
+
+fn build_initialize_pool(authority: &Pubkey, keys: &PoolKeys, fee_rate: u16, registered: bool) -> Instruction {
+    let registration = |mint| registered.then(|| find_registered_token_address(mint).0);
+
+    build(
+        accounts::InitializePool {
+            authority: *authority,
+            token_a_mint: keys.token_a_mint,
+            token_b_mint: keys.token_b_mint,
+            pool: keys.pool,
+            token_registry: find_token_registry_address().0,
+            registered_token_a: registration(&keys.token_a_mint),
+            registered_token_b: registration(&keys.token_b_mint),
+            system_program: system_program::ID,
+        },
+        instruction::InitializePool {
+            fee_rate,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+        },
+    )
+}
+
+pub fn initialize_vault_a(authority: &Pubkey, keys: &PoolKeys) -> Instruction {
+    build(
+        accounts::SetupVaultA {
+            authority: *authority,
+            pool: keys.pool,
+            token_a_mint: keys.token_a_mint,
+            token_a_vault: keys.token_a_vault,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeVaultA {},
+    )
+}
+
+pub fn initialize_vault_b(authority: &Pubkey, keys: &PoolKeys) -> Instruction {
+    build(
+        accounts::SetupVaultB {
+            authority: *authority,
+            pool: keys.pool,
+            token_b_mint: keys.token_b_mint,
+            token_b_vault: keys.token_b_vault,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeVaultB {},
+    )
+}
+
+pub fn initialize_lp_mint(authority: &Pubkey, keys: &PoolKeys) -> Instruction {
+    build(
+        accounts::SetupLpMint {
+            authority: *authority,
+            pool: keys.pool,
+            lp_mint: keys.lp_mint,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeLpMint {},
+    )
+}
+
+/// The four instructions that create a pool end to end, in order.
+pub fn create_pool(authority: &Pubkey, keys: &PoolKeys, fee_rate: u16) -> Vec<Instruction> {
+    vec![
+        initialize_pool(authority, keys, fee_rate),
+        initialize_vault_a(authority, keys),
+        initialize_vault_b(authority, keys),
+        initialize_lp_mint(authority, keys),
+    ]
+}
+
+/// `create_pool` for mints in the token registry.
+pub fn create_registered_pool(authority: &Pubkey, keys: &PoolKeys, fee_rate: u16) -> Vec<Instruction> {
+    vec![
+        initialize_registered_pool(authority, keys, fee_rate),
+        initialize_vault_a(authority, keys),
+        initialize_vault_b(authority, keys),
+        initialize_lp_mint(authority, keys),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp_tokens: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::AddLiquidity {
+            user: *user,
+            pool: keys.pool,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            user_lp_account: get_associated_token_address(user, &keys.lp_mint),
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            permission,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::AddLiquidity {
+            amount_a,
+            amount_b,
+            min_lp_tokens,
+        },
+    )
+}
+
+/// Deposits `amount_in` of one token. The program swaps the share that balances
+/// the deposit and adds both sides, so both user token accounts are required.
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity_single_sided(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    amount_in: u64,
+    token_is_a: bool,
+    min_lp_tokens: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::AddLiquiditySingleSided {
+            user: *user,
+            pool: keys.pool,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            user_lp_account: get_associated_token_address(user, &keys.lp_mint),
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            permission,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::AddLiquiditySingleSided {
+            amount_in,
+            token_is_a,
+            min_lp_tokens,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn remove_liquidity(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    lp_tokens: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::RemoveLiquidity {
+            user: *user,
+            pool: keys.pool,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            user_lp_token: get_associated_token_address(user, &keys.lp_mint),
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            permission,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RemoveLiquidity {
+            lp_tokens,
+            min_amount_a,
+            min_amount_b,
+        },
+    )
+}
+
+/// Burns `lp_tokens` and pays out only one token. The program swaps the other
+/// side of the withdrawal back through the pool.
+#[allow(clippy::too_many_arguments)]
+pub fn remove_liquidity_single_sided(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    lp_tokens: u64,
+    want_token_a: bool,
+    min_amount_out: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::RemoveLiquiditySingleSided {
+            user: *user,
+            pool: keys.pool,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            user_lp_token: get_associated_token_address(user, &keys.lp_mint),
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            permission,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RemoveLiquiditySingleSided {
+            lp_tokens,
+            want_token_a,
+            min_amount_out,
+        },
+    )
+}
+
+/// Optional parts of a swap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapOptions {
+    /// Receives `referral_bps` of the swap fee, holds the input token
+    pub referrer_token_account: Option<Pubkey>,
+    /// Owner of `referrer_token_account`
+    pub referrer: Option<Pubkey>,
+    pub referral_bps: u16,
+    /// Passes the config and the user's stake account so their staking
+    /// discount applies
+    pub fee_discount: bool,
+    pub permission: Option<Pubkey>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap_tokens(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    token_a_to_b: bool,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    swap_tokens_with_options(
+        user,
+        keys,
+        user_token_a_account,
+        user_token_b_account,
+        amount_in,
+        min_amount_out,
+        token_a_to_b,
+        SwapOptions {
+            permission,
+            ..SwapOptions::default()
+        },
+    )
+}
+
+/// Pays `referral_bps` of the swap fee to `referrer_token_account`, which
+/// holds the input token and belongs to `referrer`.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_tokens_with_referral(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    token_a_to_b: bool,
+    referrer: &Pubkey,
+    referrer_token_account: &Pubkey,
+    referral_bps: u16,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    swap_tokens_with_options(
+        user,
+        keys,
+        user_token_a_account,
+        user_token_b_account,
+        amount_in,
+        min_amount_out,
+        token_a_to_b,
+        SwapOptions {
+            referrer_token_account: Some(*referrer_token_account),
+            referrer: Some(*referrer),
+            referral_bps,
+            fee_discount: false,
+            permission,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap_tokens_with_options(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    token_a_to_b: bool,
+    options: SwapOptions,
+) -> Instruction {
+    let (config, user_stake) = if options.fee_discount {
+        (Some(find_config_address().0), Some(find_user_stake_address(user).0))
+    } else {
+        (None, None)
+    };
+
+    build(
+        accounts::Swap {
+            user: *user,
+            pool: keys.pool,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            referrer_token_account: options.referrer_token_account,
+            referrer: options.referrer,
+            config,
+            user_stake,
+            permission: options.permission,
+            token_program: token::ID,
+        },
+        instruction::SwapTokens {
+            amount_in,
+            min_amount_out,
+            token_a_to_b,
+            referral_bps: options.referral_bps,
+        },
+    )
+}
+
+/// `authority` may be the pool authority or its guardian.
+pub fn set_pool_status(authority: &Pubkey, pool: &Pubkey, status: u8) -> Instruction {
+    build(
+        accounts::SetPoolStatus {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetPoolStatus { status },
+    )
+}
+
+pub fn set_guardian(authority: &Pubkey, pool: &Pubkey, guardian: &Pubkey) -> Instruction {
+    build(
+        accounts::SetGuardian {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetGuardian { guardian: *guardian },
+    )
+}
+
+pub fn set_price_limits(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    max_price_impact_bps: u16,
+    max_slot_price_move_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetPriceLimits {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetPriceLimits {
+            max_price_impact_bps,
+            max_slot_price_move_bps,
+        },
+    )
+}
+
+/// A cap of zero disables referrals on the pool.
+pub fn set_max_referral_bps(authority: &Pubkey, pool: &Pubkey, max_referral_bps: u16) -> Instruction {
+    build(
+        accounts::SetMaxReferralBps {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetMaxReferralBps { max_referral_bps },
+    )
+}
+
+/// A multiplier of zero turns the dynamic fee mode off.
+pub fn set_dynamic_fee(authority: &Pubkey, pool: &Pubkey, min_fee_rate: u16, volatility_fee_multiplier: u16) -> Instruction {
+    build(
+        accounts::SetDynamicFee {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetDynamicFee {
+            min_fee_rate,
+            volatility_fee_multiplier,
+        },
+    )
+}
+
+/// Creates the global config. Only minidex's upgrade authority may sign as `admin`.
+pub fn initialize_config(admin: &Pubkey, governance_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin: *admin,
+            config: find_config_address().0,
+            governance_mint: *governance_mint,
+            program: minidex::ID,
+            program_data: find_program_data_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {},
+    )
+}
+
+/// An empty schedule turns fee discounts off.
+pub fn set_fee_discounts(admin: &Pubkey, tiers: Vec<FeeDiscountTier>) -> Instruction {
+    build(
+        accounts::SetFeeDiscounts {
+            admin: *admin,
+            config: find_config_address().0,
+        },
+        instruction::SetFeeDiscounts { tiers },
+    )
+}
+
+pub fn stake_governance(
+    user: &Pubkey,
+    governance_mint: &Pubkey,
+    user_governance_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (user_stake, _) = find_user_stake_address(user);
+
+    build(
+        accounts::StakeGovernance {
+            user: *user,
+            config: find_config_address().0,
+            governance_mint: *governance_mint,
+            user_stake,
+            stake_vault: find_user_stake_vault_address(&user_stake).0,
+            user_governance_account: *user_governance_account,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::StakeGovernance { amount },
+    )
+}
+
+/// Fails until `GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS` have passed since the
+/// user last staked.
+pub fn unstake_governance(user: &Pubkey, user_governance_account: &Pubkey, amount: u64) -> Instruction {
+    let (user_stake, _) = find_user_stake_address(user);
+
+    build(
+        accounts::UnstakeGovernance {
+            user: *user,
+            user_stake,
+            stake_vault: find_user_stake_vault_address(&user_stake).0,
+            user_governance_account: *user_governance_account,
+            token_program: token::ID,
+        },
+        instruction::UnstakeGovernance { amount },
+    )
+}
+
+/// Only wallets granted access may trade or provide liquidity while the
+/// allowlist is enabled.
+pub fn set_allowlist(authority: &Pubkey, pool: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetAllowlist {
+            authority: *authority,
+            pool: *pool,
+        },
+        instruction::SetAllowlist { enabled },
+    )
+}
+
+pub fn grant_access(authority: &Pubkey, pool: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::GrantAccess {
+            authority: *authority,
+            pool: *pool,
+            permission: find_permission_address(pool, user).0,
+            system_program: system_program::ID,
+        },
+        instruction::GrantAccess { user: *user },
+    )
+}
+
+pub fn revoke_access(authority: &Pubkey, pool: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::RevokeAccess {
+            authority: *authority,
+            pool: *pool,
+            permission: find_permission_address(pool, user).0,
+        },
+        instruction::RevokeAccess {},
+    )
+}
+
+/// Creates the token registry on first use. While it is enabled only
+/// registered mints can be paired in new pools.
+pub fn set_token_registry(admin: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetTokenRegistry {
+            admin: *admin,
+            config: find_config_address().0,
+            token_registry: find_token_registry_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::SetTokenRegistry { enabled },
+    )
+}
+
+/// `skip_mint_checks` accepts the mint even though it has a freeze authority.
+pub fn register_token(admin: &Pubkey, mint: &Pubkey, skip_mint_checks: bool) -> Instruction {
+    build(
+        accounts::RegisterToken {
+            admin: *admin,
+            config: find_config_address().0,
+            mint: *mint,
+            registered_token: find_registered_token_address(mint).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterToken { skip_mint_checks },
+    )
+}
+
+pub fn deregister_token(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::DeregisterToken {
+            admin: *admin,
+            config: find_config_address().0,
+            registered_token: find_registered_token_address(mint).0,
+        },
+        instruction::DeregisterToken {},
+    )
+}
+
+/// Sweeps what is left in the vaults to the authority's token accounts and
+/// closes the vaults and the pool. Fails while LP tokens are outstanding.
+pub fn close_pool(
+    authority: &Pubkey,
+    keys: &PoolKeys,
+    authority_token_a_account: &Pubkey,
+    authority_token_b_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ClosePool {
+            authority: *authority,
+            pool: keys.pool,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            lp_mint: keys.lp_mint,
+            authority_token_a_account: *authority_token_a_account,
+            authority_token_b_account: *authority_token_b_account,
+            token_program: token::ID,
+        },
+        instruction::ClosePool {},
+    )
+}
+
+/// Grows a pool created before the latest `Pool` fields to the current
+/// layout, with `payer` covering the extra rent.
+pub fn migrate_pool(payer: &Pubkey, pool: &Pubkey) -> Instruction {
+    build(
+        accounts::MigratePool {
+            payer: *payer,
+            pool: *pool,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePool {},
+    )
+}
+
+pub fn quote_swap(pool: &Pubkey, amount_in: u64, token_a_to_b: bool) -> Instruction {
+    build(
+        accounts::QuoteSwap { pool: *pool },
+        instruction::QuoteSwap {
+            amount_in,
+            token_a_to_b,
+        },
+    )
+}
+
+pub fn quote_add_liquidity(pool: &Pubkey, amount_a: u64, amount_b: u64) -> Instruction {
+    build(
+        accounts::QuoteAddLiquidity { pool: *pool },
+        instruction::QuoteAddLiquidity { amount_a, amount_b },
+    )
+}
+
+pub fn quote_remove_liquidity(pool: &Pubkey, lp_tokens: u64) -> Instruction {
+    build(
+        accounts::QuoteRemoveLiquidity { pool: *pool },
+        instruction::QuoteRemoveLiquidity { lp_tokens },
+    )
+}
+
+/// Creates the pool's farm and the vault for staked LP tokens.
+pub fn initialize_farm(authority: &Pubkey, keys: &PoolKeys) -> Instruction {
+    let (farm, _) = find_farm_address(&keys.pool);
+
+    build(
+        accounts::InitializeFarm {
+            authority: *authority,
+            pool: keys.pool,
+            farm,
+            lp_mint: keys.lp_mint,
+            lp_vault: find_farm_lp_vault_address(&farm).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeFarm {},
+    )
+}
+
+/// Adds `amount` of `reward_mint` to the farm, emitted evenly from
+/// `start_time` until `end_time` together with whatever a running schedule of
+/// the same mint has not emitted yet. Only as much of `amount` is taken as
+/// the schedule emits in whole tokens per second.
+pub fn fund_farm_reward(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    authority_reward_account: &Pubkey,
+    start_time: i64,
+    end_time: i64,
+    amount: u64,
+) -> Instruction {
+    let (farm, _) = find_farm_address(pool);
+
+    build(
+        accounts::FundFarmReward {
+            authority: *authority,
+            pool: *pool,
+            farm,
+            reward_mint: *reward_mint,
+            reward_vault: find_farm_reward_vault_address(&farm, reward_mint).0,
+            authority_reward_account: *authority_reward_account,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::FundFarmReward {
+            start_time,
+            end_time,
+            amount,
+        },
+    )
+}
+
+pub fn stake_lp(user: &Pubkey, keys: &PoolKeys, amount: u64) -> Instruction {
+    let (farm, _) = find_farm_address(&keys.pool);
+
+    build(
+        accounts::StakeLp {
+            user: *user,
+            farm,
+            position: find_farm_position_address(&farm, user).0,
+            user_lp_account: get_associated_token_address(user, &keys.lp_mint),
+            lp_vault: find_farm_lp_vault_address(&farm).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::StakeLp { amount },
+    )
+}
+
+pub fn unstake_lp(user: &Pubkey, keys: &PoolKeys, amount: u64) -> Instruction {
+    let (farm, _) = find_farm_address(&keys.pool);
+
+    build(
+        accounts::UnstakeLp {
+            user: *user,
+            farm,
+            position: find_farm_position_address(&farm, user).0,
+            user_lp_account: get_associated_token_address(user, &keys.lp_mint),
+            lp_vault: find_farm_lp_vault_address(&farm).0,
+            token_program: token::ID,
+        },
+        instruction::UnstakeLp { amount },
+    )
+}
+
+/// Withdraws all of the user's staked LP tokens and forfeits their pending
+/// rewards, for when `unstake_lp` cannot update the farm's rewards.
+pub fn emergency_unstake_lp(user: &Pubkey, keys: &PoolKeys) -> Instruction {
+    let (farm, _) = find_farm_address(&keys.pool);
+
+    build(
+        accounts::EmergencyUnstakeLp {
+            user: *user,
+            farm,
+            position: find_farm_position_address(&farm, user).0,
+            user_lp_account: get_associated_token_address(user, &keys.lp_mint),
+            lp_vault: find_farm_lp_vault_address(&farm).0,
+            token_program: token::ID,
+        },
+        instruction::EmergencyUnstakeLp {},
+    )
+}
+
+/// Pays out the user's pending rewards of the farm's `reward_index`-th
+/// stream, whose mint is `reward_mint`.
+pub fn claim_rewards(
+    user: &Pubkey,
+    pool: &Pubkey,
+    reward_mint: &Pubkey,
+    user_reward_account: &Pubkey,
+    reward_index: u8,
+) -> Instruction {
+    let (farm, _) = find_farm_address(pool);
+
+    build(
+        accounts::ClaimRewards {
+            user: *user,
+            farm,
+            position: find_farm_position_address(&farm, user).0,
+            reward_vault: find_farm_reward_vault_address(&farm, reward_mint).0,
+            user_reward_account: *user_reward_account,
+            token_program: token::ID,
+        },
+        instruction::ClaimRewards { reward_index },
+    )
+}
+
+/// Escrows `amount_in` plus `keeper_fee` of the token sold. The output is paid
+/// to the user's other token account once the order is filled.
+#[allow(clippy::too_many_arguments)]
+pub fn place_limit_order(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    order_id: u64,
+    sell_token_a: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+    keeper_fee: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    let (order, _) = find_limit_order_address(&keys.pool, user, order_id);
+
+    build(
+        accounts::PlaceLimitOrder {
+            user: *user,
+            pool: keys.pool,
+            order,
+            input_mint: if sell_token_a { keys.token_a_mint } else { keys.token_b_mint },
+            escrow: find_limit_order_escrow_address(&order).0,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            permission,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::PlaceLimitOrder {
+            order_id,
+            sell_token_a,
+            amount_in,
+            min_amount_out,
+            keeper_fee,
+        },
+    )
+}
+
+/// Fills the decoded `order` at `order_address`. The keeper fee is paid to
+/// `keeper_token_account`, which holds the order's input token. Tokens sent
+/// to the escrow on top of the order go back to the maker's associated token
+/// account for the input token.
+pub fn fill_limit_order(
+    keeper: &Pubkey,
+    keys: &PoolKeys,
+    order_address: &Pubkey,
+    order: &LimitOrder,
+    keeper_token_account: &Pubkey,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    let input_mint = if order.sell_token_a { keys.token_a_mint } else { keys.token_b_mint };
+
+    build(
+        accounts::FillLimitOrder {
+            keeper: *keeper,
+            pool: keys.pool,
+            order: *order_address,
+            maker: order.maker,
+            escrow: order.escrow,
+            maker_receive_account: order.receive_account,
+            maker_refund_account: get_associated_token_address(&order.maker, &input_mint),
+            keeper_token_account: *keeper_token_account,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            permission,
+            token_program: token::ID,
+        },
+        instruction::FillLimitOrder {},
+    )
+}
+
+/// Returns the escrow to `user_token_account`, which holds the order's input
+/// token, and closes the order.
+pub fn cancel_limit_order(user: &Pubkey, pool: &Pubkey, order_id: u64, user_token_account: &Pubkey) -> Instruction {
+    let (order, _) = find_limit_order_address(pool, user, order_id);
+
+    build(
+        accounts::CancelLimitOrder {
+            user: *user,
+            order,
+            escrow: find_limit_order_escrow_address(&order).0,
+            user_token_account: *user_token_account,
+            token_program: token::ID,
+        },
+        instruction::CancelLimitOrder {},
+    )
+}
+
+/// Escrows `amount_per_slice * slice_count` of the token sold. Each slice's
+/// output is paid to the user's other token account.
+#[allow(clippy::too_many_arguments)]
+pub fn open_dca(
+    user: &Pubkey,
+    keys: &PoolKeys,
+    user_token_a_account: &Pubkey,
+    user_token_b_account: &Pubkey,
+    dca_id: u64,
+    sell_token_a: bool,
+    amount_per_slice: u64,
+    slice_count: u32,
+    interval: i64,
+    min_amount_out_per_slice: u64,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    let (dca, _) = find_dca_address(&keys.pool, user, dca_id);
+
+    build(
+        accounts::OpenDca {
+            user: *user,
+            pool: keys.pool,
+            dca,
+            input_mint: if sell_token_a { keys.token_a_mint } else { keys.token_b_mint },
+            escrow: find_dca_escrow_address(&dca).0,
+            user_token_a_account: *user_token_a_account,
+            user_token_b_account: *user_token_b_account,
+            permission,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::OpenDca {
+            dca_id,
+            sell_token_a,
+            amount_per_slice,
+            slice_count,
+            interval,
+            min_amount_out_per_slice,
+        },
+    )
+}
+
+/// Runs the next slice of the decoded `dca` at `dca_address`. After the last
+/// one, `close_dca` returns the schedule's rent.
+pub fn execute_dca_slice(
+    cranker: &Pubkey,
+    keys: &PoolKeys,
+    dca_address: &Pubkey,
+    dca: &Dca,
+    permission: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ExecuteDcaSlice {
+            cranker: *cranker,
+            pool: keys.pool,
+            dca: *dca_address,
+            owner: dca.owner,
+            escrow: dca.escrow,
+            owner_receive_account: dca.receive_account,
+            token_a_vault: keys.token_a_vault,
+            token_b_vault: keys.token_b_vault,
+            permission,
+            token_program: token::ID,
+        },
+        instruction::ExecuteDcaSlice {},
+    )
+}
+
+/// Returns the unswapped input to `user_token_account`, which holds the
+/// schedule's input token, and closes the schedule.
+pub fn cancel_dca(user: &Pubkey, pool: &Pubkey, dca_id: u64, user_token_account: &Pubkey) -> Instruction {
+    let (dca, _) = find_dca_address(pool, user, dca_id);
+
+    build(
+        accounts::CancelDca {
+            user: *user,
+            dca,
+            escrow: find_dca_escrow_address(&dca).0,
+            user_token_account: *user_token_account,
+            token_program: token::ID,
+        },
+        instruction::CancelDca {},
+    )
+}
+
+/// Closes the decoded `dca` at `dca_address` once its last slice has run.
+/// Tokens sent to the escrow since go back to the owner's associated token
+/// account for the input token, and the rent to the owner.
+pub fn close_dca(cranker: &Pubkey, keys: &PoolKeys, dca_address: &Pubkey, dca: &Dca) -> Instruction {
+    let input_mint = if dca.sell_token_a { keys.token_a_mint } else { keys.token_b_mint };
+
+    build(
+        accounts::CloseDca {
+            cranker: *cranker,
+            dca: *dca_address,
+            owner: dca.owner,
+            escrow: dca.escrow,
+            owner_refund_account: get_associated_token_address(&dca.owner, &input_mint),
+            token_program: token::ID,
+        },
+        instruction::CloseDca {},
+    )
+}
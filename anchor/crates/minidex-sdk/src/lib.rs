@@ -0,0 +1,24 @@
+//! Off-chain helpers for the minidex program.
+//!
+//! The pool math is re-exported from the program crate itself rather than
+//! reimplemented, so quotes computed here always match what the program does
+//! on-chain.
+
+pub mod accounts;
+pub mod events;
+pub mod instructions;
+pub mod math;
+pub mod pda;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+pub use minidex::constants;
+pub use minidex::errors::MinidexError;
+pub use minidex::state::{
+    Config, Dca, Farm, FarmPosition, FarmReward, FeeDiscountTier, LimitOrder, Pool, PoolPermission, PositionReward, RegisteredToken,
+    RemoveLiquidityQuote, SingleSidedDepositQuote, SingleSidedWithdrawalQuote, SwapQuote, TokenRegistry, UserStake,
+};
+pub use minidex::ID as PROGRAM_ID;
+
+pub use accounts::*;
+pub use pda::*;
@@ -0,0 +1,4 @@
+//! Pool math shared with the on-chain program.
+
+pub use minidex::math::*;
+pub use minidex::utils::*;
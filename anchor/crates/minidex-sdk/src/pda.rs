@@ -0,0 +1,160 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::Pool;
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const VAULT_A_SEED: &[u8] = b"vault_a";
+pub const VAULT_B_SEED: &[u8] = b"vault_b";
+pub const LP_MINT_SEED: &[u8] = b"lp_mint";
+pub const FARM_SEED: &[u8] = b"farm";
+pub const FARM_LP_VAULT_SEED: &[u8] = b"farm_lp_vault";
+pub const FARM_REWARD_VAULT_SEED: &[u8] = b"farm_reward_vault";
+pub const FARM_POSITION_SEED: &[u8] = b"farm_position";
+pub const LIMIT_ORDER_SEED: &[u8] = b"limit_order";
+pub const LIMIT_ORDER_ESCROW_SEED: &[u8] = b"limit_order_escrow";
+pub const DCA_SEED: &[u8] = b"dca";
+pub const DCA_ESCROW_SEED: &[u8] = b"dca_escrow";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const USER_STAKE_SEED: &[u8] = b"user_stake";
+pub const USER_STAKE_VAULT_SEED: &[u8] = b"user_stake_vault";
+pub const PERMISSION_SEED: &[u8] = b"permission";
+pub const TOKEN_REGISTRY_SEED: &[u8] = b"token_registry";
+pub const REGISTERED_TOKEN_SEED: &[u8] = b"registered_token";
+
+/// Owner of minidex's upgradeable program accounts
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = anchor_lang::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+pub fn find_pool_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POOL_SEED, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        &minidex::ID,
+    )
+}
+
+pub fn find_vault_a_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_A_SEED, pool.as_ref()], &minidex::ID)
+}
+
+pub fn find_vault_b_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_B_SEED, pool.as_ref()], &minidex::ID)
+}
+
+pub fn find_lp_mint_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LP_MINT_SEED, pool.as_ref()], &minidex::ID)
+}
+
+pub fn find_farm_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FARM_SEED, pool.as_ref()], &minidex::ID)
+}
+
+pub fn find_farm_lp_vault_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FARM_LP_VAULT_SEED, farm.as_ref()], &minidex::ID)
+}
+
+pub fn find_farm_reward_vault_address(farm: &Pubkey, reward_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FARM_REWARD_VAULT_SEED, farm.as_ref(), reward_mint.as_ref()],
+        &minidex::ID,
+    )
+}
+
+pub fn find_farm_position_address(farm: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FARM_POSITION_SEED, farm.as_ref(), owner.as_ref()], &minidex::ID)
+}
+
+pub fn find_limit_order_address(pool: &Pubkey, maker: &Pubkey, order_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LIMIT_ORDER_SEED, pool.as_ref(), maker.as_ref(), &order_id.to_le_bytes()],
+        &minidex::ID,
+    )
+}
+
+pub fn find_limit_order_escrow_address(order: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LIMIT_ORDER_ESCROW_SEED, order.as_ref()], &minidex::ID)
+}
+
+pub fn find_dca_address(pool: &Pubkey, owner: &Pubkey, dca_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DCA_SEED, pool.as_ref(), owner.as_ref(), &dca_id.to_le_bytes()],
+        &minidex::ID,
+    )
+}
+
+pub fn find_dca_escrow_address(dca: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DCA_ESCROW_SEED, dca.as_ref()], &minidex::ID)
+}
+
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], &minidex::ID)
+}
+
+pub fn find_user_stake_address(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_STAKE_SEED, owner.as_ref()], &minidex::ID)
+}
+
+pub fn find_user_stake_vault_address(user_stake: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_STAKE_VAULT_SEED, user_stake.as_ref()], &minidex::ID)
+}
+
+pub fn find_permission_address(pool: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PERMISSION_SEED, pool.as_ref(), user.as_ref()], &minidex::ID)
+}
+
+/// The permission account `user` passes to trade on or provide liquidity to
+/// the pool at `address`: their `PoolPermission` while the allowlist is
+/// enabled, `None` otherwise.
+pub fn pool_permission(address: &Pubkey, pool: &Pool, user: &Pubkey) -> Option<Pubkey> {
+    pool.allowlist_enabled.then(|| find_permission_address(address, user).0)
+}
+
+pub fn find_token_registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOKEN_REGISTRY_SEED], &minidex::ID)
+}
+
+pub fn find_registered_token_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTERED_TOKEN_SEED, mint.as_ref()], &minidex::ID)
+}
+
+/// The loader account recording minidex's upgrade authority.
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[minidex::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID)
+}
+
+/// Every address that belongs to a single pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+impl PoolKeys {
+    /// Derives the keys of the pool for this mint pair, whether or not it exists yet.
+    pub fn derive(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> Self {
+        let (pool, _) = find_pool_address(token_a_mint, token_b_mint);
+
+        Self {
+            pool,
+            token_a_mint: *token_a_mint,
+            token_b_mint: *token_b_mint,
+            token_a_vault: find_vault_a_address(&pool).0,
+            token_b_vault: find_vault_b_address(&pool).0,
+            lp_mint: find_lp_mint_address(&pool).0,
+        }
+    }
+
+    /// Reads the keys recorded in an existing pool account.
+    pub fn from_pool(address: &Pubkey, pool: &Pool) -> Self {
+        Self {
+            pool: *address,
+            token_a_mint: pool.token_a_mint,
+            token_b_mint: pool.token_b_mint,
+            token_a_vault: pool.token_a_vault,
+            token_b_vault: pool.token_b_vault,
+            lp_mint: pool.lp_mint,
+        }
+    }
+}
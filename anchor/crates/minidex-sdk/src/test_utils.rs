@@ -0,0 +1,44 @@
+//! Fixtures for tests of crates built on the SDK, enabled by the
+//! `test-utils` feature.
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::Pool;
+
+pub trait PoolTestDefault {
+    /// An empty pool with a 30 bps fee and every optional mode off. Tests
+    /// override the fields they need with struct update syntax, so new
+    /// fields only have to be added here.
+    fn test_default() -> Self;
+}
+
+impl PoolTestDefault for Pool {
+    fn test_default() -> Self {
+        Pool {
+            authority: Pubkey::default(),
+            token_a_mint: Pubkey::default(),
+            token_b_mint: Pubkey::default(),
+            token_a_vault: Pubkey::default(),
+            token_b_vault: Pubkey::default(),
+            lp_mint: Pubkey::default(),
+            fee_rate: 30,
+            bump: 255,
+            reserve_a: 0,
+            reserve_b: 0,
+            total_lp_supply: 0,
+            guardian: Pubkey::default(),
+            status: 0,
+            max_price_impact_bps: 0,
+            max_slot_price_move_bps: 0,
+            last_trade_slot: 0,
+            slot_start_reserve_a: 0,
+            slot_start_reserve_b: 0,
+            max_referral_bps: 0,
+            volatility_fee_multiplier: 0,
+            min_fee_rate: 0,
+            volatility_accumulator: 0,
+            volatility_update_slot: 0,
+            allowlist_enabled: false,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! The arbitrage bot: trades the pool back toward the market price whenever
+//! that is profitable after the pool fee.
+
+use minidex_sdk::math::calculate_swap_quote;
+use minidex_sdk::Pool;
+
+/// A swap the bot decided to make.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trade {
+    pub token_a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    /// Profit in raw token B units when the other side is settled at the market price
+    pub profit: f64,
+}
+
+/// Profit of selling `amount_in` to the pool and settling at `price`, raw
+/// token B per raw token A. Swaps the program would reject are worthless.
+fn quote(pool: &Pool, price: f64, amount_in: u64, token_a_to_b: bool) -> Option<Trade> {
+    let quote = calculate_swap_quote(pool, amount_in, token_a_to_b, 0).ok()?;
+
+    let profit = if token_a_to_b {
+        quote.amount_out as f64 - amount_in as f64 * price
+    } else {
+        quote.amount_out as f64 * price - amount_in as f64
+    };
+
+    Some(Trade {
+        token_a_to_b,
+        amount_in,
+        amount_out: quote.amount_out,
+        fee_amount: quote.fee_amount,
+        profit,
+    })
+}
+
+fn profit(pool: &Pool, price: f64, amount_in: u64, token_a_to_b: bool) -> f64 {
+    quote(pool, price, amount_in, token_a_to_b).map_or(f64::NEG_INFINITY, |trade| trade.profit)
+}
+
+/// The most profitable swap against `pool` at market `price`, raw token B
+/// per raw token A, if it earns more than `min_profit` raw token B.
+///
+/// The continuous constant-product optimum narrows the search, the exact
+/// program math then picks the amount, so rounding and fees match on-chain.
+pub fn best_trade(pool: &Pool, price: f64, min_profit: f64) -> Option<Trade> {
+    let (reserve_a, reserve_b) = (pool.reserve_a as f64, pool.reserve_b as f64);
+    let fee_factor = 1.0 - pool.fee_rate as f64 / 10_000.0;
+
+    // The pool overprices token A: buy it on the market and sell it here
+    let token_a_to_b = reserve_b / reserve_a > price;
+
+    // Selling x of the input token is optimal once the pool's marginal price
+    // fee_factor * reserve_in * reserve_out / (reserve_in + fee_factor * x)^2
+    // meets the market price of the input token in the output token.
+    let (reserve_in, reserve_out, market_price) = if token_a_to_b {
+        (reserve_a, reserve_b, price)
+    } else {
+        (reserve_b, reserve_a, 1.0 / price)
+    };
+    let estimate = ((fee_factor * reserve_in * reserve_out / market_price).sqrt() - reserve_in) / fee_factor;
+
+    if estimate.is_nan() || estimate < 1.0 {
+        return None;
+    }
+
+    // Profit is concave in the amount, a ternary search finds its peak
+    let (mut low, mut high) = (1u64, (estimate * 2.0).min(u64::MAX as f64 / 2.0) as u64 + 1);
+    while high - low > 2 {
+        let third = (high - low) / 3;
+        let (left, right) = (low + third, high - third);
+
+        if profit(pool, price, left, token_a_to_b) < profit(pool, price, right, token_a_to_b) {
+            low = left;
+        } else {
+            high = right;
+        }
+    }
+
+    (low..=high)
+        .filter_map(|amount_in| quote(pool, price, amount_in, token_a_to_b))
+        .max_by(|a, b| a.profit.total_cmp(&b.profit))
+        .filter(|trade| trade.profit > min_profit)
+}
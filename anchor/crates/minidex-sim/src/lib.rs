@@ -0,0 +1,13 @@
+//! Backtesting of minidex pools.
+//!
+//! A price series, read from CSV or generated, is replayed against a pool
+//! running the program's own swap and liquidity math. At every step an
+//! arbitrage bot trades the pool back to the market price when that pays
+//! after fees, which is how a real pool follows the market. The LP position
+//! is then compared with simply holding the deposit, once per fee rate.
+
+pub mod arbitrage;
+pub mod prices;
+pub mod simulation;
+
+pub use simulation::{simulate, Report, SimulationConfig};
@@ -0,0 +1,134 @@
+use std::fs::File;
+use std::io::{stdout, BufReader};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use minidex_sim::prices::{geometric_brownian_motion, read_csv, write_csv};
+use minidex_sim::{simulate, Report, SimulationConfig};
+
+#[derive(Parser)]
+#[command(name = "minidex-sim", version, about = "Backtest minidex pools over price series")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay a CSV price series once per fee rate and report LP returns
+    Run {
+        /// CSV file with a `price` column, token B per token A in whole tokens
+        #[arg(long)]
+        prices: PathBuf,
+        /// Fee rates to compare, in basis points
+        #[arg(long, value_delimiter = ',', default_value = "5,30,100")]
+        fee_rates: Vec<u16>,
+        /// Token A the LP deposits, in raw units
+        #[arg(long, default_value_t = 1_000_000_000_000)]
+        deposit_a: u64,
+        #[arg(long, default_value_t = 9)]
+        decimals_a: u8,
+        #[arg(long, default_value_t = 6)]
+        decimals_b: u8,
+        /// Smallest profit, in whole token B, the arbitrage bot trades for
+        #[arg(long, default_value_t = 0.0)]
+        min_profit: f64,
+        /// The arbitrage bot trades at every N-th price
+        #[arg(long, default_value_t = 1)]
+        interval: usize,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Write a synthetic price series (geometric Brownian motion) as CSV to stdout
+    Generate {
+        #[arg(long, default_value_t = 150.0)]
+        start_price: f64,
+        #[arg(long, default_value_t = 1_000)]
+        steps: usize,
+        /// Expected log return per step
+        #[arg(long, default_value_t = 0.0)]
+        drift: f64,
+        /// Standard deviation of the log return per step
+        #[arg(long, default_value_t = 0.01)]
+        volatility: f64,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+fn print_table(reports: &[Report]) {
+    println!(
+        "{:>8} {:>7} {:>16} {:>12} {:>12} {:>16} {:>16} {:>14} {:>9} {:>8}",
+        "fee_bps", "trades", "volume", "fees", "arb_profit", "lp_value", "hodl_value", "pnl", "pnl_bps", "il_bps"
+    );
+
+    for report in reports {
+        println!(
+            "{:>8} {:>7} {:>16.2} {:>12.2} {:>12.2} {:>16.2} {:>16.2} {:>14.2} {:>9.1} {:>8.1}",
+            report.fee_rate,
+            report.trades,
+            report.volume,
+            report.fees,
+            report.arbitrage_profit,
+            report.lp_value,
+            report.hodl_value,
+            report.pnl,
+            report.pnl_bps,
+            report.impermanent_loss_bps,
+        );
+    }
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Run {
+            prices,
+            fee_rates,
+            deposit_a,
+            decimals_a,
+            decimals_b,
+            min_profit,
+            interval,
+            format,
+        } => {
+            let file = File::open(&prices).with_context(|| format!("failed to open {}", prices.display()))?;
+            let prices = read_csv(BufReader::new(file))?;
+            let config = SimulationConfig {
+                deposit_a,
+                decimals_a,
+                decimals_b,
+                min_profit,
+                interval,
+            };
+
+            let reports = fee_rates
+                .iter()
+                .map(|&fee_rate| simulate(&prices, fee_rate, &config))
+                .collect::<Result<Vec<_>>>()?;
+
+            match format {
+                Format::Table => print_table(&reports),
+                Format::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+            }
+        }
+        Command::Generate {
+            start_price,
+            steps,
+            drift,
+            volatility,
+            seed,
+        } => {
+            let prices = geometric_brownian_motion(start_price, steps, drift, volatility, seed);
+            write_csv(stdout().lock(), &prices)?;
+        }
+    }
+
+    Ok(())
+}
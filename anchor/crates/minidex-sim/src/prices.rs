@@ -0,0 +1,63 @@
+//! Price series: read from CSV or generated.
+
+use std::io::{Read, Write};
+
+use anyhow::{ensure, Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Row {
+    price: f64,
+}
+
+/// Reads the `price` column of a CSV file with a header row. Other columns,
+/// such as a timestamp, are ignored. Prices are token B per token A in whole
+/// tokens, e.g. `150.25` for SOL/USDC.
+pub fn read_csv(reader: impl Read) -> Result<Vec<f64>> {
+    let mut prices = Vec::new();
+
+    for (line, row) in csv::Reader::from_reader(reader).deserialize::<Row>().enumerate() {
+        // Line 1 is the header
+        let price = row.with_context(|| format!("invalid price on line {}", line + 2))?.price;
+        ensure!(price.is_finite() && price > 0.0, "price on line {} must be positive", line + 2);
+        prices.push(price);
+    }
+
+    ensure!(!prices.is_empty(), "the price series is empty");
+    Ok(prices)
+}
+
+/// Writes `prices` in the format `read_csv` reads, numbered by step.
+pub fn write_csv(writer: impl Write, prices: &[f64]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["step", "price"])?;
+
+    for (step, price) in prices.iter().enumerate() {
+        writer.write_record([step.to_string(), price.to_string()])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// A geometric Brownian motion path of `steps` prices after `start`.
+/// `drift` and `volatility` are per step, so the same seed always gives the
+/// same path.
+pub fn geometric_brownian_motion(start: f64, steps: usize, drift: f64, volatility: f64, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut price = start;
+    let mut prices = vec![start];
+
+    for _ in 0..steps {
+        // Box-Muller transform of two uniform samples into a standard normal one
+        let (u1, u2): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+        let normal = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+
+        price *= ((drift - volatility * volatility / 2.0) + volatility * normal).exp();
+        prices.push(price);
+    }
+
+    prices
+}
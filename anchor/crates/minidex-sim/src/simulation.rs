@@ -0,0 +1,145 @@
+//! Replays a price series through one pool and values the LP position.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, ensure, Result};
+use minidex_sdk::constants::{MAX_FEE_RATE, MINIMUM_LIQUIDITY};
+use minidex_sdk::math::{calculate_add_liquidity_quote, calculate_remove_liquidity_quote, calculate_reserves_after_swap};
+use minidex_sdk::Pool;
+use serde::Serialize;
+
+use crate::arbitrage::best_trade;
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    /// Token A the LP deposits, in raw units. Token B is matched at the first price.
+    pub deposit_a: u64,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+    /// Profit in whole token B below which the bot does not trade, e.g. its transaction costs
+    pub min_profit: f64,
+    /// The bot only looks at every `interval`-th price, 1 to arbitrage every step
+    pub interval: usize,
+}
+
+/// Outcome for one fee rate. Values are in whole token B at the last price
+/// unless noted.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub fee_rate: u16,
+    pub trades: usize,
+    /// Arbitrage volume, each trade valued at its step's price
+    pub volume: f64,
+    /// Fees paid into the pool, each valued at its step's price
+    pub fees: f64,
+    /// What the bot earned, taken from the LPs
+    pub arbitrage_profit: f64,
+    /// The LP position withdrawn at the end
+    pub lp_value: f64,
+    /// The initial deposit held outside the pool
+    pub hodl_value: f64,
+    /// `lp_value - hodl_value`
+    pub pnl: f64,
+    pub pnl_bps: f64,
+    /// Loss of a fee-free position against holding for the first and last
+    /// price, `2 * sqrt(r) / (1 + r) - 1`
+    pub impermanent_loss_bps: f64,
+}
+
+fn pool(fee_rate: u16) -> Pool {
+    Pool {
+        authority: Pubkey::default(),
+        token_a_mint: Pubkey::default(),
+        token_b_mint: Pubkey::default(),
+        token_a_vault: Pubkey::default(),
+        token_b_vault: Pubkey::default(),
+        lp_mint: Pubkey::default(),
+        fee_rate,
+        bump: 0,
+        reserve_a: 0,
+        reserve_b: 0,
+        total_lp_supply: 0,
+        guardian: Pubkey::default(),
+        status: 0,
+        max_price_impact_bps: 0,
+        max_slot_price_move_bps: 0,
+        last_trade_slot: 0,
+        slot_start_reserve_a: 0,
+        slot_start_reserve_b: 0,
+        max_referral_bps: 0,
+        volatility_fee_multiplier: 0,
+        min_fee_rate: 0,
+        volatility_accumulator: 0,
+        volatility_update_slot: 0,
+        allowlist_enabled: false,
+    }
+}
+
+fn anchor_error(error: anchor_lang::error::Error) -> anyhow::Error {
+    anyhow!("{error}")
+}
+
+/// Seeds a pool with `fee_rate` at the first price, lets the bot arbitrage
+/// it against every following price and withdraws the LP position at the end.
+pub fn simulate(prices: &[f64], fee_rate: u16, config: &SimulationConfig) -> Result<Report> {
+    ensure!(!prices.is_empty(), "the price series is empty");
+    ensure!(fee_rate <= MAX_FEE_RATE, "fee rate {fee_rate} exceeds the maximum of {MAX_FEE_RATE} bps");
+    ensure!(config.interval > 0, "the arbitrage interval must be at least 1");
+
+    // Whole token prices to raw units: raw B per raw A
+    let unit_a = 10f64.powi(config.decimals_a as i32);
+    let unit_b = 10f64.powi(config.decimals_b as i32);
+    let raw_price = |price: f64| price * unit_b / unit_a;
+
+    let deposit_a = config.deposit_a;
+    let deposit_b = (deposit_a as f64 * raw_price(prices[0])).round();
+    ensure!(deposit_b >= 1.0 && deposit_b < u64::MAX as f64, "deposit of token B out of range");
+    let deposit_b = deposit_b as u64;
+
+    // The first deposit, as add_liquidity books it
+    let mut pool = pool(fee_rate);
+    let lp_tokens = calculate_add_liquidity_quote(&pool, deposit_a, deposit_b).map_err(anchor_error)?;
+    pool.reserve_a = deposit_a;
+    pool.reserve_b = deposit_b;
+    pool.total_lp_supply = lp_tokens + MINIMUM_LIQUIDITY;
+
+    let min_profit = config.min_profit * unit_b;
+    let (mut trades, mut volume, mut fees, mut arbitrage_profit) = (0, 0.0, 0.0, 0.0);
+
+    for &price in prices[1..].iter().skip(config.interval - 1).step_by(config.interval) {
+        let price = raw_price(price);
+
+        if let Some(trade) = best_trade(&pool, price, min_profit) {
+            let (reserve_a, reserve_b) =
+                calculate_reserves_after_swap(&pool, trade.amount_in, trade.amount_out, trade.token_a_to_b)
+                    .map_err(anchor_error)?;
+            pool.reserve_a = reserve_a;
+            pool.reserve_b = reserve_b;
+
+            let in_token_b = if trade.token_a_to_b { price } else { 1.0 };
+            trades += 1;
+            volume += trade.amount_in as f64 * in_token_b;
+            fees += trade.fee_amount as f64 * in_token_b;
+            arbitrage_profit += trade.profit;
+        }
+    }
+
+    let last_price = raw_price(prices[prices.len() - 1]);
+    let withdrawal = calculate_remove_liquidity_quote(&pool, lp_tokens).map_err(anchor_error)?;
+
+    let lp_value = (withdrawal.amount_a as f64 * last_price + withdrawal.amount_b as f64) / unit_b;
+    let hodl_value = (deposit_a as f64 * last_price + deposit_b as f64) / unit_b;
+    let ratio = prices[prices.len() - 1] / prices[0];
+
+    Ok(Report {
+        fee_rate,
+        trades,
+        volume: volume / unit_b,
+        fees: fees / unit_b,
+        arbitrage_profit: arbitrage_profit / unit_b,
+        lp_value,
+        hodl_value,
+        pnl: lp_value - hodl_value,
+        pnl_bps: (lp_value / hodl_value - 1.0) * 10_000.0,
+        impermanent_loss_bps: (2.0 * ratio.sqrt() / (1.0 + ratio) - 1.0) * 10_000.0,
+    })
+}
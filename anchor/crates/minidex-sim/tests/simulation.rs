@@ -0,0 +1,137 @@
+use minidex_sdk::math::calculate_reserves_after_swap;
+use minidex_sdk::test_utils::PoolTestDefault;
+use minidex_sdk::Pool;
+use minidex_sim::arbitrage::best_trade;
+use minidex_sim::prices::{geometric_brownian_motion, read_csv, write_csv};
+use minidex_sim::{simulate, SimulationConfig};
+
+/// 1,000 SOL against USDC.
+fn config() -> SimulationConfig {
+    SimulationConfig {
+        deposit_a: 1_000_000_000_000,
+        decimals_a: 9,
+        decimals_b: 6,
+        min_profit: 0.0,
+        interval: 1,
+    }
+}
+
+fn pool(reserve_a: u64, reserve_b: u64, fee_rate: u16) -> Pool {
+    Pool {
+        fee_rate,
+        reserve_a,
+        reserve_b,
+        total_lp_supply: 1_000_000,
+        ..Pool::test_default()
+    }
+}
+
+#[test]
+fn arbitrage_moves_the_pool_to_the_market_price() {
+    for (market_price, fee_rate) in [(4.4, 30), (3.6, 30), (4.4, 0), (10.0, 100)] {
+        let mut pool = pool(1_000_000_000, 4_000_000_000, fee_rate);
+        let trade = best_trade(&pool, market_price, 0.0).unwrap();
+
+        assert_eq!(trade.token_a_to_b, market_price < 4.0);
+        assert!(trade.profit > 0.0);
+
+        (pool.reserve_a, pool.reserve_b) =
+            calculate_reserves_after_swap(&pool, trade.amount_in, trade.amount_out, trade.token_a_to_b).unwrap();
+
+        // Afterwards the pool sits within the fee of the market price, give or
+        // take a basis point where rounding makes the last units worthless
+        let pool_price = pool.reserve_b as f64 / pool.reserve_a as f64;
+        let fee = fee_rate as f64 / 10_000.0;
+        assert!((pool_price / market_price - 1.0).abs() <= fee + 1e-4, "{pool_price} vs {market_price}");
+        assert!(best_trade(&pool, market_price, 0.0).is_none());
+    }
+}
+
+#[test]
+fn no_trade_inside_the_fee_band() {
+    let pool = pool(1_000_000_000, 4_000_000_000, 30);
+
+    assert!(best_trade(&pool, 4.0, 0.0).is_none());
+    assert!(best_trade(&pool, 4.01, 0.0).is_none());
+}
+
+#[test]
+fn a_fee_free_pool_loses_exactly_the_impermanent_loss() {
+    let report = simulate(&[150.0, 180.0, 120.0, 200.0], 0, &config()).unwrap();
+
+    assert_eq!(report.fees, 0.0);
+    assert!(report.pnl < 0.0);
+    assert!((report.pnl_bps - report.impermanent_loss_bps).abs() < 0.1, "{report:?}");
+}
+
+#[test]
+fn fees_pay_lps_for_a_round_trip() {
+    let prices = [150.0, 165.0, 135.0, 160.0, 140.0, 150.0];
+
+    let fee_free = simulate(&prices, 0, &config()).unwrap();
+    let with_fees = simulate(&prices, 30, &config()).unwrap();
+
+    // Back at the starting price there is no impermanent loss left
+    assert!(fee_free.impermanent_loss_bps.abs() < 1e-9);
+    assert!(fee_free.pnl.abs() < 0.01);
+    assert!(with_fees.fees > 0.0);
+    assert!(with_fees.pnl > 0.0);
+    assert!(with_fees.arbitrage_profit > 0.0);
+    assert_eq!((with_fees.trades, fee_free.trades), (5, 5));
+}
+
+#[test]
+fn higher_fees_mean_fewer_trades() {
+    let prices = geometric_brownian_motion(150.0, 200, 0.0, 0.005, 3);
+
+    let reports: Vec<_> = [5, 30, 100]
+        .into_iter()
+        .map(|fee_rate| simulate(&prices, fee_rate, &config()).unwrap())
+        .collect();
+
+    assert!(reports.windows(2).all(|pair| pair[0].trades >= pair[1].trades));
+    assert!(reports.windows(2).all(|pair| pair[0].volume > pair[1].volume));
+}
+
+#[test]
+fn the_bot_skips_trades_below_its_minimum_profit_and_between_intervals() {
+    let prices = [150.0, 151.0, 152.0, 153.0];
+
+    let all = simulate(&prices, 5, &config()).unwrap();
+    let every_other = simulate(&prices, 5, &SimulationConfig { interval: 2, ..config() }).unwrap();
+    let unprofitable = simulate(&prices, 5, &SimulationConfig { min_profit: 1_000_000.0, ..config() }).unwrap();
+
+    assert_eq!(all.trades, 3);
+    assert_eq!(every_other.trades, 1);
+    assert_eq!(unprofitable.trades, 0);
+    assert_eq!(unprofitable.arbitrage_profit, 0.0);
+}
+
+#[test]
+fn rejects_fee_rates_the_program_rejects() {
+    assert!(simulate(&[150.0], 1_001, &config()).is_err());
+}
+
+#[test]
+fn reads_the_price_column_of_a_csv() {
+    let csv = "timestamp,price,volume\n1700000000,150.5,10\n1700000060,151,12\n";
+    assert_eq!(read_csv(csv.as_bytes()).unwrap(), vec![150.5, 151.0]);
+
+    assert!(read_csv("timestamp,price\n1,0\n".as_bytes()).is_err());
+    assert!(read_csv("timestamp,price\n".as_bytes()).is_err());
+    assert!(read_csv("timestamp,close\n1,150\n".as_bytes()).is_err());
+}
+
+#[test]
+fn generated_series_are_reproducible_and_round_trip_through_csv() {
+    let prices = geometric_brownian_motion(150.0, 50, 0.0, 0.02, 42);
+
+    assert_eq!(prices.len(), 51);
+    assert_eq!(prices[0], 150.0);
+    assert_eq!(prices, geometric_brownian_motion(150.0, 50, 0.0, 0.02, 42));
+    assert_ne!(prices, geometric_brownian_motion(150.0, 50, 0.0, 0.02, 43));
+
+    let mut csv = Vec::new();
+    write_csv(&mut csv, &prices).unwrap();
+    assert_eq!(read_csv(csv.as_slice()).unwrap(), prices);
+}
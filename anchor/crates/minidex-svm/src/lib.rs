@@ -0,0 +1,282 @@
+//! Test harness that runs minidex without a validator.
+//!
+//! The compiled program is loaded into [LiteSVM](litesvm) next to the SPL
+//! Token and associated token programs it ships with, so every instruction
+//! executes as SBF under the real loader, CPI and account rules. The program
+//! is read from `$SBF_OUT_DIR/minidex.so`, or `target/deploy/minidex.so`
+//! after `anchor build`. Signatures are not verified: a transaction lists the
+//! addresses that signed it, and an internal account pays its fees.
+
+mod token;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::{Clock, Pubkey, Rent};
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::{pubkey, AccountDeserialize};
+use litesvm::LiteSVM;
+use solana_message::Message;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+/// Bytes before the ELF in an upgradeable program's data account
+const PROGRAM_DATA_METADATA_LEN: usize = 45;
+const FEE_PAYER_LAMPORTS: u64 = 1_000_000_000_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl From<solana_account::Account> for Account {
+    fn from(account: solana_account::Account) -> Self {
+        Self {
+            lamports: account.lamports,
+            data: account.data,
+            owner: account.owner,
+            executable: account.executable,
+        }
+    }
+}
+
+impl From<Account> for solana_account::Account {
+    fn from(account: Account) -> Self {
+        Self {
+            lamports: account.lamports,
+            data: account.data,
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: 0,
+        }
+    }
+}
+
+/// Outcome of a transaction that executed successfully.
+#[derive(Clone, Debug)]
+pub struct TransactionMetadata {
+    pub logs: Vec<String>,
+    /// Return data left by the last instruction, if any
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub compute_units_consumed: u64,
+}
+
+/// A transaction that failed. None of its account changes are kept.
+#[derive(Clone, Debug)]
+pub struct FailedTransaction {
+    /// Index of the instruction that failed
+    pub index: usize,
+    pub error: InstructionError,
+    pub logs: Vec<String>,
+}
+
+pub type TransactionResult = Result<TransactionMetadata, FailedTransaction>;
+
+pub struct Svm {
+    svm: LiteSVM,
+    program: Vec<u8>,
+    fee_payer: Pubkey,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        let path = program_path();
+        let program = std::fs::read(&path)
+            .unwrap_or_else(|error| panic!("cannot read {}: {error}; build the program first", path.display()));
+
+        Self::with_program(program)
+    }
+
+    /// Deploys `program`, an SBF build of minidex, instead of the one `new`
+    /// finds on disk.
+    pub fn with_program(program: Vec<u8>) -> Self {
+        let mut svm = Self {
+            svm: LiteSVM::new()
+                .with_sigverify(false)
+                .with_transaction_history(0)
+                .with_log_bytes_limit(None),
+            program,
+            fee_payer: Pubkey::new_unique(),
+        };
+
+        // Like a live cluster, nothing runs in the genesis slot
+        svm.warp_to_slot(1);
+        svm.airdrop(&svm.fee_payer.clone(), FEE_PAYER_LAMPORTS);
+        svm.set_upgrade_authority(None);
+
+        svm
+    }
+
+    /// Address of the account holding minidex's upgrade authority.
+    pub fn program_data_address() -> Pubkey {
+        Pubkey::find_program_address(&[minidex::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    /// Deploys minidex as an upgradeable program with `authority` allowed to
+    /// upgrade it, or as immutable for `None`.
+    pub fn set_upgrade_authority(&mut self, authority: Option<Pubkey>) {
+        let program_data_address = Self::program_data_address();
+
+        // Both accounts are laid out the way the loader's bincode encoding would
+        let mut program = 2u32.to_le_bytes().to_vec();
+        program.extend_from_slice(program_data_address.as_ref());
+
+        let mut program_data = vec![0; PROGRAM_DATA_METADATA_LEN];
+        program_data[..4].copy_from_slice(&3u32.to_le_bytes());
+        if let Some(authority) = authority {
+            program_data[12] = 1;
+            program_data[13..45].copy_from_slice(authority.as_ref());
+        }
+        program_data.extend_from_slice(&self.program);
+
+        // The program account is loaded from its data account, so that goes first
+        self.set_account(
+            program_data_address,
+            Account {
+                lamports: self.rent().minimum_balance(program_data.len()),
+                data: program_data,
+                owner: BPF_LOADER_UPGRADEABLE_ID,
+                executable: false,
+            },
+        );
+        self.set_account(
+            minidex::ID,
+            Account {
+                lamports: self.rent().minimum_balance(program.len()),
+                data: program,
+                owner: BPF_LOADER_UPGRADEABLE_ID,
+                executable: true,
+            },
+        );
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.svm.get_account(address).map(Account::from)
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.svm
+            .set_account(address, account.into())
+            .unwrap_or_else(|error| panic!("cannot set {address}: {error}"));
+    }
+
+    /// Decodes an Anchor account, checking its discriminator.
+    pub fn anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        self.account(address)
+            .and_then(|account| T::try_deserialize(&mut account.data.as_slice()).ok())
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    /// Credits `lamports` to an account, creating it as a system account if
+    /// needed.
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        let mut account = self.account(address).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*address, account);
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.svm.get_sysvar()
+    }
+
+    pub fn rent(&self) -> Rent {
+        self.svm.get_sysvar()
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.svm.warp_to_slot(slot);
+    }
+
+    pub fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Executes `instructions` atomically. Every address that must sign has
+    /// to be listed in `signers`.
+    pub fn process_transaction(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> TransactionResult {
+        let transaction = self.transaction(instructions, signers)?;
+
+        match self.svm.send_transaction(transaction) {
+            Ok(metadata) => Ok(transaction_metadata(metadata)),
+            Err(failed) => Err(failed_transaction(failed.err, failed.meta.logs)),
+        }
+    }
+
+    /// Executes `instructions` like `process_transaction` but discards every
+    /// account change.
+    pub fn simulate_transaction(&self, instructions: &[Instruction], signers: &[Pubkey]) -> TransactionResult {
+        let transaction = self.transaction(instructions, signers)?;
+
+        match self.svm.simulate_transaction(transaction) {
+            Ok(simulated) => Ok(transaction_metadata(simulated.meta)),
+            Err(failed) => Err(failed_transaction(failed.err, failed.meta.logs)),
+        }
+    }
+
+    fn transaction(&self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<Transaction, FailedTransaction> {
+        let unsigned = instructions.iter().position(|instruction| {
+            instruction
+                .accounts
+                .iter()
+                .any(|meta| meta.is_signer && !signers.contains(&meta.pubkey))
+        });
+
+        if let Some(index) = unsigned {
+            return Err(FailedTransaction {
+                index,
+                error: InstructionError::MissingRequiredSignature,
+                logs: Vec::new(),
+            });
+        }
+
+        let message = Message::new_with_blockhash(instructions, Some(&self.fee_payer), &self.svm.latest_blockhash());
+
+        Ok(Transaction::new_unsigned(message))
+    }
+}
+
+fn program_path() -> PathBuf {
+    let directory = match std::env::var_os("SBF_OUT_DIR") {
+        Some(directory) => PathBuf::from(directory),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"),
+    };
+
+    directory.join("minidex.so")
+}
+
+fn transaction_metadata(metadata: litesvm::types::TransactionMetadata) -> TransactionMetadata {
+    let return_data = metadata.return_data;
+
+    TransactionMetadata {
+        logs: metadata.logs,
+        return_data: (!return_data.data.is_empty()).then_some((return_data.program_id, return_data.data)),
+        compute_units_consumed: metadata.compute_units_consumed,
+    }
+}
+
+/// Transactions the runtime rejects before running any instruction are a bug
+/// in the test, not an outcome to assert on.
+fn failed_transaction(error: TransactionError, logs: Vec<String>) -> FailedTransaction {
+    match error {
+        TransactionError::InstructionError(index, error) => FailedTransaction {
+            index: index as usize,
+            error,
+            logs,
+        },
+        error => panic!("transaction rejected: {error}\n{logs:#?}"),
+    }
+}
@@ -0,0 +1,110 @@
+//! Shortcuts for setting up SPL Token state directly.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+
+use crate::{Account, Svm};
+
+impl Svm {
+    fn set_token_program_account<T: Pack>(&mut self, address: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        T::pack(state, &mut data).expect("buffer has the packed length");
+
+        self.set_account(
+            address,
+            Account {
+                lamports: self.rent().minimum_balance(T::LEN),
+                data,
+                owner: TOKEN_PROGRAM_ID,
+                executable: false,
+            },
+        );
+    }
+
+    /// Creates a mint with no supply at a new address.
+    pub fn create_mint(&mut self, mint_authority: &Pubkey, decimals: u8) -> Pubkey {
+        let address = Pubkey::new_unique();
+
+        self.set_token_program_account(
+            address,
+            Mint {
+                mint_authority: COption::Some(*mint_authority),
+                supply: 0,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+        );
+
+        address
+    }
+
+    /// Creates a token account at `address` holding `amount` freshly minted
+    /// tokens.
+    pub fn create_token_account_at(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let mut mint_state = self.mint(mint).expect("mint exists");
+        mint_state.supply = mint_state.supply.checked_add(amount).expect("mint supply overflow");
+        self.set_token_program_account(*mint, mint_state);
+
+        self.set_token_account_state(
+            address,
+            TokenAccount {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                delegate: COption::None,
+                state: AccountState::Initialized,
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            },
+        );
+    }
+
+    /// Creates a token account at a new address holding `amount` freshly
+    /// minted tokens.
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.create_token_account_at(address, mint, owner, amount);
+        address
+    }
+
+    /// Creates `owner`'s associated token account holding `amount` freshly
+    /// minted tokens.
+    pub fn create_associated_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = get_associated_token_address(owner, mint);
+        self.create_token_account_at(address, mint, owner, amount);
+        address
+    }
+
+    /// Overwrites a token account without touching its mint's supply.
+    pub fn set_token_account_state(&mut self, address: Pubkey, state: TokenAccount) {
+        self.set_token_program_account(address, state);
+    }
+
+    /// Overwrites a mint, e.g. to give it a freeze authority.
+    pub fn set_mint_state(&mut self, address: Pubkey, state: Mint) {
+        self.set_token_program_account(address, state);
+    }
+
+    pub fn mint(&self, address: &Pubkey) -> Option<Mint> {
+        self.account(address)
+            .filter(|account| account.owner == TOKEN_PROGRAM_ID)
+            .and_then(|account| Mint::unpack(&account.data).ok())
+    }
+
+    pub fn token_account(&self, address: &Pubkey) -> Option<TokenAccount> {
+        self.account(address)
+            .filter(|account| account.owner == TOKEN_PROGRAM_ID)
+            .and_then(|account| TokenAccount::unpack(&account.data).ok())
+    }
+
+    /// Balance of a token account, zero if it does not exist.
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        self.token_account(address).map_or(0, |account| account.amount)
+    }
+}
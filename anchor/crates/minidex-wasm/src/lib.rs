@@ -0,0 +1,165 @@
+//! WebAssembly bindings of the pool math for the web app.
+//!
+//! The program's `constants`, `errors`, `math`, `state` and `utils` modules
+//! are compiled in from their source files instead of through the `minidex`
+//! crate, whose SPL dependencies pull in Token-2022's proof generation crates
+//! that do not build for `wasm32-unknown-unknown`. Quotes therefore run the
+//! exact code the program runs, including its rounding and error checks.
+
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::error::Error;
+use anchor_lang::prelude::*;
+use wasm_bindgen::prelude::*;
+
+#[path = "../../../programs/minidex/src/constants.rs"]
+pub mod constants;
+#[path = "../../../programs/minidex/src/errors.rs"]
+pub mod errors;
+#[path = "../../../programs/minidex/src/math.rs"]
+pub mod math;
+#[path = "../../../programs/minidex/src/state.rs"]
+pub mod state;
+#[path = "../../../programs/minidex/src/utils.rs"]
+pub mod utils;
+
+use constants::{BPS_DENOMINATOR, MINIMUM_LIQUIDITY};
+use state::Pool;
+
+// `#[account]` on `Pool` expects the program id at the crate root
+declare_id!("JAVuBXeBZqXNtS73azhBDAoYaaAFfo4gWXoZe2e7Jf8H");
+
+/// Program errors surface in JavaScript as `"<ErrorName>: <message>"`.
+fn to_js_error(error: Error) -> JsError {
+    match error {
+        Error::AnchorError(error) => JsError::new(&format!("{}: {}", error.error_name, error.error_msg)),
+        Error::ProgramError(error) => JsError::new(&error.to_string()),
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    #[wasm_bindgen(js_name = amountOut)]
+    pub amount_out: u64,
+    #[wasm_bindgen(js_name = feeAmount)]
+    pub fee_amount: u64,
+    #[wasm_bindgen(js_name = priceImpactBps)]
+    pub price_impact_bps: u64,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositQuote {
+    #[wasm_bindgen(js_name = lpTokens)]
+    pub lp_tokens: u64,
+    /// Share of the LP supply the new LP tokens make up after the deposit, in basis points
+    #[wasm_bindgen(js_name = poolShareBps)]
+    pub pool_share_bps: u64,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawalQuote {
+    #[wasm_bindgen(js_name = amountA)]
+    pub amount_a: u64,
+    #[wasm_bindgen(js_name = amountB)]
+    pub amount_b: u64,
+}
+
+/// A decoded `Pool` account. Build one from the raw account bytes and quote
+/// against it until the account changes.
+#[wasm_bindgen]
+pub struct PoolMath {
+    pool: Pool,
+}
+
+#[wasm_bindgen]
+impl PoolMath {
+    /// Decodes raw `Pool` account data, checking the Anchor discriminator.
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> std::result::Result<PoolMath, JsError> {
+        let mut data = data;
+        let pool = Pool::try_deserialize(&mut data).map_err(to_js_error)?;
+
+        Ok(Self { pool })
+    }
+
+    #[wasm_bindgen(getter, js_name = reserveA)]
+    pub fn reserve_a(&self) -> u64 {
+        self.pool.reserve_a
+    }
+
+    #[wasm_bindgen(getter, js_name = reserveB)]
+    pub fn reserve_b(&self) -> u64 {
+        self.pool.reserve_b
+    }
+
+    #[wasm_bindgen(getter, js_name = totalLpSupply)]
+    pub fn total_lp_supply(&self) -> u64 {
+        self.pool.total_lp_supply
+    }
+
+    #[wasm_bindgen(getter, js_name = feeRate)]
+    pub fn fee_rate(&self) -> u16 {
+        self.pool.fee_rate
+    }
+
+    /// Fee a swap at `current_slot` pays, in bps. Differs from `feeRate` in
+    /// the dynamic fee mode.
+    #[wasm_bindgen(js_name = effectiveFeeRate)]
+    pub fn effective_fee_rate(&self, current_slot: u64) -> u16 {
+        let mut pool = self.pool.clone();
+        utils::decay_volatility(&mut pool, current_slot);
+        pool.effective_fee_rate()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> u8 {
+        self.pool.status
+    }
+
+    /// Quotes `swap_tokens`. `current_slot` matters only when the pool has a
+    /// per-slot price move limit or the dynamic fee mode on.
+    #[wasm_bindgen(js_name = quoteSwap)]
+    pub fn quote_swap(
+        &self,
+        amount_in: u64,
+        token_a_to_b: bool,
+        current_slot: u64,
+    ) -> std::result::Result<SwapQuote, JsError> {
+        let quote = utils::calculate_swap_quote(&self.pool, amount_in, token_a_to_b, current_slot).map_err(to_js_error)?;
+
+        Ok(SwapQuote {
+            amount_out: quote.amount_out,
+            fee_amount: quote.fee_amount,
+            price_impact_bps: quote.price_impact_bps,
+        })
+    }
+
+    /// Quotes `add_liquidity`, including the depositor's resulting pool share.
+    #[wasm_bindgen(js_name = quoteAddLiquidity)]
+    pub fn quote_add_liquidity(&self, amount_a: u64, amount_b: u64) -> std::result::Result<DepositQuote, JsError> {
+        let lp_tokens = utils::calculate_add_liquidity_quote(&self.pool, amount_a, amount_b).map_err(to_js_error)?;
+
+        // The first deposit also locks the minimum liquidity
+        let locked = if self.pool.total_lp_supply == 0 { MINIMUM_LIQUIDITY } else { 0 };
+        let supply_after = self.pool.total_lp_supply as u128 + lp_tokens as u128 + locked as u128;
+
+        Ok(DepositQuote {
+            lp_tokens,
+            pool_share_bps: (lp_tokens as u128 * BPS_DENOMINATOR as u128 / supply_after) as u64,
+        })
+    }
+
+    /// Quotes `remove_liquidity`.
+    #[wasm_bindgen(js_name = quoteRemoveLiquidity)]
+    pub fn quote_remove_liquidity(&self, lp_tokens: u64) -> std::result::Result<WithdrawalQuote, JsError> {
+        let quote = utils::calculate_remove_liquidity_quote(&self.pool, lp_tokens).map_err(to_js_error)?;
+
+        Ok(WithdrawalQuote {
+            amount_a: quote.amount_a,
+            amount_b: quote.amount_b,
+        })
+    }
+}
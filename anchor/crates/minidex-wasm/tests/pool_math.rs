@@ -0,0 +1,93 @@
+//! The bindings decode real `Pool` account bytes and agree with the program
+//! math. Error paths create JavaScript values, so they are only reachable
+//! from a wasm host.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use minidex_sdk::constants::MINIMUM_LIQUIDITY;
+use minidex_sdk::math::{calculate_add_liquidity_quote, calculate_remove_liquidity_quote, calculate_swap_quote};
+use minidex_sdk::test_utils::PoolTestDefault;
+use minidex_sdk::Pool;
+use minidex_wasm::{DepositQuote, PoolMath, SwapQuote, WithdrawalQuote};
+
+fn pool(reserve_a: u64, reserve_b: u64, total_lp_supply: u64) -> Pool {
+    Pool {
+        authority: Pubkey::new_unique(),
+        token_a_mint: Pubkey::new_unique(),
+        token_b_mint: Pubkey::new_unique(),
+        token_a_vault: Pubkey::new_unique(),
+        token_b_vault: Pubkey::new_unique(),
+        lp_mint: Pubkey::new_unique(),
+        reserve_a,
+        reserve_b,
+        total_lp_supply,
+        ..Pool::test_default()
+    }
+}
+
+fn account_data(pool: &Pool) -> Vec<u8> {
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn decodes_the_pool_account() {
+    let pool = pool(1_000_000, 4_000_000, 2_000_000);
+    let math = PoolMath::new(&account_data(&pool)).unwrap();
+
+    assert_eq!((math.reserve_a(), math.reserve_b()), (1_000_000, 4_000_000));
+    assert_eq!(math.total_lp_supply(), 2_000_000);
+    assert_eq!((math.fee_rate(), math.status()), (30, 0));
+}
+
+#[test]
+fn quotes_match_the_program() {
+    let pool = pool(1_000_000_000, 4_000_000_000, 2_000_000_000);
+    let math = PoolMath::new(&account_data(&pool)).unwrap();
+
+    for token_a_to_b in [true, false] {
+        let expected = calculate_swap_quote(&pool, 12_345_678, token_a_to_b, 0).unwrap();
+
+        assert_eq!(
+            math.quote_swap(12_345_678, token_a_to_b, 0).unwrap(),
+            SwapQuote {
+                amount_out: expected.amount_out,
+                fee_amount: expected.fee_amount,
+                price_impact_bps: expected.price_impact_bps,
+            }
+        );
+    }
+
+    let lp_tokens = calculate_add_liquidity_quote(&pool, 100_000_000, 400_000_000).unwrap();
+    assert_eq!(math.quote_add_liquidity(100_000_000, 400_000_000).unwrap().lp_tokens, lp_tokens);
+
+    let expected = calculate_remove_liquidity_quote(&pool, 500_000_000).unwrap();
+    assert_eq!(
+        math.quote_remove_liquidity(500_000_000).unwrap(),
+        WithdrawalQuote {
+            amount_a: expected.amount_a,
+            amount_b: expected.amount_b,
+        }
+    );
+}
+
+#[test]
+fn deposit_quotes_preview_the_pool_share() {
+    // Matching the reserves one to one mints a tenth of the current supply
+    let math = PoolMath::new(&account_data(&pool(1_000_000, 4_000_000, 2_000_000))).unwrap();
+    assert_eq!(
+        math.quote_add_liquidity(100_000, 400_000).unwrap(),
+        DepositQuote {
+            lp_tokens: 200_000,
+            pool_share_bps: 909,
+        }
+    );
+
+    // The first depositor owns everything except the locked minimum liquidity
+    let math = PoolMath::new(&account_data(&pool(0, 0, 0))).unwrap();
+    let quote = math.quote_add_liquidity(1_000_000, 1_000_000).unwrap();
+
+    assert_eq!(quote.lp_tokens, 1_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(quote.pool_share_bps, 9_990);
+}
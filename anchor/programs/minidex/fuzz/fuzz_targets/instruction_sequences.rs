@@ -0,0 +1,210 @@
+//! Random sequences of pool creation, deposits, withdrawals and swaps by
+//! several users, executed on the LiteSVM harness. Global invariants are
+//! checked after every step.
+
+#![no_main]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_spl::associated_token::get_associated_token_address;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use minidex_sdk::constants::{MAX_FEE_RATE, MINIMUM_LIQUIDITY};
+use minidex_sdk::math::U256;
+use minidex_sdk::{instructions, Pool, PoolKeys};
+use minidex_svm::Svm;
+
+const USERS: usize = 3;
+const INITIAL_BALANCE: u64 = 1_000_000_000_000;
+const LAMPORTS_PER_USER: u64 = 10_000_000_000;
+
+/// Token amounts are taken modulo what the user holds plus one, so most
+/// steps are affordable while zero and "everything" stay reachable.
+#[derive(Arbitrary, Debug)]
+enum Step {
+    InitializePool { fee_rate: u16 },
+    AddLiquidity { user: u8, amount_a: u64, amount_b: u64 },
+    RemoveLiquidity { user: u8, lp_tokens: u64 },
+    Swap { user: u8, amount_in: u64, token_a_to_b: bool },
+    WarpSlots { slots: u8 },
+}
+
+struct User {
+    key: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    lp: Pubkey,
+}
+
+struct Harness {
+    svm: Svm,
+    authority: Pubkey,
+    keys: PoolKeys,
+    users: Vec<User>,
+    /// Reserves product and LP supply after the last step that left liquidity in the pool
+    last_share_value: Option<(u128, u64)>,
+}
+
+fn pick(raw: u64, available: u64) -> u64 {
+    raw % available.saturating_add(1)
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut svm = Svm::new();
+        let authority = Pubkey::new_unique();
+        svm.airdrop(&authority, LAMPORTS_PER_USER);
+
+        let mint_a = svm.create_mint(&authority, 6);
+        let mint_b = svm.create_mint(&authority, 6);
+        let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+        let users = (0..USERS)
+            .map(|_| {
+                let key = Pubkey::new_unique();
+                svm.airdrop(&key, LAMPORTS_PER_USER);
+
+                User {
+                    key,
+                    token_a: svm.create_associated_token_account(&mint_a, &key, INITIAL_BALANCE),
+                    token_b: svm.create_associated_token_account(&mint_b, &key, INITIAL_BALANCE),
+                    lp: get_associated_token_address(&key, &keys.lp_mint),
+                }
+            })
+            .collect();
+
+        Self {
+            svm,
+            authority,
+            keys,
+            users,
+            last_share_value: None,
+        }
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signer: Pubkey) {
+        if let Err(failed) = self.svm.process_transaction(instructions, &[signer]) {
+            // Program and token errors are expected, runtime violations and
+            // anything else point at a bug
+            assert!(
+                matches!(failed.error, InstructionError::Custom(_)),
+                "unexpected {:?}: {:#?}",
+                failed.error,
+                failed.logs
+            );
+        }
+    }
+
+    fn step(&mut self, step: Step) {
+        match step {
+            Step::InitializePool { fee_rate } => {
+                let fee_rate = fee_rate % (MAX_FEE_RATE + 1);
+                let ixs = instructions::create_pool(&self.authority, &self.keys, fee_rate);
+                self.send(&ixs, self.authority);
+            }
+            Step::AddLiquidity { user, amount_a, amount_b } => {
+                let user = &self.users[user as usize % USERS];
+                let ix = instructions::add_liquidity(
+                    &user.key,
+                    &self.keys,
+                    &user.token_a,
+                    &user.token_b,
+                    pick(amount_a, self.svm.token_balance(&user.token_a)),
+                    pick(amount_b, self.svm.token_balance(&user.token_b)),
+                    0,
+                    None,
+                );
+                let signer = user.key;
+                self.send(&[ix], signer);
+            }
+            Step::RemoveLiquidity { user, lp_tokens } => {
+                let user = &self.users[user as usize % USERS];
+                let ix = instructions::remove_liquidity(
+                    &user.key,
+                    &self.keys,
+                    &user.token_a,
+                    &user.token_b,
+                    pick(lp_tokens, self.svm.token_balance(&user.lp)),
+                    0,
+                    0,
+                    None,
+                );
+                let signer = user.key;
+                self.send(&[ix], signer);
+            }
+            Step::Swap { user, amount_in, token_a_to_b } => {
+                let user = &self.users[user as usize % USERS];
+                let source = if token_a_to_b { user.token_a } else { user.token_b };
+                let ix = instructions::swap_tokens(
+                    &user.key,
+                    &self.keys,
+                    &user.token_a,
+                    &user.token_b,
+                    pick(amount_in, self.svm.token_balance(&source)),
+                    0,
+                    token_a_to_b,
+                    None,
+                );
+                let signer = user.key;
+                self.send(&[ix], signer);
+            }
+            Step::WarpSlots { slots } => {
+                let slot = self.svm.clock().slot + slots as u64;
+                self.svm.warp_to_slot(slot);
+            }
+        }
+    }
+
+    fn check_invariants(&mut self) {
+        let Some(pool) = self.svm.anchor_account::<Pool>(&self.keys.pool) else {
+            return;
+        };
+
+        // Vaults always cover the reserves
+        let vault_a = self.svm.token_balance(&self.keys.token_a_vault);
+        let vault_b = self.svm.token_balance(&self.keys.token_b_vault);
+        assert!(vault_a >= pool.reserve_a, "vault A {vault_a} < reserve {}", pool.reserve_a);
+        assert!(vault_b >= pool.reserve_b, "vault B {vault_b} < reserve {}", pool.reserve_b);
+
+        // Only the locked minimum liquidity is counted without being minted
+        let lp_supply = self.svm.mint(&self.keys.lp_mint).expect("LP mint exists").supply;
+        let locked = if pool.total_lp_supply == 0 { 0 } else { MINIMUM_LIQUIDITY };
+        assert_eq!(lp_supply + locked, pool.total_lp_supply);
+
+        let lp_held: u64 = self.users.iter().map(|user| self.svm.token_balance(&user.lp)).sum();
+        assert_eq!(lp_held, lp_supply);
+
+        // No tokens are created or lost
+        let held_a: u64 = self.users.iter().map(|user| self.svm.token_balance(&user.token_a)).sum();
+        let held_b: u64 = self.users.iter().map(|user| self.svm.token_balance(&user.token_b)).sum();
+        assert_eq!(held_a + vault_a, INITIAL_BALANCE * USERS as u64);
+        assert_eq!(held_b + vault_b, INITIAL_BALANCE * USERS as u64);
+
+        // Nobody extracts value beyond fees: the pool's value per LP token,
+        // measured as sqrt(reserve_a * reserve_b) / total_lp_supply, never drops
+        if pool.total_lp_supply > 0 {
+            let k = pool.reserve_a as u128 * pool.reserve_b as u128;
+            let supply = pool.total_lp_supply as u128;
+
+            if let Some((k_before, supply_before)) = self.last_share_value {
+                let supply_before = supply_before as u128;
+                assert!(
+                    U256::from(k) * U256::from(supply_before * supply_before)
+                        >= U256::from(k_before) * U256::from(supply * supply),
+                    "value per LP token dropped: k {k_before} -> {k}, supply {supply_before} -> {supply}"
+                );
+            }
+
+            self.last_share_value = Some((k, pool.total_lp_supply));
+        }
+    }
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let mut harness = Harness::new();
+
+    for step in steps {
+        harness.step(step);
+        harness.check_invariants();
+    }
+});
@@ -1,4 +1,26 @@
-use anchor_lang::prelude::*;
-
 pub const MAX_FEE_RATE: u16 = 1000;
 pub const MINIMUM_LIQUIDITY: u64 = 1000;
+
+// Pool status flags, each one independently pauses a class of instructions.
+pub const POOL_SWAPS_PAUSED: u8 = 1 << 0;
+pub const POOL_DEPOSITS_PAUSED: u8 = 1 << 1;
+pub const POOL_WITHDRAWALS_PAUSED: u8 = 1 << 2;
+pub const POOL_STATUS_MASK: u8 = POOL_SWAPS_PAUSED | POOL_DEPOSITS_PAUSED | POOL_WITHDRAWALS_PAUSED;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Dynamic fees
+/// Slots after which the volatility accumulator has halved, about a minute.
+pub const VOLATILITY_HALF_LIFE_SLOTS: u64 = 150;
+/// Cap on the volatility accumulator, in bps of price movement.
+pub const MAX_VOLATILITY_ACCUMULATOR: u64 = 1_000_000;
+
+// Fee discounts
+pub const MAX_FEE_DISCOUNT_TIERS: usize = 4;
+/// Slots staked governance tokens stay locked after the last stake, about a day.
+pub const GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS: u64 = 216_000;
+
+// Farming
+pub const MAX_FARM_REWARDS: usize = 3;
+/// Fixed-point scale of the farms' reward-per-share accumulators.
+pub const REWARD_PER_SHARE_SCALE: u128 = 1_000_000_000_000_000_000;
@@ -26,4 +26,58 @@ pub enum MinidexError {
     ZeroSwapAmount,
     #[msg("Insufficient user balance")]
     InsufficientUserBalance,
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+    #[msg("Invalid pool status flags")]
+    InvalidPoolStatus,
+    #[msg("Swaps are paused for this pool")]
+    SwapsPaused,
+    #[msg("Deposits are paused for this pool")]
+    DepositsPaused,
+    #[msg("Withdrawals are paused for this pool")]
+    WithdrawalsPaused,
+    #[msg("Invalid price limit")]
+    InvalidPriceLimit,
+    #[msg("Swap exceeds the pool's maximum price impact")]
+    PriceImpactExceeded,
+    #[msg("Swap exceeds the pool's maximum price move for this slot")]
+    SlotPriceMoveExceeded,
+    #[msg("Invalid reward emission schedule")]
+    InvalidRewardSchedule,
+    #[msg("Farm has no free reward slot")]
+    TooManyFarmRewards,
+    #[msg("Invalid reward index")]
+    InvalidRewardIndex,
+    #[msg("Insufficient staked LP tokens")]
+    InsufficientStake,
+    #[msg("The pool does not pay the order's limit price")]
+    LimitPriceNotReached,
+    #[msg("Invalid DCA schedule")]
+    InvalidDcaSchedule,
+    #[msg("The next DCA slice is not due yet")]
+    DcaSliceNotDue,
+    #[msg("Invalid referral fee")]
+    InvalidReferralFee,
+    #[msg("Referral fee exceeds the pool's maximum")]
+    ReferralFeeTooHigh,
+    #[msg("A referral fee needs a referrer token account")]
+    MissingReferrer,
+    #[msg("Invalid fee discount schedule")]
+    InvalidFeeDiscounts,
+    #[msg("Wallet is not on the pool's allowlist")]
+    NotAllowlisted,
+    #[msg("Mint is not in the token registry")]
+    MintNotRegistered,
+    #[msg("Mint has a freeze authority")]
+    MintHasFreezeAuthority,
+    #[msg("Pool still has liquidity")]
+    PoolNotEmpty,
+    #[msg("Pool already has the current layout")]
+    PoolAlreadyMigrated,
+    #[msg("The DCA schedule has run all its slices")]
+    DcaCompleted,
+    #[msg("The DCA schedule still has slices to run")]
+    DcaNotCompleted,
+    #[msg("Staked tokens are locked until the unstake cooldown ends")]
+    StakeLocked,
 }
\ No newline at end of file
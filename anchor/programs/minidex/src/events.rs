@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+// Every event carries the pool's reserves and LP supply after the instruction,
+// so the pool history can be rebuilt from the logs alone.
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub token_a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    /// Default when the swap had no referrer
+    pub referrer: Pubkey,
+    /// Part of `fee_amount` paid to the referrer, in the input token
+    pub referral_amount: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub total_lp_supply: u64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddLiquidityEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub total_lp_supply: u64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoveLiquidityEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub lp_tokens: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub total_lp_supply: u64,
+}
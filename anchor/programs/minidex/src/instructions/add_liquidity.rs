@@ -5,11 +5,16 @@ use anchor_spl::associated_token::AssociatedToken;
 
 use crate::state::*;
 use crate::errors::*;
+use crate::events::AddLiquidityEvent;
 use crate::constants::*;
+use crate::utils::*;
+use crate::math::*;
 
 pub fn liquidity_add(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64, min_lp_tokens: u64) -> Result<()> {
 
     require!(amount_a > 0 && amount_b > 0, MinidexError::ZeroAmount);
+    require!(!ctx.accounts.pool.deposits_paused(), MinidexError::DepositsPaused);
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
 
     let pool_account_info = ctx.accounts.pool.to_account_info();
 
@@ -19,32 +24,7 @@ pub fn liquidity_add(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64, m
     let token_b_mint = pool.token_b_mint;
     let bump = pool.bump;
 
-    let lp_tokens = if pool.total_lp_supply == 0 {
-        let lp_amount = ((amount_a as u128)
-            .checked_mul(amount_b as u128)
-            .ok_or(MinidexError::MathOverflow)?)
-            .integer_sqrt() as u64;
-
-        lp_amount
-        .checked_sub(MINIMUM_LIQUIDITY)
-        .ok_or(MinidexError::InsufficientLiquidity)?
-    } 
-    else {
-        let lp_from_a = (amount_a as u128)
-            .checked_mul(pool.total_lp_supply as u128)
-            .ok_or(MinidexError::MathOverflow)?
-            .checked_div(pool.reserve_a as u128)
-            .ok_or(MinidexError::MathOverflow)? as u64;
-
-        let lp_from_b = (amount_b as u128)
-            .checked_mul(pool.total_lp_supply as u128)
-            .ok_or(MinidexError::MathOverflow)?
-            .checked_div(pool.reserve_b as u128)
-            .ok_or(MinidexError::MathOverflow)? as u64;
-
-        std::cmp::min(lp_from_a, lp_from_b)
-
-    };
+    let lp_tokens = calculate_lp_tokens(pool, amount_a, amount_b)?;
     require!(lp_tokens >= min_lp_tokens, MinidexError::SlippageExceeded);
     require!(lp_tokens > 0, MinidexError::ZeroLPTokens);
 
@@ -93,44 +73,32 @@ pub fn liquidity_add(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64, m
         lp_tokens,
     )?;
 
-    pool.reserve_a = pool.reserve_a.checked_add(amount_a).ok_or(MinidexError::MathOverflow)?;
-    pool.reserve_b = pool.reserve_b.checked_add(amount_b).ok_or(MinidexError::MathOverflow)?;
+    pool.reserve_a = pool.reserve_a.safe_add(amount_a)?;
+    pool.reserve_b = pool.reserve_b.safe_add(amount_b)?;
 
     let total_lp_increase = if pool.total_lp_supply == 0 {
-        lp_tokens.checked_add(MINIMUM_LIQUIDITY).ok_or(MinidexError::MathOverflow)?
+        lp_tokens.safe_add(MINIMUM_LIQUIDITY)?
     } else {
         lp_tokens
     };
 
-    pool.total_lp_supply = pool.total_lp_supply.checked_add(total_lp_increase).ok_or(MinidexError::MathOverflow)?;
+    pool.total_lp_supply = pool.total_lp_supply.safe_add(total_lp_increase)?;
 
+    emit!(AddLiquidityEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        amount_a,
+        amount_b,
+        lp_tokens,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
 
     Ok(())
 }
 
 
-trait IntegerSqrt {
-    fn integer_sqrt(self) -> Self;
-}
-
-impl IntegerSqrt for u128 {
-    fn integer_sqrt(self) -> Self {
-        if self < 2 {
-            return self;
-        }
-        
-        let mut x = self;
-        let mut y = (self + 1) / 2;
-        
-        while y < x {
-            x = y;
-            y = (y + self / y) / 2;
-        }
-        
-        x
-    }
-}
-
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
     #[account(mut)]
@@ -141,21 +109,21 @@ pub struct AddLiquidity<'info> {
         seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
         bump = pool.bump,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         mut,
         token::mint = pool.token_a_mint,
         token::authority = user,
     )]
-    pub user_token_a_account: Account<'info, TokenAccount>,
+    pub user_token_a_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         token::mint = pool.token_b_mint,
         token::authority = user,
     )]
-    pub user_token_b_account: Account<'info, TokenAccount>,
+    pub user_token_b_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
@@ -163,25 +131,32 @@ pub struct AddLiquidity<'info> {
         associated_token::mint = lp_mint,
         associated_token::authority = user,
     )]
-    pub user_lp_account: Account<'info, TokenAccount>,
+    pub user_lp_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         address = pool.token_a_vault
     )]
-    pub token_a_vault: Account<'info, TokenAccount>,
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         address = pool.token_b_vault
     )]
-    pub token_b_vault: Account<'info, TokenAccount>,
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         address = pool.lp_mint
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
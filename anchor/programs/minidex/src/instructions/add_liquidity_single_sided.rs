@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer, mint_to};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::*;
+use crate::errors::*;
+use crate::events::{AddLiquidityEvent, SwapEvent};
+use crate::utils::*;
+use crate::math::*;
+
+pub fn liquidity_add_single_sided(ctx: Context<AddLiquiditySingleSided>, amount_in: u64, token_is_a: bool, min_lp_tokens: u64) -> Result<()> {
+
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+
+    let pool = &mut ctx.accounts.pool;
+
+    let token_a_mint = pool.token_a_mint;
+    let token_b_mint = pool.token_b_mint;
+    let bump = pool.bump;
+
+    let current_slot = Clock::get()?.slot;
+    let quote = calculate_single_sided_deposit_quote(pool, amount_in, token_is_a, current_slot)?;
+    require!(quote.lp_tokens >= min_lp_tokens, MinidexError::SlippageExceeded);
+
+    let (user_token_account, token_vault) = if token_is_a {
+        (&ctx.accounts.user_token_a_account, &ctx.accounts.token_a_vault)
+    } else {
+        (&ctx.accounts.user_token_b_account, &ctx.accounts.token_b_vault)
+    };
+
+    require!(user_token_account.amount >= amount_in, MinidexError::InsufficientUserBalance);
+
+    // The internal swap counts toward the per-slot price move like any other
+    if pool.last_trade_slot != current_slot {
+        pool.last_trade_slot = current_slot;
+        pool.slot_start_reserve_a = pool.reserve_a;
+        pool.slot_start_reserve_b = pool.reserve_b;
+    }
+
+    // Only the input token moves, the swap output never leaves the vault
+    let cpi_accounts = Transfer {
+        from: user_token_account.to_account_info(),
+        to: token_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount_in,
+    )?;
+
+    let seeds = &[
+        b"pool",
+        token_a_mint.as_ref(),
+        token_b_mint.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts_lp = anchor_spl::token::MintTo {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        to: ctx.accounts.user_lp_account.to_account_info(),
+        authority: pool_account_info.clone(),
+    };
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_lp,
+            signer,
+        ),
+        quote.lp_tokens,
+    )?;
+
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(pool, quote.swap_amount, quote.swap_output, token_is_a)?;
+    record_swap_volatility(pool, reserve_a, reserve_b, current_slot)?;
+    pool.reserve_a = reserve_a;
+    pool.reserve_b = reserve_b;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        token_a_to_b: token_is_a,
+        amount_in: quote.swap_amount,
+        amount_out: quote.swap_output,
+        fee_amount: quote.fee_amount,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    let deposit = amount_in.safe_sub(quote.swap_amount)?;
+    let (amount_a, amount_b) = if token_is_a { (deposit, quote.swap_output) } else { (quote.swap_output, deposit) };
+
+    pool.reserve_a = pool.reserve_a.safe_add(amount_a)?;
+    pool.reserve_b = pool.reserve_b.safe_add(amount_b)?;
+    pool.total_lp_supply = pool.total_lp_supply.safe_add(quote.lp_tokens)?;
+
+    emit!(AddLiquidityEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        amount_a,
+        amount_b,
+        lp_tokens: quote.lp_tokens,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddLiquiditySingleSided<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        token::authority = user,
+    )]
+    pub user_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        token::authority = user,
+    )]
+    pub user_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user,
+    )]
+    pub user_lp_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.token_a_vault
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.token_b_vault
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.lp_mint
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
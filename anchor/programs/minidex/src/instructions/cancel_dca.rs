@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{CloseAccount, Token, TokenAccount, Transfer, close_account, transfer};
+
+use crate::state::Dca;
+use crate::errors::*;
+
+pub fn dca_cancel(ctx: Context<CancelDca>) -> Result<()> {
+    let dca_account_info = ctx.accounts.dca.to_account_info();
+    let dca = &ctx.accounts.dca;
+
+    let dca_id = dca.dca_id.to_le_bytes();
+    let seeds = &[
+        b"dca",
+        dca.pool.as_ref(),
+        dca.owner.as_ref(),
+        dca_id.as_ref(),
+        &[dca.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Whatever has not been swapped yet goes back
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: dca_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+        ctx.accounts.escrow.amount,
+    )?;
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.user.to_account_info(),
+        authority: dca_account_info.clone(),
+    };
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts_close,
+        signer,
+    ))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelDca<'info> {
+    /// Owner of the schedule
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = escrow,
+        constraint = dca.owner == user.key() @ MinidexError::Unauthorized,
+    )]
+    pub dca: Account<'info, Dca>,
+
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Receives the input not swapped yet
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
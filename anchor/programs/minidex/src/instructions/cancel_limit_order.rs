@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{CloseAccount, Token, TokenAccount, Transfer, close_account, transfer};
+
+use crate::state::LimitOrder;
+use crate::errors::*;
+
+pub fn limit_order_cancel(ctx: Context<CancelLimitOrder>) -> Result<()> {
+    let order_account_info = ctx.accounts.order.to_account_info();
+    let order = &ctx.accounts.order;
+
+    let order_id = order.order_id.to_le_bytes();
+    let seeds = &[
+        b"limit_order",
+        order.pool.as_ref(),
+        order.maker.as_ref(),
+        order_id.as_ref(),
+        &[order.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Everything in escrow, the input and the unpaid keeper fee, goes back
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: order_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+        ctx.accounts.escrow.amount,
+    )?;
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.user.to_account_info(),
+        authority: order_account_info.clone(),
+    };
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts_close,
+        signer,
+    ))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    /// Maker
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = escrow,
+        constraint = order.maker == user.key() @ MinidexError::Unauthorized,
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Receives the escrowed tokens
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
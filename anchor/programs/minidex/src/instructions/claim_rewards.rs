@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Farm, FarmPosition};
+use crate::constants::*;
+use crate::errors::*;
+use crate::utils::*;
+
+pub fn rewards_claim(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+    let index = reward_index as usize;
+    require!(index < MAX_FARM_REWARDS, MinidexError::InvalidRewardIndex);
+
+    let now = Clock::get()?.unix_timestamp;
+    let farm_account_info = ctx.accounts.farm.to_account_info();
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+
+    require!(farm.rewards[index].mint != Pubkey::default(), MinidexError::InvalidRewardIndex);
+    require!(ctx.accounts.reward_vault.key() == farm.rewards[index].vault, MinidexError::InvalidRewardIndex);
+
+    update_farm_rewards(farm, now)?;
+    settle_position_rewards(farm, position)?;
+
+    let amount = position.rewards[index].pending;
+    if amount == 0 {
+        return Ok(());
+    }
+    position.rewards[index].pending = 0;
+
+    let seeds = &[
+        b"farm",
+        farm.pool.as_ref(),
+        &[farm.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.user_reward_account.to_account_info(),
+        authority: farm_account_info,
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool.as_ref()],
+        bump = farm.bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [b"farm_position", farm.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    /// Vault of the claimed reward, `farm.rewards[reward_index].vault`
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        token::authority = user,
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{CloseAccount, Token, TokenAccount, Transfer, close_account, transfer};
+
+use crate::state::Dca;
+use crate::errors::*;
+
+pub fn dca_close(ctx: Context<CloseDca>) -> Result<()> {
+    let dca_account_info = ctx.accounts.dca.to_account_info();
+    let dca = &ctx.accounts.dca;
+
+    let dca_id = dca.dca_id.to_le_bytes();
+    let seeds = &[
+        b"dca",
+        dca.pool.as_ref(),
+        dca.owner.as_ref(),
+        dca_id.as_ref(),
+        &[dca.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Every slice has been swapped, so only tokens sent to the escrow since are left
+    let dust = ctx.accounts.escrow.amount;
+
+    if dust > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow.to_account_info(),
+            to: ctx.accounts.owner_refund_account.to_account_info(),
+            authority: dca_account_info.clone(),
+        };
+
+        transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+            dust,
+        )?;
+    }
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.owner.to_account_info(),
+        authority: dca_account_info.clone(),
+    };
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts_close,
+        signer,
+    ))?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseDca<'info> {
+    /// Anyone may close a schedule once its last slice has run
+    pub cranker: Signer<'info>,
+
+    /// Its rent goes back to the owner
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = escrow,
+        constraint = dca.slices_executed == dca.slice_count @ MinidexError::DcaNotCompleted,
+    )]
+    pub dca: Account<'info, Dca>,
+
+    /// CHECK: Only receives the rent of the schedule and its escrow, checked against `dca.owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Receives anything sent to the escrow after the last slice
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = owner,
+    )]
+    pub owner_refund_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{CloseAccount, Mint, Token, TokenAccount, Transfer, close_account, transfer};
+
+use crate::state::Pool;
+use crate::errors::*;
+use crate::constants::*;
+
+/// Closes a pool nobody holds LP tokens of anymore. What the locked minimum
+/// liquidity still backs goes to the authority with the vaults' and the pool's
+/// rent. The LP mint stays, SPL Token mints cannot be closed, and
+/// `initialize_lp_mint` takes it over when the pool is created again.
+pub fn pool_close(ctx: Context<ClosePool>) -> Result<()> {
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let pool = &ctx.accounts.pool;
+
+    require!(
+        ctx.accounts.lp_mint.supply == 0 && pool.total_lp_supply <= MINIMUM_LIQUIDITY,
+        MinidexError::PoolNotEmpty
+    );
+
+    let seeds = &[
+        b"pool",
+        pool.token_a_mint.as_ref(),
+        pool.token_b_mint.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let vaults = [
+        (&ctx.accounts.token_a_vault, &ctx.accounts.authority_token_a_account),
+        (&ctx.accounts.token_b_vault, &ctx.accounts.authority_token_b_account),
+    ];
+
+    for (vault, authority_token_account) in vaults {
+        // A vault can only be closed once it is empty
+        let cpi_accounts = Transfer {
+            from: vault.to_account_info(),
+            to: authority_token_account.to_account_info(),
+            authority: pool_account_info.clone(),
+        };
+
+        transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer),
+            vault.amount,
+        )?;
+
+        let cpi_accounts_close = CloseAccount {
+            account: vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: pool_account_info.clone(),
+        };
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_close,
+            signer,
+        ))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    /// Pool authority, receives the rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized,
+        close = authority
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        address = pool.token_a_vault
+    )]
+    pub token_a_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.token_b_vault
+    )]
+    pub token_b_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        address = pool.lp_mint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Receives what is left in vault A
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        token::authority = authority,
+    )]
+    pub authority_token_a_account: Account<'info, TokenAccount>,
+
+    /// Receives what is left in vault B
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        token::authority = authority,
+    )]
+    pub authority_token_b_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Config, RegisteredToken};
+use crate::errors::*;
+
+/// Closes the registration, returning its rent to the admin. Existing pools
+/// with the mint are not affected.
+pub fn token_deregister(_ctx: Context<DeregisterToken>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeregisterToken<'info> {
+    /// Config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MinidexError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"registered_token", registered_token.mint.as_ref()],
+        bump = registered_token.bump,
+        close = admin
+    )]
+    pub registered_token: Account<'info, RegisteredToken>,
+}
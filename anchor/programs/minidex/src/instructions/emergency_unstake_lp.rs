@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Farm, FarmPosition, PositionReward};
+use crate::constants::*;
+use crate::errors::*;
+use crate::math::*;
+
+/// Withdraws the whole stake without touching the reward accounting, so LP
+/// tokens can always leave the farm even if updating rewards fails. Pending
+/// rewards are forfeited.
+pub fn lp_emergency_unstake(ctx: Context<EmergencyUnstakeLp>) -> Result<()> {
+    let farm_account_info = ctx.accounts.farm.to_account_info();
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+
+    let amount = position.staked;
+    require!(amount > 0, MinidexError::InsufficientStake);
+
+    position.staked = 0;
+    position.rewards = [PositionReward::default(); MAX_FARM_REWARDS];
+    farm.total_staked = farm.total_staked.safe_sub(amount)?;
+
+    let seeds = &[
+        b"farm",
+        farm.pool.as_ref(),
+        &[farm.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.lp_vault.to_account_info(),
+        to: ctx.accounts.user_lp_account.to_account_info(),
+        authority: farm_account_info,
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EmergencyUnstakeLp<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool.as_ref()],
+        bump = farm.bump,
+        has_one = lp_vault
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [b"farm_position", farm.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        token::mint = lp_vault.mint,
+        token::authority = user,
+    )]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Dca, Pool, PoolPermission};
+use crate::errors::*;
+use crate::events::SwapEvent;
+use crate::utils::*;
+
+pub fn dca_slice_execute(ctx: Context<ExecuteDcaSlice>) -> Result<()> {
+    // Slices trade on the owner's behalf, so they need the owner's access
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let dca_account_info = ctx.accounts.dca.to_account_info();
+
+    let pool = &mut ctx.accounts.pool;
+    let dca = &mut ctx.accounts.dca;
+
+    let clock = Clock::get()?;
+    require!(dca.slices_executed < dca.slice_count, MinidexError::DcaCompleted);
+    require!(clock.unix_timestamp >= dca.next_slice_time, MinidexError::DcaSliceNotDue);
+
+    let token_a_to_b = dca.sell_token_a;
+    let amount_in = dca.amount_per_slice;
+
+    let quote = calculate_swap_quote(pool, amount_in, token_a_to_b, clock.slot)?;
+    require!(quote.amount_out >= dca.min_amount_out_per_slice, MinidexError::SlippageExceeded);
+
+    let (reserve_a_after, reserve_b_after) = calculate_reserves_after_swap(pool, amount_in, quote.amount_out, token_a_to_b)?;
+
+    if pool.last_trade_slot != clock.slot {
+        pool.last_trade_slot = clock.slot;
+        pool.slot_start_reserve_a = pool.reserve_a;
+        pool.slot_start_reserve_b = pool.reserve_b;
+    }
+
+    let (vault_in, vault_out) = if token_a_to_b {
+        (&ctx.accounts.token_a_vault, &ctx.accounts.token_b_vault)
+    } else {
+        (&ctx.accounts.token_b_vault, &ctx.accounts.token_a_vault)
+    };
+
+    let pool_seeds = &[
+        b"pool",
+        pool.token_a_mint.as_ref(),
+        pool.token_b_mint.as_ref(),
+        &[pool.bump],
+    ];
+    let pool_signer = &[&pool_seeds[..]];
+
+    let (dca_pool, dca_owner, dca_id, dca_bump) = (dca.pool, dca.owner, dca.dca_id.to_le_bytes(), dca.bump);
+    let dca_seeds = &[
+        b"dca",
+        dca_pool.as_ref(),
+        dca_owner.as_ref(),
+        dca_id.as_ref(),
+        &[dca_bump],
+    ];
+    let dca_signer = &[&dca_seeds[..]];
+
+    let cpi_accounts_in = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: vault_in.to_account_info(),
+        authority: dca_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_in, dca_signer),
+        amount_in,
+    )?;
+
+    let cpi_accounts_out = Transfer {
+        from: vault_out.to_account_info(),
+        to: ctx.accounts.owner_receive_account.to_account_info(),
+        authority: pool_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_out, pool_signer),
+        quote.amount_out,
+    )?;
+
+    record_swap_volatility(pool, reserve_a_after, reserve_b_after, clock.slot)?;
+    pool.reserve_a = reserve_a_after;
+    pool.reserve_b = reserve_b_after;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        user: dca.owner,
+        token_a_to_b,
+        amount_in,
+        amount_out: quote.amount_out,
+        fee_amount: quote.fee_amount,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    dca.slices_executed += 1;
+    // Measured from now, so a late crank does not make up missed slices in a burst
+    dca.next_slice_time = clock.unix_timestamp.saturating_add(dca.interval);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDcaSlice<'info> {
+    /// Anyone may run a slice once it is due
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner,
+        has_one = escrow,
+    )]
+    pub dca: Account<'info, Dca>,
+
+    /// CHECK: Only identifies the schedule's owner, checked against `dca.owner`
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = dca.receive_account
+    )]
+    pub owner_receive_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.token_a_vault
+    )]
+    pub token_a_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.token_b_vault
+    )]
+    pub token_b_vault: Account<'info, TokenAccount>,
+
+    /// The owner's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), owner.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{CloseAccount, Token, TokenAccount, Transfer, close_account, transfer};
+
+use crate::state::{LimitOrder, Pool, PoolPermission};
+use crate::errors::*;
+use crate::events::SwapEvent;
+use crate::utils::*;
+
+pub fn limit_order_fill(ctx: Context<FillLimitOrder>) -> Result<()> {
+    // Fills trade on the maker's behalf, so they need the maker's access
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let order_account_info = ctx.accounts.order.to_account_info();
+
+    let pool = &mut ctx.accounts.pool;
+    let order = &ctx.accounts.order;
+
+    let token_a_to_b = order.sell_token_a;
+    let amount_in = order.amount_in;
+
+    // Anyone can send tokens to the escrow, and it only closes once empty
+    let dust = ctx.accounts.escrow.amount
+        .checked_sub(amount_in)
+        .and_then(|amount| amount.checked_sub(order.keeper_fee))
+        .ok_or(MinidexError::MathOverflow)?;
+
+    let current_slot = Clock::get()?.slot;
+    let quote = calculate_swap_quote(pool, amount_in, token_a_to_b, current_slot)?;
+    require!(quote.amount_out >= order.min_amount_out, MinidexError::LimitPriceNotReached);
+
+    let (reserve_a_after, reserve_b_after) = calculate_reserves_after_swap(pool, amount_in, quote.amount_out, token_a_to_b)?;
+
+    if pool.last_trade_slot != current_slot {
+        pool.last_trade_slot = current_slot;
+        pool.slot_start_reserve_a = pool.reserve_a;
+        pool.slot_start_reserve_b = pool.reserve_b;
+    }
+
+    let (vault_in, vault_out) = if token_a_to_b {
+        (&ctx.accounts.token_a_vault, &ctx.accounts.token_b_vault)
+    } else {
+        (&ctx.accounts.token_b_vault, &ctx.accounts.token_a_vault)
+    };
+
+    let pool_seeds = &[
+        b"pool",
+        pool.token_a_mint.as_ref(),
+        pool.token_b_mint.as_ref(),
+        &[pool.bump],
+    ];
+    let pool_signer = &[&pool_seeds[..]];
+
+    let order_id = order.order_id.to_le_bytes();
+    let order_seeds = &[
+        b"limit_order",
+        order.pool.as_ref(),
+        order.maker.as_ref(),
+        order_id.as_ref(),
+        &[order.bump],
+    ];
+    let order_signer = &[&order_seeds[..]];
+
+    // The escrowed input goes to the pool, the output straight to the maker
+    let cpi_accounts_in = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: vault_in.to_account_info(),
+        authority: order_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_in, order_signer),
+        amount_in,
+    )?;
+
+    let cpi_accounts_out = Transfer {
+        from: vault_out.to_account_info(),
+        to: ctx.accounts.maker_receive_account.to_account_info(),
+        authority: pool_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_out, pool_signer),
+        quote.amount_out,
+    )?;
+
+    if order.keeper_fee > 0 {
+        let cpi_accounts_fee = Transfer {
+            from: ctx.accounts.escrow.to_account_info(),
+            to: ctx.accounts.keeper_token_account.to_account_info(),
+            authority: order_account_info.clone(),
+        };
+
+        transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_fee, order_signer),
+            order.keeper_fee,
+        )?;
+    }
+
+    if dust > 0 {
+        let cpi_accounts_dust = Transfer {
+            from: ctx.accounts.escrow.to_account_info(),
+            to: ctx.accounts.maker_refund_account.to_account_info(),
+            authority: order_account_info.clone(),
+        };
+
+        transfer(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts_dust, order_signer),
+            dust,
+        )?;
+    }
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.maker.to_account_info(),
+        authority: order_account_info.clone(),
+    };
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts_close,
+        order_signer,
+    ))?;
+
+    record_swap_volatility(pool, reserve_a_after, reserve_b_after, current_slot)?;
+    pool.reserve_a = reserve_a_after;
+    pool.reserve_b = reserve_b_after;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        user: order.maker,
+        token_a_to_b,
+        amount_in,
+        amount_out: quote.amount_out,
+        fee_amount: quote.fee_amount,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    /// Anyone may fill an order once the pool pays its limit price
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// Closed once filled, its rent goes back to the maker
+    #[account(
+        mut,
+        close = maker,
+        has_one = pool,
+        has_one = maker,
+        has_one = escrow,
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    /// CHECK: Only receives the rent of the order and its escrow, checked against `order.maker`
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = order.receive_account
+    )]
+    pub maker_receive_account: Box<Account<'info, TokenAccount>>,
+
+    /// Receives anything in the escrow beyond the order's input and keeper fee
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = maker,
+    )]
+    pub maker_refund_account: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the keeper fee
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = keeper,
+    )]
+    pub keeper_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.token_a_vault
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.token_b_vault
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The maker's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), maker.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+}
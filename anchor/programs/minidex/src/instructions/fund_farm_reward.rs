@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Farm, FarmReward, Pool};
+use crate::errors::*;
+use crate::utils::*;
+use crate::math::*;
+
+pub fn reward_fund(ctx: Context<FundFarmReward>, start_time: i64, end_time: i64, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let reward_mint = ctx.accounts.reward_mint.key();
+    let farm = &mut ctx.accounts.farm;
+
+    update_farm_rewards(farm, now)?;
+
+    let index = farm
+        .rewards
+        .iter()
+        .position(|reward| reward.mint == reward_mint)
+        .or_else(|| farm.rewards.iter().position(|reward| reward.mint == Pubkey::default()))
+        .ok_or(MinidexError::TooManyFarmRewards)?;
+    let reward = &mut farm.rewards[index];
+
+    // Whatever the current schedule has yet to emit carries over into the new one
+    let undistributed = if reward.mint == reward_mint && now < reward.end_time {
+        let from = now.max(reward.start_time);
+        to_u64((reward.emission_rate as u128).safe_mul((reward.end_time - from) as u128)?)?
+    } else {
+        0
+    };
+
+    let start_time = start_time.max(now);
+    require!(end_time > start_time, MinidexError::InvalidRewardSchedule);
+
+    let duration = (end_time - start_time) as u64;
+    let emission_rate = amount.safe_add(undistributed)? / duration;
+    require!(emission_rate > 0, MinidexError::InvalidRewardSchedule);
+
+    // Only what the schedule emits is taken, so no remainder is stranded in the vault
+    let amount = emission_rate
+        .safe_mul(duration)?
+        .checked_sub(undistributed)
+        .ok_or(MinidexError::InvalidRewardSchedule)?;
+
+    if reward.mint != reward_mint {
+        *reward = FarmReward {
+            mint: reward_mint,
+            vault: ctx.accounts.reward_vault.key(),
+            last_update_time: now,
+            ..FarmReward::default()
+        };
+    }
+
+    reward.emission_rate = emission_rate;
+    reward.start_time = start_time;
+    reward.end_time = end_time;
+
+    if amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority_reward_account.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+
+        transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundFarmReward<'info> {
+    /// Pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// Farm of the pool
+    #[account(
+        mut,
+        seeds = [b"farm", pool.key().as_ref()],
+        bump = farm.bump,
+        has_one = pool
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// Mint of the reward token
+    pub reward_mint: Account<'info, Mint>,
+
+    /// Vault the farm pays this reward from
+    #[account(
+        init_if_needed,
+        seeds = [b"farm_reward_vault", farm.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = farm
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// Authority's account the rewards are paid in from
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+    )]
+    pub authority_reward_account: Account<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
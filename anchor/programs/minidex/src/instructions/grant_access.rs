@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolPermission};
+use crate::errors::*;
+
+pub fn access_grant(ctx: Context<GrantAccess>, user: Pubkey) -> Result<()> {
+    let permission = &mut ctx.accounts.permission;
+
+    permission.pool = ctx.accounts.pool.key();
+    permission.user = user;
+    permission.bump = ctx.bumps.permission;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct GrantAccess<'info> {
+    /// Pool authority, pays for the permission
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PoolPermission::INIT_SPACE,
+        seeds = [b"permission", pool.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub permission: Account<'info, PoolPermission>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::program::Minidex;
+use crate::state::{Config, FeeDiscountTier};
+use crate::errors::*;
+use crate::constants::*;
+
+pub fn config_initialize(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.admin = ctx.accounts.admin.key();
+    config.governance_mint = ctx.accounts.governance_mint.key();
+    config.bump = ctx.bumps.config;
+    config.fee_discount_tiers = [FeeDiscountTier::default(); MAX_FEE_DISCOUNT_TIERS];
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// The program's upgrade authority, becomes the config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub governance_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, Minidex>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ MinidexError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::state::{Farm, Pool};
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+    /// Pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Pool whose LP tokens the farm stakes
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized,
+        has_one = lp_mint
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// Farm PDA
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Farm::INIT_SPACE,
+        seeds = [b"farm", pool.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// LP mint of the pool
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Vault holding the staked LP tokens
+    #[account(
+        init,
+        seeds = [b"farm_lp_vault", farm.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = lp_mint,
+        token::authority = farm
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn farm_initialize(ctx: Context<InitializeFarm>) -> Result<()> {
+    let farm = &mut ctx.accounts.farm;
+
+    farm.pool = ctx.accounts.pool.key();
+    farm.lp_vault = ctx.accounts.lp_vault.key();
+    farm.total_staked = 0;
+    farm.bump = ctx.bumps.farm;
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 
 use crate::Pool;
+use crate::errors::*;
 
 #[derive(Accounts)]
 pub struct SetupLpMint<'info> {
@@ -17,9 +18,10 @@ pub struct SetupLpMint<'info> {
     )]
     pub pool: Account<'info, Pool>,
 
-    /// LP token mint PDA
+    /// LP token mint PDA. A closed pool leaves it behind, so creating the pool
+    /// again reuses it once the constraints confirm it is still the pool's
     #[account(
-        init,
+        init_if_needed,
         seeds = [b"lp_mint", pool.key().as_ref()],
         bump,
         payer = authority,
@@ -36,8 +38,11 @@ pub struct SetupLpMint<'info> {
 }
 
 pub fn initialize_lp(ctx: Context<SetupLpMint>) -> Result<()> {
+    // Only a mint nobody holds tokens of can back a fresh pool
+    require!(ctx.accounts.lp_mint.supply == 0, MinidexError::PoolNotEmpty);
+
     let pool = &mut ctx.accounts.pool;
-    
+
     pool.lp_mint = ctx.accounts.lp_mint.key();
 
     Ok(())
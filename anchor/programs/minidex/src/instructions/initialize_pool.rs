@@ -16,6 +16,13 @@ pub fn initialize(
     require!(fee_rate <= MAX_FEE_RATE, MinidexError::InvalidFeeRate);
     require!(ctx.accounts.token_a_mint.key() != ctx.accounts.token_b_mint.key(), MinidexError::IdenticalMints);
 
+    // The registry is optional, pools can pair any mints until the admin creates and enables it
+    let registry = &ctx.accounts.token_registry;
+    if !registry.data_is_empty() && TokenRegistry::try_deserialize(&mut &registry.try_borrow_data()?[..])?.enabled {
+        check_registered_mint(&ctx.accounts.token_a_mint, ctx.accounts.registered_token_a.as_deref())?;
+        check_registered_mint(&ctx.accounts.token_b_mint, ctx.accounts.registered_token_b.as_deref())?;
+    }
+
     let pool = &mut ctx.accounts.pool;
 
     pool.authority = ctx.accounts.authority.key();
@@ -29,6 +36,32 @@ pub fn initialize(
     pool.reserve_a = 0;
     pool.reserve_b = 0;
     pool.total_lp_supply = 0;
+    pool.guardian = Pubkey::default();
+    pool.status = 0;
+    pool.max_price_impact_bps = 0;
+    pool.max_slot_price_move_bps = 0;
+    pool.last_trade_slot = 0;
+    pool.slot_start_reserve_a = 0;
+    pool.slot_start_reserve_b = 0;
+    pool.max_referral_bps = 0;
+    pool.volatility_fee_multiplier = 0;
+    pool.min_fee_rate = 0;
+    pool.volatility_accumulator = 0;
+    pool.volatility_update_slot = 0;
+    pool.allowlist_enabled = false;
+    Ok(())
+}
+
+/// Pools only take SPL Token mints, which have no close authority or
+/// extensions, so the freeze authority is the one mint check left.
+fn check_registered_mint(mint: &Mint, registered: Option<&RegisteredToken>) -> Result<()> {
+    let registered = registered.ok_or(MinidexError::MintNotRegistered)?;
+
+    require!(
+        registered.skip_mint_checks || mint.freeze_authority.is_none(),
+        MinidexError::MintHasFreezeAuthority
+    );
+
     Ok(())
 }
 
@@ -52,6 +85,26 @@ pub struct InitializePool<'info> {
     )]
     pub pool: Account<'info, Pool>,
 
+    /// CHECK: the token registry PDA, read only when the admin has created it
+    #[account(
+        seeds = [b"token_registry"],
+        bump
+    )]
+    pub token_registry: UncheckedAccount<'info>,
+
+    /// Registrations of the two mints, only needed while the registry is enabled
+    #[account(
+        seeds = [b"registered_token", token_a_mint.key().as_ref()],
+        bump = registered_token_a.bump
+    )]
+    pub registered_token_a: Option<Account<'info, RegisteredToken>>,
+
+    #[account(
+        seeds = [b"registered_token", token_b_mint.key().as_ref()],
+        bump = registered_token_b.bump
+    )]
+    pub registered_token_b: Option<Account<'info, RegisteredToken>>,
+
     pub system_program: Program<'info, System>,
 
 }
\ No newline at end of file
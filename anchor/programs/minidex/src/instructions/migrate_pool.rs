@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::state::Pool;
+use crate::errors::*;
+
+/// Grows a pool created before fields were appended to `Pool` to the current
+/// layout. The appended fields start zeroed, which leaves every feature they
+/// control off. Anyone can pay the rent of the added space.
+pub fn pool_migrate(ctx: Context<MigratePool>) -> Result<()> {
+    let pool = ctx.accounts.pool.to_account_info();
+    let old_len = pool.data_len();
+    let new_len = Pool::DISCRIMINATOR.len() + Pool::INIT_SPACE;
+
+    require!(
+        pool.try_borrow_data()?.starts_with(Pool::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(old_len < new_len, MinidexError::PoolAlreadyMigrated);
+
+    let rent = Rent::get()?.minimum_balance(new_len).saturating_sub(pool.lamports());
+    if rent > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: pool.clone(),
+        };
+
+        transfer(CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts), rent)?;
+    }
+
+    pool.resize(new_len)?;
+    pool.try_borrow_mut_data()?[old_len..].fill(0);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: a pool in an older, shorter layout that `Account<Pool>` cannot
+    /// deserialize. The handler checks its discriminator
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -5,6 +5,41 @@ pub mod initialize_lp_mint;
 pub mod initialize_vault_a;
 pub mod initialize_vault_b;
 pub mod swap;
+pub mod set_pool_status;
+pub mod set_guardian;
+pub mod set_price_limits;
+pub mod quote_swap;
+pub mod quote_add_liquidity;
+pub mod quote_remove_liquidity;
+pub mod initialize_farm;
+pub mod fund_farm_reward;
+pub mod stake_lp;
+pub mod unstake_lp;
+pub mod emergency_unstake_lp;
+pub mod claim_rewards;
+pub mod add_liquidity_single_sided;
+pub mod remove_liquidity_single_sided;
+pub mod place_limit_order;
+pub mod fill_limit_order;
+pub mod cancel_limit_order;
+pub mod open_dca;
+pub mod execute_dca_slice;
+pub mod cancel_dca;
+pub mod close_dca;
+pub mod set_max_referral_bps;
+pub mod set_dynamic_fee;
+pub mod initialize_config;
+pub mod set_fee_discounts;
+pub mod stake_governance;
+pub mod unstake_governance;
+pub mod set_allowlist;
+pub mod grant_access;
+pub mod revoke_access;
+pub mod set_token_registry;
+pub mod register_token;
+pub mod deregister_token;
+pub mod close_pool;
+pub mod migrate_pool;
 
 pub use initialize_pool::*;
 pub use add_liquidity::*;
@@ -13,3 +48,38 @@ pub use initialize_lp_mint::*;
 pub use initialize_vault_a::*;
 pub use initialize_vault_b::*;
 pub use swap::*;
+pub use set_pool_status::*;
+pub use set_guardian::*;
+pub use set_price_limits::*;
+pub use quote_swap::*;
+pub use quote_add_liquidity::*;
+pub use quote_remove_liquidity::*;
+pub use initialize_farm::*;
+pub use fund_farm_reward::*;
+pub use stake_lp::*;
+pub use unstake_lp::*;
+pub use emergency_unstake_lp::*;
+pub use claim_rewards::*;
+pub use add_liquidity_single_sided::*;
+pub use remove_liquidity_single_sided::*;
+pub use place_limit_order::*;
+pub use fill_limit_order::*;
+pub use cancel_limit_order::*;
+pub use open_dca::*;
+pub use execute_dca_slice::*;
+pub use cancel_dca::*;
+pub use close_dca::*;
+pub use set_max_referral_bps::*;
+pub use set_dynamic_fee::*;
+pub use initialize_config::*;
+pub use set_fee_discounts::*;
+pub use stake_governance::*;
+pub use unstake_governance::*;
+pub use set_allowlist::*;
+pub use grant_access::*;
+pub use revoke_access::*;
+pub use set_token_registry::*;
+pub use register_token::*;
+pub use deregister_token::*;
+pub use close_pool::*;
+pub use migrate_pool::*;
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Dca, Pool, PoolPermission};
+use crate::errors::*;
+use crate::utils::*;
+use crate::math::*;
+
+#[allow(clippy::too_many_arguments)]
+pub fn dca_open(
+    ctx: Context<OpenDca>,
+    dca_id: u64,
+    sell_token_a: bool,
+    amount_per_slice: u64,
+    slice_count: u32,
+    interval: i64,
+    min_amount_out_per_slice: u64,
+) -> Result<()> {
+    require!(amount_per_slice > 0, MinidexError::ZeroAmount);
+    require!(slice_count > 0 && interval > 0, MinidexError::InvalidDcaSchedule);
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let (user_source_account, user_receive_account) = if sell_token_a {
+        (&ctx.accounts.user_token_a_account, &ctx.accounts.user_token_b_account)
+    } else {
+        (&ctx.accounts.user_token_b_account, &ctx.accounts.user_token_a_account)
+    };
+
+    let total = amount_per_slice.safe_mul(slice_count as u64)?;
+    require!(user_source_account.amount >= total, MinidexError::InsufficientUserBalance);
+
+    let dca = &mut ctx.accounts.dca;
+
+    dca.pool = ctx.accounts.pool.key();
+    dca.owner = ctx.accounts.user.key();
+    dca.dca_id = dca_id;
+    dca.sell_token_a = sell_token_a;
+    dca.amount_per_slice = amount_per_slice;
+    dca.slice_count = slice_count;
+    dca.slices_executed = 0;
+    dca.interval = interval;
+    // The first slice may run right away
+    dca.next_slice_time = Clock::get()?.unix_timestamp;
+    dca.min_amount_out_per_slice = min_amount_out_per_slice;
+    dca.escrow = ctx.accounts.escrow.key();
+    dca.receive_account = user_receive_account.key();
+    dca.bump = ctx.bumps.dca;
+
+    let cpi_accounts = Transfer {
+        from: user_source_account.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        total,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(dca_id: u64, sell_token_a: bool)]
+pub struct OpenDca<'info> {
+    /// Owner of the schedule
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Dca::INIT_SPACE,
+        seeds = [b"dca", pool.key().as_ref(), user.key().as_ref(), &dca_id.to_le_bytes()],
+        bump
+    )]
+    pub dca: Account<'info, Dca>,
+
+    /// Mint of the token the schedule sells
+    #[account(
+        address = if sell_token_a { pool.token_a_mint } else { pool.token_b_mint }
+    )]
+    pub input_mint: Account<'info, Mint>,
+
+    /// Holds the input not swapped yet
+    #[account(
+        init,
+        seeds = [b"dca_escrow", dca.key().as_ref()],
+        bump,
+        payer = user,
+        token::mint = input_mint,
+        token::authority = dca
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        token::authority = user,
+    )]
+    pub user_token_a_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        token::authority = user,
+    )]
+    pub user_token_b_account: Account<'info, TokenAccount>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
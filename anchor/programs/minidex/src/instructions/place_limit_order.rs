@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{LimitOrder, Pool, PoolPermission};
+use crate::errors::*;
+use crate::utils::*;
+use crate::math::*;
+
+pub fn limit_order_place(
+    ctx: Context<PlaceLimitOrder>,
+    order_id: u64,
+    sell_token_a: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+    keeper_fee: u64,
+) -> Result<()> {
+    require!(amount_in > 0 && min_amount_out > 0, MinidexError::ZeroAmount);
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let (user_source_account, user_receive_account) = if sell_token_a {
+        (&ctx.accounts.user_token_a_account, &ctx.accounts.user_token_b_account)
+    } else {
+        (&ctx.accounts.user_token_b_account, &ctx.accounts.user_token_a_account)
+    };
+
+    let escrowed = amount_in.safe_add(keeper_fee)?;
+    require!(user_source_account.amount >= escrowed, MinidexError::InsufficientUserBalance);
+
+    let order = &mut ctx.accounts.order;
+
+    order.pool = ctx.accounts.pool.key();
+    order.maker = ctx.accounts.user.key();
+    order.order_id = order_id;
+    order.sell_token_a = sell_token_a;
+    order.amount_in = amount_in;
+    order.min_amount_out = min_amount_out;
+    order.keeper_fee = keeper_fee;
+    order.escrow = ctx.accounts.escrow.key();
+    order.receive_account = user_receive_account.key();
+    order.bump = ctx.bumps.order;
+
+    let cpi_accounts = Transfer {
+        from: user_source_account.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        escrowed,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64, sell_token_a: bool)]
+pub struct PlaceLimitOrder<'info> {
+    /// Maker
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [b"limit_order", pool.key().as_ref(), user.key().as_ref(), &order_id.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    /// Mint of the token the order sells
+    #[account(
+        address = if sell_token_a { pool.token_a_mint } else { pool.token_b_mint }
+    )]
+    pub input_mint: Account<'info, Mint>,
+
+    /// Holds the input and the keeper fee until the order is filled or cancelled
+    #[account(
+        init,
+        seeds = [b"limit_order_escrow", order.key().as_ref()],
+        bump,
+        payer = user,
+        token::mint = input_mint,
+        token::authority = order
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        token::authority = user,
+    )]
+    pub user_token_a_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        token::authority = user,
+    )]
+    pub user_token_b_account: Account<'info, TokenAccount>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct QuoteAddLiquidity<'info> {
+    /// Pool account
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Returns the LP tokens `add_liquidity` would mint for these amounts.
+pub fn add_liquidity_quote(ctx: Context<QuoteAddLiquidity>, amount_a: u64, amount_b: u64) -> Result<u64> {
+    calculate_add_liquidity_quote(&ctx.accounts.pool, amount_a, amount_b)
+}
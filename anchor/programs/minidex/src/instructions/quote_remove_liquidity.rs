@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct QuoteRemoveLiquidity<'info> {
+    /// Pool account
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Returns the token amounts `remove_liquidity` would pay out for `lp_tokens`.
+pub fn remove_liquidity_quote(ctx: Context<QuoteRemoveLiquidity>, lp_tokens: u64) -> Result<RemoveLiquidityQuote> {
+    calculate_remove_liquidity_quote(&ctx.accounts.pool, lp_tokens)
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// Pool account
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Runs the same checks and math as `swap_tokens` without moving any tokens.
+pub fn swap_quote(ctx: Context<QuoteSwap>, amount_in: u64, token_a_to_b: bool) -> Result<SwapQuote> {
+    calculate_swap_quote(&ctx.accounts.pool, amount_in, token_a_to_b, Clock::get()?.slot)
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::state::{Config, RegisteredToken};
+use crate::errors::*;
+
+/// Registers `mint` for new pools, or updates the override of a registered one.
+pub fn token_register(ctx: Context<RegisterToken>, skip_mint_checks: bool) -> Result<()> {
+    let registered = &mut ctx.accounts.registered_token;
+
+    registered.mint = ctx.accounts.mint.key();
+    registered.skip_mint_checks = skip_mint_checks;
+    registered.bump = ctx.bumps.registered_token;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterToken<'info> {
+    /// Config admin, pays for the registration
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MinidexError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RegisteredToken::INIT_SPACE,
+        seeds = [b"registered_token", mint.key().as_ref()],
+        bump
+    )]
+    pub registered_token: Account<'info, RegisteredToken>,
+
+    pub system_program: Program<'info, System>,
+}
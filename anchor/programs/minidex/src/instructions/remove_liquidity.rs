@@ -2,12 +2,17 @@ use anchor_lang::prelude::*;
 
 use anchor_spl::token::{Mint, Token, TokenAccount, Burn, burn, Transfer, transfer};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::Pool;
+use crate::state::{Pool, PoolPermission};
 use crate::errors::*;
+use crate::events::RemoveLiquidityEvent;
+use crate::utils::*;
+use crate::math::*;
 
 pub fn liquidity_remove(ctx: Context<RemoveLiquidity>, lp_tokens: u64, min_amount_a: u64, min_amount_b: u64) -> Result<()> {
 
     require!(lp_tokens > 0, MinidexError::ZeroLPTokens);
+    require!(!ctx.accounts.pool.withdrawals_paused(), MinidexError::WithdrawalsPaused);
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
 
     let pool_account_info = ctx.accounts.pool.to_account_info();
 
@@ -21,24 +26,11 @@ pub fn liquidity_remove(ctx: Context<RemoveLiquidity>, lp_tokens: u64, min_amoun
     require!(pool.reserve_a > 0 && pool.reserve_b > 0, MinidexError::InsufficientLiquidity);
     require!(ctx.accounts.user_lp_token.amount >= lp_tokens, MinidexError::InsufficientLPTokens);
 
-    let amount_a = (lp_tokens as u128)
-        .checked_mul(pool.reserve_a as u128)
-        .ok_or(MinidexError::MathOverflow)?
-        .checked_div(pool.total_lp_supply as u128)
-        .ok_or(MinidexError::MathOverflow)? as u64;
-
-    let amount_b = (lp_tokens as u128)
-        .checked_mul(pool.reserve_b as u128)
-        .ok_or(MinidexError::MathOverflow)?
-        .checked_div(pool.total_lp_supply as u128)
-        .ok_or(MinidexError::MathOverflow)? as u64;
+    let (amount_a, amount_b) = calculate_withdrawal_amounts(pool, lp_tokens)?;
 
     require!(amount_a >= min_amount_a, MinidexError::SlippageExceeded);
     require!(amount_b >= min_amount_b, MinidexError::SlippageExceeded);
 
-    require!(amount_a <= pool.reserve_a, MinidexError::InsufficientLiquidity);
-    require!(amount_b <= pool.reserve_b, MinidexError::InsufficientLiquidity);
-
     let cpi_accounts = Burn {
         mint: ctx.accounts.lp_mint.to_account_info(),
         from: ctx.accounts.user_lp_token.to_account_info(),
@@ -88,9 +80,20 @@ pub fn liquidity_remove(ctx: Context<RemoveLiquidity>, lp_tokens: u64, min_amoun
         amount_b,
     )?;
 
-    pool.reserve_a = pool.reserve_a.checked_sub(amount_a).ok_or(MinidexError::MathOverflow)?;
-    pool.reserve_b = pool.reserve_b.checked_sub(amount_b).ok_or(MinidexError::MathOverflow)?;
-    pool.total_lp_supply = pool.total_lp_supply.checked_sub(lp_tokens).ok_or(MinidexError::MathOverflow)?;
+    pool.reserve_a = pool.reserve_a.safe_sub(amount_a)?;
+    pool.reserve_b = pool.reserve_b.safe_sub(amount_b)?;
+    pool.total_lp_supply = pool.total_lp_supply.safe_sub(lp_tokens)?;
+
+    emit!(RemoveLiquidityEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        lp_tokens,
+        amount_a,
+        amount_b,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
 
     Ok(())
 }
@@ -146,6 +149,13 @@ pub struct RemoveLiquidity<'info> {
     )]
     pub lp_mint: Account<'info, Mint>,
 
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::token::{Mint, Token, TokenAccount, Burn, burn, Transfer, transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{Pool, PoolPermission};
+use crate::errors::*;
+use crate::events::{RemoveLiquidityEvent, SwapEvent};
+use crate::utils::*;
+use crate::math::*;
+
+pub fn liquidity_remove_single_sided(ctx: Context<RemoveLiquiditySingleSided>, lp_tokens: u64, want_token_a: bool, min_amount_out: u64) -> Result<()> {
+
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
+
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+
+    let pool = &mut ctx.accounts.pool;
+
+    let token_a_mint = pool.token_a_mint;
+    let token_b_mint = pool.token_b_mint;
+    let bump = pool.bump;
+
+    let current_slot = Clock::get()?.slot;
+    let quote = calculate_single_sided_withdrawal_quote(pool, lp_tokens, want_token_a, current_slot)?;
+
+    require!(ctx.accounts.user_lp_token.amount >= lp_tokens, MinidexError::InsufficientLPTokens);
+    require!(quote.amount_out >= min_amount_out, MinidexError::SlippageExceeded);
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        from: ctx.accounts.user_lp_token.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    burn(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        lp_tokens,
+    )?;
+
+    pool.reserve_a = pool.reserve_a.safe_sub(quote.amount_a)?;
+    pool.reserve_b = pool.reserve_b.safe_sub(quote.amount_b)?;
+    pool.total_lp_supply = pool.total_lp_supply.safe_sub(lp_tokens)?;
+
+    emit!(RemoveLiquidityEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        lp_tokens,
+        amount_a: quote.amount_a,
+        amount_b: quote.amount_b,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    // The unwanted side never leaves the vault, it is swapped straight back
+    let (swap_amount, token_a_to_b) = if want_token_a { (quote.amount_b, false) } else { (quote.amount_a, true) };
+
+    if pool.last_trade_slot != current_slot {
+        pool.last_trade_slot = current_slot;
+        pool.slot_start_reserve_a = pool.reserve_a;
+        pool.slot_start_reserve_b = pool.reserve_b;
+    }
+
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(pool, swap_amount, quote.swap_output, token_a_to_b)?;
+    record_swap_volatility(pool, reserve_a, reserve_b, current_slot)?;
+    pool.reserve_a = reserve_a;
+    pool.reserve_b = reserve_b;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        token_a_to_b,
+        amount_in: swap_amount,
+        amount_out: quote.swap_output,
+        fee_amount: quote.fee_amount,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
+    let seeds = &[
+        b"pool",
+        token_a_mint.as_ref(),
+        token_b_mint.as_ref(),
+        &[bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let (token_vault, user_token_account) = if want_token_a {
+        (&ctx.accounts.token_a_vault, &ctx.accounts.user_token_a_account)
+    } else {
+        (&ctx.accounts.token_b_vault, &ctx.accounts.user_token_b_account)
+    };
+
+    let cpi_accounts_out = Transfer {
+        from: token_vault.to_account_info(),
+        to: user_token_account.to_account_info(),
+        authority: pool_account_info.clone(),
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_out,
+            signer,
+        ),
+        quote.amount_out,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquiditySingleSided<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_a_mint,
+        token::authority = user,
+    )]
+    pub user_token_a_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = pool.token_b_mint,
+        token::authority = user,
+    )]
+    pub user_token_b_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool.lp_mint,
+        associated_token::authority = user
+    )]
+    pub user_lp_token: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.token_a_vault
+    )]
+    pub token_a_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.token_b_vault
+    )]
+    pub token_b_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = pool.lp_mint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
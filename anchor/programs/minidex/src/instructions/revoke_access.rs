@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolPermission};
+use crate::errors::*;
+
+/// Closes the permission, returning its rent to the authority.
+pub fn access_revoke(_ctx: Context<RevokeAccess>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeAccess<'info> {
+    /// Pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"permission", pool.key().as_ref(), permission.user.as_ref()],
+        bump = permission.bump,
+        close = authority
+    )]
+    pub permission: Account<'info, PoolPermission>,
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Restricts swaps and liquidity changes to wallets granted access, or opens
+/// the pool to everyone again. Permissions are kept while the allowlist is off.
+pub fn update_allowlist(ctx: Context<SetAllowlist>, enabled: bool) -> Result<()> {
+    ctx.accounts.pool.allowlist_enabled = enabled;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct SetDynamicFee<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// A multiplier of zero turns the dynamic fee mode off and swaps pay `fee_rate` again.
+pub fn update_dynamic_fee(ctx: Context<SetDynamicFee>, min_fee_rate: u16, volatility_fee_multiplier: u16) -> Result<()> {
+    require!(min_fee_rate <= MAX_FEE_RATE, MinidexError::InvalidFeeRate);
+
+    let pool = &mut ctx.accounts.pool;
+
+    pool.min_fee_rate = min_fee_rate;
+    pool.volatility_fee_multiplier = volatility_fee_multiplier;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Config, FeeDiscountTier};
+use crate::errors::*;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct SetFeeDiscounts<'info> {
+    /// Config admin
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MinidexError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Replaces the discount schedule. Tiers must require more stake and give at
+/// least the same discount as the one before. An empty list turns discounts off.
+pub fn update_fee_discounts(ctx: Context<SetFeeDiscounts>, tiers: Vec<FeeDiscountTier>) -> Result<()> {
+    require!(tiers.len() <= MAX_FEE_DISCOUNT_TIERS, MinidexError::InvalidFeeDiscounts);
+
+    let mut previous = FeeDiscountTier::default();
+    for tier in &tiers {
+        require!(tier.min_stake > previous.min_stake, MinidexError::InvalidFeeDiscounts);
+        require!(tier.discount_bps >= previous.discount_bps, MinidexError::InvalidFeeDiscounts);
+        require!(tier.discount_bps as u64 <= BPS_DENOMINATOR, MinidexError::InvalidFeeDiscounts);
+        previous = *tier;
+    }
+
+    let config = &mut ctx.accounts.config;
+
+    config.fee_discount_tiers = [FeeDiscountTier::default(); MAX_FEE_DISCOUNT_TIERS];
+    config.fee_discount_tiers[..tiers.len()].copy_from_slice(&tiers);
+
+    Ok(())
+}
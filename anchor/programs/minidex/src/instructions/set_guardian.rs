@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn update_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    pool.guardian = guardian;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct SetMaxReferralBps<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Caps the share of the swap fee swaps may pay their referrer. Zero disables referrals.
+pub fn update_max_referral_bps(ctx: Context<SetMaxReferralBps>, max_referral_bps: u16) -> Result<()> {
+    require!(max_referral_bps as u64 <= BPS_DENOMINATOR, MinidexError::InvalidReferralFee);
+
+    ctx.accounts.pool.max_referral_bps = max_referral_bps;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct SetPoolStatus<'info> {
+    /// Pool authority, or the guardian when it only adds pause flags
+    #[account(
+        constraint = authority.key() == pool.authority || authority.key() == pool.guardian @ MinidexError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn update_status(ctx: Context<SetPoolStatus>, status: u8) -> Result<()> {
+    require!(status & !POOL_STATUS_MASK == 0, MinidexError::InvalidPoolStatus);
+
+    let pool = &mut ctx.accounts.pool;
+
+    // The guardian can only pause, lifting a pause is up to the authority
+    require!(
+        ctx.accounts.authority.key() == pool.authority || pool.status & !status == 0,
+        MinidexError::Unauthorized
+    );
+
+    pool.status = status;
+
+    Ok(())
+}
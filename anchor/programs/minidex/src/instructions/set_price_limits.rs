@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::errors::*;
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct SetPriceLimits<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Pool account
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ MinidexError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// A limit of zero disables the corresponding check.
+pub fn update_price_limits(
+    ctx: Context<SetPriceLimits>,
+    max_price_impact_bps: u16,
+    max_slot_price_move_bps: u16,
+) -> Result<()> {
+    require!(max_price_impact_bps as u64 <= BPS_DENOMINATOR, MinidexError::InvalidPriceLimit);
+    require!(max_slot_price_move_bps as u64 <= BPS_DENOMINATOR, MinidexError::InvalidPriceLimit);
+
+    let pool = &mut ctx.accounts.pool;
+
+    pool.max_price_impact_bps = max_price_impact_bps;
+    pool.max_slot_price_move_bps = max_slot_price_move_bps;
+
+    Ok(())
+}
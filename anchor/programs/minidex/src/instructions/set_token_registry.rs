@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Config, TokenRegistry};
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetTokenRegistry<'info> {
+    /// Config admin, pays for the registry the first time
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MinidexError::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + TokenRegistry::INIT_SPACE,
+        seeds = [b"token_registry"],
+        bump
+    )]
+    pub token_registry: Account<'info, TokenRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the token registry on first use. While it is enabled, new pools
+/// may only pair registered mints; registrations are kept while it is off.
+pub fn update_token_registry(ctx: Context<SetTokenRegistry>, enabled: bool) -> Result<()> {
+    let registry = &mut ctx.accounts.token_registry;
+
+    registry.enabled = enabled;
+    registry.bump = ctx.bumps.token_registry;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Config, UserStake};
+use crate::errors::*;
+use crate::math::*;
+
+pub fn governance_stake(ctx: Context<StakeGovernance>, amount: u64) -> Result<()> {
+    require!(amount > 0, MinidexError::ZeroAmount);
+
+    let stake = &mut ctx.accounts.user_stake;
+
+    if stake.owner == Pubkey::default() {
+        stake.owner = ctx.accounts.user.key();
+        stake.vault = ctx.accounts.stake_vault.key();
+        stake.bump = ctx.bumps.user_stake;
+    }
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_governance_account.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+
+    stake.staked = stake.staked.safe_add(amount)?;
+    // Stops a flash loan of governance tokens from discounting a swap in the same slot
+    stake.last_stake_slot = Clock::get()?.slot;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeGovernance<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = governance_mint
+    )]
+    pub config: Account<'info, Config>,
+
+    pub governance_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStake::INIT_SPACE,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = governance_mint,
+        token::authority = user_stake,
+        seeds = [b"user_stake_vault", user_stake.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = user,
+    )]
+    pub user_governance_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
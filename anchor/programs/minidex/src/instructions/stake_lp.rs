@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Farm, FarmPosition};
+use crate::errors::*;
+use crate::utils::*;
+use crate::math::*;
+
+pub fn lp_stake(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, MinidexError::ZeroAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+
+    if position.farm == Pubkey::default() {
+        position.farm = farm.key();
+        position.owner = ctx.accounts.user.key();
+        position.bump = ctx.bumps.position;
+    }
+
+    update_farm_rewards(farm, now)?;
+    settle_position_rewards(farm, position)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_lp_account.to_account_info(),
+        to: ctx.accounts.lp_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+
+    transfer(
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+
+    position.staked = position.staked.safe_add(amount)?;
+    farm.total_staked = farm.total_staked.safe_add(amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool.as_ref()],
+        bump = farm.bump,
+        has_one = lp_vault
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FarmPosition::INIT_SPACE,
+        seeds = [b"farm_position", farm.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        token::mint = lp_vault.mint,
+        token::authority = user,
+    )]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
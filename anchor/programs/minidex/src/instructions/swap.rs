@@ -1,13 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
-use crate::state::Pool;
+use crate::state::{Config, Pool, PoolPermission, UserStake};
 use crate::utils::*;
 use crate::errors::*;
+use crate::events::SwapEvent;
+use crate::math::*;
 
 
-pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_a_to_b: bool) -> Result<()> {
+/// `referral_bps` of the swap fee, at most the pool's `max_referral_bps`, is
+/// paid to the optional referrer token account in the input token. Passing the
+/// config and the user's stake account applies their staking fee discount.
+pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_a_to_b: bool, referral_bps: u16) -> Result<()> {
 
     require!(amount_in > 0, MinidexError::ZeroSwapAmount);
+    require!(!ctx.accounts.pool.swaps_paused(), MinidexError::SwapsPaused);
+    check_allowlist(&ctx.accounts.pool, ctx.accounts.permission.is_some())?;
 
     let pool_account_info = ctx.accounts.pool.to_account_info();
 
@@ -19,13 +26,40 @@ pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_
 
     require!(pool.reserve_a > 0 && pool.reserve_b > 0, MinidexError::InsufficientLiquidity);
 
-    let amount_out = calculate_swap_output(pool, amount_in, token_a_to_b)?;
+    // The fee follows the volatility accumulator as of this slot
+    let current_slot = Clock::get()?.slot;
+    decay_volatility(pool, current_slot);
+
+    let discount_bps = match (&ctx.accounts.config, &ctx.accounts.user_stake) {
+        (Some(config), Some(user_stake)) => calculate_fee_discount_bps(config, user_stake, current_slot),
+        _ => 0,
+    };
+    let pricing_pool = &apply_fee_discount(pool, discount_bps, current_slot)?;
+
+    let amount_out = calculate_swap_output(pricing_pool, amount_in, token_a_to_b)?;
+    let fee_amount = calculate_swap_fee(pricing_pool, amount_in)?;
 
     require!(amount_out >= min_amount_out, MinidexError::SlippageExceeded);
 
-    let reserve_out = if token_a_to_b { pool.reserve_b } else { pool.reserve_a };
+    let referral_amount = calculate_referral_fee(pool, fee_amount, referral_bps)?;
+    require!(
+        referral_amount == 0 || ctx.accounts.referrer_token_account.is_some(),
+        MinidexError::MissingReferrer
+    );
+
+    // The referral leaves with the input, only the rest of it reaches the pool
+    let pool_amount_in = amount_in.safe_sub(referral_amount)?;
 
-    require!(amount_out < reserve_out, MinidexError::InsufficientLiquidity);
+    let (reserve_a_after, reserve_b_after) = calculate_reserves_after_swap(pool, pool_amount_in, amount_out, token_a_to_b)?;
+
+    check_price_limits(pool, reserve_a_after, reserve_b_after, current_slot)?;
+
+    // The first swap in a slot records the reserves the cumulative move is measured from
+    if pool.last_trade_slot != current_slot {
+        pool.last_trade_slot = current_slot;
+        pool.slot_start_reserve_a = pool.reserve_a;
+        pool.slot_start_reserve_b = pool.reserve_b;
+    }
 
     if token_a_to_b {
         require!(
@@ -59,7 +93,7 @@ pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_
         let cpi_program_in = ctx.accounts.token_program.to_account_info();
         let cpi_ctx_in = CpiContext::new(cpi_program_in, cpi_accounts_in);
 
-        transfer(cpi_ctx_in, amount_in)?;
+        transfer(cpi_ctx_in, pool_amount_in)?;
 
         // Transfer token B from vault to user
         let cpi_accounts_out = Transfer {
@@ -72,10 +106,6 @@ pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_
         let cpi_ctx_out = CpiContext::new_with_signer(cpi_program_out, cpi_accounts_out, signer);
 
         transfer(cpi_ctx_out, amount_out)?;
-
-        // Update reserves
-        pool.reserve_a = pool.reserve_a.checked_add(amount_in).unwrap();
-        pool.reserve_b = pool.reserve_b.checked_sub(amount_out).unwrap();
     } else {
         // Transfer token B from user to vault
         let cpi_accounts_in = Transfer {
@@ -87,7 +117,7 @@ pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_
         let cpi_program_in = ctx.accounts.token_program.to_account_info();
         let cpi_ctx_in = CpiContext::new(cpi_program_in, cpi_accounts_in);
 
-        transfer(cpi_ctx_in, amount_in)?;
+        transfer(cpi_ctx_in, pool_amount_in)?;
 
         // Transfer token A from vault to user
         let cpi_accounts_out = Transfer {
@@ -99,17 +129,55 @@ pub fn token_swap(ctx:Context<Swap>, amount_in: u64, min_amount_out: u64, token_
         let cpi_ctx_out = CpiContext::new_with_signer(cpi_program_out, cpi_accounts_out, signer);
 
         transfer(cpi_ctx_out, amount_out)?;
+    }
 
-        // Update reserves
-        pool.reserve_b = pool.reserve_b.checked_add(amount_in).unwrap();
-        pool.reserve_a = pool.reserve_a.checked_sub(amount_out).unwrap();
+    if let Some(referrer_token_account) = &ctx.accounts.referrer_token_account {
+        if referral_amount > 0 {
+            let user_token_in_account = if token_a_to_b {
+                &ctx.accounts.user_token_a_account
+            } else {
+                &ctx.accounts.user_token_b_account
+            };
+
+            let cpi_accounts_referral = Transfer {
+                from: user_token_in_account.to_account_info(),
+                to: referrer_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+
+            let cpi_program_referral = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx_referral = CpiContext::new(cpi_program_referral, cpi_accounts_referral);
+
+            transfer(cpi_ctx_referral, referral_amount)?;
+        }
     }
 
+    record_swap_volatility(pool, reserve_a_after, reserve_b_after, current_slot)?;
+
+    // Update reserves
+    pool.reserve_a = reserve_a_after;
+    pool.reserve_b = reserve_b_after;
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        token_a_to_b,
+        amount_in,
+        amount_out,
+        fee_amount,
+        referrer: ctx.accounts.referrer_token_account.as_ref().map_or(Pubkey::default(), |account| account.key()),
+        referral_amount,
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+    });
+
     Ok(())
 
 }
 
 #[derive(Accounts)]
+#[instruction(amount_in: u64, min_amount_out: u64, token_a_to_b: bool)]
 pub struct Swap<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -119,33 +187,64 @@ pub struct Swap<'info> {
         seeds = [b"pool", pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
         bump = pool.bump,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
 
     #[account(
         mut,
         token::mint = pool.token_a_mint,
         token::authority = user,
     )]
-    pub user_token_a_account: Account<'info, TokenAccount>,
+    pub user_token_a_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         token::mint = pool.token_b_mint,
         token::authority = user,
     )]
-    pub user_token_b_account: Account<'info, TokenAccount>,
+    pub user_token_b_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         address = pool.token_a_vault
     )]
-    pub token_a_vault: Account<'info, TokenAccount>,
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
         address = pool.token_b_vault
     )]
-    pub token_b_vault: Account<'info, TokenAccount>,
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the referral, in the input token
+    #[account(
+        mut,
+        token::mint = if token_a_to_b { pool.token_a_mint } else { pool.token_b_mint },
+        token::authority = referrer,
+    )]
+    pub referrer_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Only the owner of `referrer_token_account`
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Fee discount schedule, only needed with `user_stake`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Option<Account<'info, Config>>,
+
+    #[account(
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Option<Account<'info, UserStake>>,
+
+    /// The user's permission, only needed while the pool's allowlist is enabled
+    #[account(
+        seeds = [b"permission", pool.key().as_ref(), user.key().as_ref()],
+        bump = permission.bump
+    )]
+    pub permission: Option<Account<'info, PoolPermission>>,
 
     pub token_program: Program<'info, Token>,
 }
\ No newline at end of file
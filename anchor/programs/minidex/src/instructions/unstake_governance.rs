@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::constants::GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS;
+use crate::state::UserStake;
+use crate::errors::*;
+use crate::math::*;
+
+pub fn governance_unstake(ctx: Context<UnstakeGovernance>, amount: u64) -> Result<()> {
+    require!(amount > 0, MinidexError::ZeroAmount);
+
+    let stake_account_info = ctx.accounts.user_stake.to_account_info();
+    let stake = &mut ctx.accounts.user_stake;
+
+    require!(stake.staked >= amount, MinidexError::InsufficientStake);
+
+    // A discount is only worth borrowing the token for if it stays locked for a while
+    let unlock_slot = stake.last_stake_slot.safe_add(GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS)?;
+    require!(Clock::get()?.slot >= unlock_slot, MinidexError::StakeLocked);
+
+    stake.staked = stake.staked.safe_sub(amount)?;
+
+    let seeds = &[
+        b"user_stake",
+        stake.owner.as_ref(),
+        &[stake.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        to: ctx.accounts.user_governance_account.to_account_info(),
+        authority: stake_account_info,
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeGovernance<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        address = user_stake.vault
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_vault.mint,
+        token::authority = user,
+    )]
+    pub user_governance_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
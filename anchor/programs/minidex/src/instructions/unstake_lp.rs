@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+
+use crate::state::{Farm, FarmPosition};
+use crate::errors::*;
+use crate::utils::*;
+use crate::math::*;
+
+pub fn lp_unstake(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, MinidexError::ZeroAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let farm_account_info = ctx.accounts.farm.to_account_info();
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+
+    require!(position.staked >= amount, MinidexError::InsufficientStake);
+
+    update_farm_rewards(farm, now)?;
+    settle_position_rewards(farm, position)?;
+
+    position.staked = position.staked.safe_sub(amount)?;
+    farm.total_staked = farm.total_staked.safe_sub(amount)?;
+
+    let seeds = &[
+        b"farm",
+        farm.pool.as_ref(),
+        &[farm.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.lp_vault.to_account_info(),
+        to: ctx.accounts.user_lp_account.to_account_info(),
+        authority: farm_account_info,
+    };
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool.as_ref()],
+        bump = farm.bump,
+        has_one = lp_vault
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [b"farm_position", farm.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, FarmPosition>,
+
+    #[account(
+        mut,
+        token::mint = lp_vault.mint,
+        token::authority = user,
+    )]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -4,43 +4,212 @@ use anchor_lang::prelude::*;
 pub mod state;
 pub mod instructions;
 pub mod errors;
+pub mod events;
 pub mod constants;
 pub mod utils;
+pub mod math;
 
 use instructions::*;
 use state::*;
 
 declare_id!("JAVuBXeBZqXNtS73azhBDAoYaaAFfo4gWXoZe2e7Jf8H");
 
-#[program]
-pub mod minidex {
+pub use program_entry::*;
+
+// The IDL instructions `#[program]` generates call the deprecated `AccountInfo::realloc`. They are
+// emitted next to the program module, so only a module around the whole expansion scopes the allow.
+#[allow(deprecated)]
+mod program_entry {
     use super::*;
 
-    pub fn initialize_pool(ctx: Context<InitializePool>, fee_rate: u16, token_a_vault: Pubkey, token_b_vault: Pubkey, lp_mint: Pubkey) -> Result<()> {
-        initialize(ctx, fee_rate, token_a_vault, token_b_vault, lp_mint)
-    }
-    pub fn initialize_vault_a(ctx: Context<SetupVaultA>) -> Result<()> {
-        initialize_a(ctx)
-    }
-    pub fn initialize_vault_b(ctx: Context<SetupVaultB>) -> Result<()> {
-        initialize_b(ctx)
-    }
-    pub fn initialize_lp_mint(ctx: Context<SetupLpMint>) -> Result<()> {
-        initialize_lp(ctx)
-    }
+    #[program]
+    pub mod minidex {
+        use super::*;
 
-    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64, min_lp_tokens: u64) -> Result<()> {
-        liquidity_add(ctx, amount_a, amount_b, min_lp_tokens)
-    }
+        pub fn initialize_pool(ctx: Context<InitializePool>, fee_rate: u16, token_a_vault: Pubkey, token_b_vault: Pubkey, lp_mint: Pubkey) -> Result<()> {
+            initialize(ctx, fee_rate, token_a_vault, token_b_vault, lp_mint)
+        }
+        pub fn initialize_vault_a(ctx: Context<SetupVaultA>) -> Result<()> {
+            initialize_a(ctx)
+        }
+        pub fn initialize_vault_b(ctx: Context<SetupVaultB>) -> Result<()> {
+            initialize_b(ctx)
+        }
+        pub fn initialize_lp_mint(ctx: Context<SetupLpMint>) -> Result<()> {
+            initialize_lp(ctx)
+        }
 
-    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_tokens: u64, min_amount_a: u64, min_amount_b: u64) -> Result<()> {
-        liquidity_remove(ctx, lp_tokens, min_amount_a, min_amount_b)
-    }
+        pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64, min_lp_tokens: u64) -> Result<()> {
+            liquidity_add(ctx, amount_a, amount_b, min_lp_tokens)
+        }
 
-    pub fn swap_tokens(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64, token_a_to_b: bool) -> Result<()> {
-        token_swap(ctx, amount_in, min_amount_out, token_a_to_b)
-    }
+        pub fn add_liquidity_single_sided(ctx: Context<AddLiquiditySingleSided>, amount_in: u64, token_is_a: bool, min_lp_tokens: u64) -> Result<()> {
+            liquidity_add_single_sided(ctx, amount_in, token_is_a, min_lp_tokens)
+        }
 
-   
-}
+        pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_tokens: u64, min_amount_a: u64, min_amount_b: u64) -> Result<()> {
+            liquidity_remove(ctx, lp_tokens, min_amount_a, min_amount_b)
+        }
+
+        pub fn remove_liquidity_single_sided(ctx: Context<RemoveLiquiditySingleSided>, lp_tokens: u64, want_token_a: bool, min_amount_out: u64) -> Result<()> {
+            liquidity_remove_single_sided(ctx, lp_tokens, want_token_a, min_amount_out)
+        }
+
+        pub fn swap_tokens(
+            ctx: Context<Swap>,
+            amount_in: u64,
+            min_amount_out: u64,
+            token_a_to_b: bool,
+            referral_bps: u16,
+        ) -> Result<()> {
+            token_swap(ctx, amount_in, min_amount_out, token_a_to_b, referral_bps)
+        }
+
+        pub fn set_pool_status(ctx: Context<SetPoolStatus>, status: u8) -> Result<()> {
+            update_status(ctx, status)
+        }
+
+        pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+            update_guardian(ctx, guardian)
+        }
+
+        pub fn set_price_limits(ctx: Context<SetPriceLimits>, max_price_impact_bps: u16, max_slot_price_move_bps: u16) -> Result<()> {
+            update_price_limits(ctx, max_price_impact_bps, max_slot_price_move_bps)
+        }
+
+        pub fn quote_swap(ctx: Context<QuoteSwap>, amount_in: u64, token_a_to_b: bool) -> Result<SwapQuote> {
+            swap_quote(ctx, amount_in, token_a_to_b)
+        }
+
+        pub fn quote_add_liquidity(ctx: Context<QuoteAddLiquidity>, amount_a: u64, amount_b: u64) -> Result<u64> {
+            add_liquidity_quote(ctx, amount_a, amount_b)
+        }
+
+        pub fn quote_remove_liquidity(ctx: Context<QuoteRemoveLiquidity>, lp_tokens: u64) -> Result<RemoveLiquidityQuote> {
+            remove_liquidity_quote(ctx, lp_tokens)
+        }
+
+        pub fn initialize_farm(ctx: Context<InitializeFarm>) -> Result<()> {
+            farm_initialize(ctx)
+        }
+
+        pub fn fund_farm_reward(ctx: Context<FundFarmReward>, start_time: i64, end_time: i64, amount: u64) -> Result<()> {
+            reward_fund(ctx, start_time, end_time, amount)
+        }
+
+        pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+            lp_stake(ctx, amount)
+        }
+
+        pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+            lp_unstake(ctx, amount)
+        }
+
+        pub fn emergency_unstake_lp(ctx: Context<EmergencyUnstakeLp>) -> Result<()> {
+            lp_emergency_unstake(ctx)
+        }
+
+        pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+            rewards_claim(ctx, reward_index)
+        }
 
+        pub fn place_limit_order(
+            ctx: Context<PlaceLimitOrder>,
+            order_id: u64,
+            sell_token_a: bool,
+            amount_in: u64,
+            min_amount_out: u64,
+            keeper_fee: u64,
+        ) -> Result<()> {
+            limit_order_place(ctx, order_id, sell_token_a, amount_in, min_amount_out, keeper_fee)
+        }
+
+        pub fn fill_limit_order(ctx: Context<FillLimitOrder>) -> Result<()> {
+            limit_order_fill(ctx)
+        }
+
+        pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+            limit_order_cancel(ctx)
+        }
+
+        pub fn open_dca(
+            ctx: Context<OpenDca>,
+            dca_id: u64,
+            sell_token_a: bool,
+            amount_per_slice: u64,
+            slice_count: u32,
+            interval: i64,
+            min_amount_out_per_slice: u64,
+        ) -> Result<()> {
+            dca_open(ctx, dca_id, sell_token_a, amount_per_slice, slice_count, interval, min_amount_out_per_slice)
+        }
+
+        pub fn execute_dca_slice(ctx: Context<ExecuteDcaSlice>) -> Result<()> {
+            dca_slice_execute(ctx)
+        }
+
+        pub fn cancel_dca(ctx: Context<CancelDca>) -> Result<()> {
+            dca_cancel(ctx)
+        }
+
+        pub fn close_dca(ctx: Context<CloseDca>) -> Result<()> {
+            dca_close(ctx)
+        }
+
+        pub fn set_max_referral_bps(ctx: Context<SetMaxReferralBps>, max_referral_bps: u16) -> Result<()> {
+            update_max_referral_bps(ctx, max_referral_bps)
+        }
+
+        pub fn set_dynamic_fee(ctx: Context<SetDynamicFee>, min_fee_rate: u16, volatility_fee_multiplier: u16) -> Result<()> {
+            update_dynamic_fee(ctx, min_fee_rate, volatility_fee_multiplier)
+        }
+
+        pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+            config_initialize(ctx)
+        }
+
+        pub fn set_fee_discounts(ctx: Context<SetFeeDiscounts>, tiers: Vec<FeeDiscountTier>) -> Result<()> {
+            update_fee_discounts(ctx, tiers)
+        }
+
+        pub fn stake_governance(ctx: Context<StakeGovernance>, amount: u64) -> Result<()> {
+            governance_stake(ctx, amount)
+        }
+
+        pub fn unstake_governance(ctx: Context<UnstakeGovernance>, amount: u64) -> Result<()> {
+            governance_unstake(ctx, amount)
+        }
+
+        pub fn set_allowlist(ctx: Context<SetAllowlist>, enabled: bool) -> Result<()> {
+            update_allowlist(ctx, enabled)
+        }
+
+        pub fn grant_access(ctx: Context<GrantAccess>, user: Pubkey) -> Result<()> {
+            access_grant(ctx, user)
+        }
+
+        pub fn revoke_access(ctx: Context<RevokeAccess>) -> Result<()> {
+            access_revoke(ctx)
+        }
+
+        pub fn set_token_registry(ctx: Context<SetTokenRegistry>, enabled: bool) -> Result<()> {
+            update_token_registry(ctx, enabled)
+        }
+
+        pub fn register_token(ctx: Context<RegisterToken>, skip_mint_checks: bool) -> Result<()> {
+            token_register(ctx, skip_mint_checks)
+        }
+
+        pub fn deregister_token(ctx: Context<DeregisterToken>) -> Result<()> {
+            token_deregister(ctx)
+        }
+
+        pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+            pool_close(ctx)
+        }
+
+        pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+            pool_migrate(ctx)
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+
+pub use wide::U256;
+
+// Kept out of this module's scope, the macro expands to code that expects
+// `Result` to be the std one rather than Anchor's.
+#[allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+mod wide {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+
+/// Which way a division result is rounded. Amounts the user pays round up,
+/// amounts the user receives round down, so rounding always favours the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Checked arithmetic that reports overflow as `MinidexError::MathOverflow`
+/// instead of panicking or wrapping.
+pub trait SafeMath: Sized {
+    fn safe_add(self, rhs: Self) -> Result<Self>;
+    fn safe_sub(self, rhs: Self) -> Result<Self>;
+    fn safe_mul(self, rhs: Self) -> Result<Self>;
+    fn safe_div(self, rhs: Self) -> Result<Self>;
+}
+
+macro_rules! impl_safe_math {
+    ($($t:ty),*) => {
+        $(
+            impl SafeMath for $t {
+                fn safe_add(self, rhs: Self) -> Result<Self> {
+                    Ok(self.checked_add(rhs).ok_or(MinidexError::MathOverflow)?)
+                }
+
+                fn safe_sub(self, rhs: Self) -> Result<Self> {
+                    Ok(self.checked_sub(rhs).ok_or(MinidexError::MathOverflow)?)
+                }
+
+                fn safe_mul(self, rhs: Self) -> Result<Self> {
+                    Ok(self.checked_mul(rhs).ok_or(MinidexError::MathOverflow)?)
+                }
+
+                fn safe_div(self, rhs: Self) -> Result<Self> {
+                    Ok(self.checked_div(rhs).ok_or(MinidexError::MathOverflow)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_safe_math!(u64, u128);
+
+/// Narrows a u128 intermediate back to a token amount.
+pub fn to_u64(value: u128) -> Result<u64> {
+    Ok(u64::try_from(value).map_err(|_| MinidexError::MathOverflow)?)
+}
+
+/// `a * b / c` for token amounts, computed in u128 so the product cannot overflow.
+pub fn mul_div(a: u64, b: u64, c: u64, rounding: Rounding) -> Result<u64> {
+    to_u64(mul_div_u128(a as u128, b as u128, c as u128, rounding)?)
+}
+
+/// `a * b / c` for u128 intermediates, computed in U256 so the product cannot overflow.
+pub fn mul_div_u128(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128> {
+    require!(c > 0, MinidexError::MathOverflow);
+
+    let product = U256::from(a) * U256::from(b);
+    let divisor = U256::from(c);
+    let (quotient, remainder) = product.div_mod(divisor);
+
+    let result = match rounding {
+        Rounding::Up if !remainder.is_zero() => quotient + U256::one(),
+        _ => quotient,
+    };
+
+    require!(result <= U256::from(u128::MAX), MinidexError::MathOverflow);
+
+    Ok(result.as_u128())
+}
+
+pub trait IntegerSqrt {
+    /// Largest integer whose square does not exceed `self`.
+    fn integer_sqrt(self) -> Self;
+}
+
+impl IntegerSqrt for u128 {
+    fn integer_sqrt(self) -> Self {
+        if self < 2 {
+            return self;
+        }
+
+        let mut x = self;
+        let mut y = self.div_ceil(2);
+
+        while y < x {
+            x = y;
+            y = (y + self / y) / 2;
+        }
+
+        x
+    }
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::*;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Pool {
@@ -14,9 +16,236 @@ pub struct Pool {
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub total_lp_supply: u64,
+    pub guardian: Pubkey,
+    pub status: u8,
+    pub max_price_impact_bps: u16,
+    pub max_slot_price_move_bps: u16,
+    pub last_trade_slot: u64,
+    pub slot_start_reserve_a: u64,
+    pub slot_start_reserve_b: u64,
+    /// Largest share of the swap fee, in bps, a swap may pay its referrer
+    pub max_referral_bps: u16,
+    /// Fee bps added per 10,000 bps of accumulated volatility. Zero keeps the fee at `fee_rate`
+    pub volatility_fee_multiplier: u16,
+    /// Lowest fee in the dynamic fee mode
+    pub min_fee_rate: u16,
+    /// Recent price movement in bps, halved every `VOLATILITY_HALF_LIFE_SLOTS`
+    pub volatility_accumulator: u64,
+    /// Slot up to which the accumulator has been decayed
+    pub volatility_update_slot: u64,
+    /// Only wallets granted a `PoolPermission` may trade or provide liquidity
+    pub allowlist_enabled: bool,
+}
+
+impl Pool {
+    /// Fee charged on swaps, in bps. In the dynamic fee mode it rises from
+    /// `min_fee_rate` with the volatility accumulator, up to `MAX_FEE_RATE`.
+    pub fn effective_fee_rate(&self) -> u16 {
+        if self.volatility_fee_multiplier == 0 {
+            return self.fee_rate;
+        }
+
+        let volatility_fee = self.volatility_accumulator as u128 * self.volatility_fee_multiplier as u128
+            / BPS_DENOMINATOR as u128;
+
+        (self.min_fee_rate as u128 + volatility_fee).min(MAX_FEE_RATE as u128) as u16
+    }
+
+    pub fn swaps_paused(&self) -> bool {
+        self.status & POOL_SWAPS_PAUSED != 0
+    }
+
+    pub fn deposits_paused(&self) -> bool {
+        self.status & POOL_DEPOSITS_PAUSED != 0
+    }
+
+    pub fn withdrawals_paused(&self) -> bool {
+        self.status & POOL_WITHDRAWALS_PAUSED != 0
+    }
+}
+
+/// Lets `user` trade and provide liquidity in a pool with the allowlist enabled.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolPermission {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+/// Settings shared by every pool. There is a single one, created by the
+/// program's upgrade authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Token staked for swap fee discounts
+    pub governance_mint: Pubkey,
+    pub bump: u8,
+    /// Ordered by `min_stake`, unused tiers are all zero
+    pub fee_discount_tiers: [FeeDiscountTier; MAX_FEE_DISCOUNT_TIERS],
+}
+
+/// Swappers with at least `min_stake` governance tokens staked pay
+/// `discount_bps` less of the pool's fee.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct FeeDiscountTier {
+    pub min_stake: u64,
+    pub discount_bps: u16,
+}
+
+/// Governance tokens one owner has staked for fee discounts.
+#[account]
+#[derive(InitSpace)]
+pub struct UserStake {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub staked: u64,
+    /// Stake added in this slot earns no discount until the next one, and
+    /// none of the stake can be withdrawn until the cooldown after it ends
+    pub last_stake_slot: u64,
+    pub bump: u8,
+}
+
+/// Once created by the config admin and enabled, only registered mints can
+/// be paired in new pools.
+#[account]
+#[derive(InitSpace)]
+pub struct TokenRegistry {
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+/// A mint the config admin accepts for new pools.
+#[account]
+#[derive(InitSpace)]
+pub struct RegisteredToken {
+    pub mint: Pubkey,
+    /// Admin override accepting the mint even though it has a freeze authority
+    pub skip_mint_checks: bool,
+    pub bump: u8,
 }
 
+/// One reward stream of a farm. Slots whose `mint` is the default key are unused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct FarmReward {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    /// Tokens emitted per second from `start_time` until `end_time`
+    pub emission_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Time up to which `reward_per_share` includes the emissions
+    pub last_update_time: i64,
+    /// Rewards per staked LP token so far, scaled by `REWARD_PER_SHARE_SCALE`
+    pub reward_per_share: u128,
+}
 
+/// Stakes LP tokens of `pool` and pays them rewards funded by the pool authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Farm {
+    pub pool: Pubkey,
+    pub lp_vault: Pubkey,
+    pub total_staked: u64,
+    pub bump: u8,
+    pub rewards: [FarmReward; MAX_FARM_REWARDS],
+}
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct PositionReward {
+    /// The farm's `reward_per_share` when the position was last settled
+    pub reward_per_share_paid: u128,
+    /// Earned and not yet claimed
+    pub pending: u64,
+}
 
+/// LP tokens one owner has staked in a farm, and the rewards they earned.
+#[account]
+#[derive(InitSpace)]
+pub struct FarmPosition {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub staked: u64,
+    pub bump: u8,
+    pub rewards: [PositionReward; MAX_FARM_REWARDS],
+}
 
+/// Sells `amount_in` of one pool token once the pool pays at least
+/// `min_amount_out` for it. The input and keeper fee wait in `escrow`.
+#[account]
+#[derive(InitSpace)]
+pub struct LimitOrder {
+    pub pool: Pubkey,
+    pub maker: Pubkey,
+    /// Chosen by the maker to tell their orders apart
+    pub order_id: u64,
+    pub sell_token_a: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    /// Paid from the escrow, in the input token, to the keeper that fills the order
+    pub keeper_fee: u64,
+    pub escrow: Pubkey,
+    /// The maker's account for the output token
+    pub receive_account: Pubkey,
+    pub bump: u8,
+}
+
+/// Swaps `amount_per_slice` of one pool token every `interval` seconds until
+/// `slice_count` slices have run. The unswapped input waits in `escrow`.
+#[account]
+#[derive(InitSpace)]
+pub struct Dca {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// Chosen by the owner to tell their schedules apart
+    pub dca_id: u64,
+    pub sell_token_a: bool,
+    pub amount_per_slice: u64,
+    pub slice_count: u32,
+    pub slices_executed: u32,
+    /// Seconds between slices
+    pub interval: i64,
+    /// Earliest time the next slice may run
+    pub next_slice_time: i64,
+    /// Smallest output each slice accepts
+    pub min_amount_out_per_slice: u64,
+    pub escrow: Pubkey,
+    /// The owner's account for the output token
+    pub receive_account: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub price_impact_bps: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoveLiquidityQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SingleSidedDepositQuote {
+    /// Part of the deposit swapped for the other token
+    pub swap_amount: u64,
+    pub swap_output: u64,
+    pub fee_amount: u64,
+    pub lp_tokens: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SingleSidedWithdrawalQuote {
+    /// Proportional withdrawal, as `remove_liquidity` pays it
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// What the unwanted side swaps for
+    pub swap_output: u64,
+    pub fee_amount: u64,
+    /// Total of the wanted token paid out
+    pub amount_out: u64,
+}
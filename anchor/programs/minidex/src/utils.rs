@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::errors::*;
+use crate::constants::*;
+use crate::math::*;
 
 pub fn calculate_swap_output(pool: &Pool, amount: u64, token_a_to_b: bool) -> Result<u64> {
     // Implementation of swap output calculation
@@ -9,23 +12,404 @@ pub fn calculate_swap_output(pool: &Pool, amount: u64, token_a_to_b: bool) -> Re
         (pool.reserve_b, pool.reserve_a)
     };
 
-    let fee_adjusted_amount_in = (amount as u128)
-        .checked_mul(10000u128.checked_sub(pool.fee_rate as u128).unwrap())
-        .unwrap()
-        .checked_div(10000)
-        .unwrap();
+    let fee_adjusted_amount_in = amount.safe_sub(calculate_swap_fee(pool, amount)?)? as u128;
 
+    let denominator = (reserve_in as u128).safe_add(fee_adjusted_amount_in)?;
 
-    let numerator = fee_adjusted_amount_in
-        .checked_mul(reserve_out as u128)
-        .unwrap();
-    
-    let denominator = (reserve_in as u128)
-        .checked_add(fee_adjusted_amount_in)
-        .unwrap();
+    // The user receives the output, so it rounds down
+    let amount_out = mul_div_u128(fee_adjusted_amount_in, reserve_out as u128, denominator, Rounding::Down)?;
 
-    let amount_out = numerator.checked_div(denominator).unwrap() as u64;
-    
-    Ok(amount_out)
+    to_u64(amount_out)
 }
 
+/// Relative change of the price of token A (quoted in token B) between two
+/// reserve snapshots, in basis points.
+pub fn calculate_price_move_bps(
+    reserve_a_before: u64,
+    reserve_b_before: u64,
+    reserve_a_after: u64,
+    reserve_b_after: u64,
+) -> Result<u64> {
+    // price_after / price_before = (b_after * a_before) / (b_before * a_after)
+    let price_after = (reserve_b_after as u128).safe_mul(reserve_a_before as u128)?;
+    let price_before = (reserve_b_before as u128).safe_mul(reserve_a_after as u128)?;
+
+    require!(price_before > 0, MinidexError::InsufficientLiquidity);
+
+    let delta = price_after.abs_diff(price_before);
+
+    // Moves too large to represent saturate, they exceed any configured limit anyway
+    Ok(mul_div_u128(delta, BPS_DENOMINATOR as u128, price_before, Rounding::Up)
+        .ok()
+        .and_then(|move_bps| u64::try_from(move_bps).ok())
+        .unwrap_or(u64::MAX))
+}
+
+/// Portion of `amount` kept by the pool as the swap fee.
+pub fn calculate_swap_fee(pool: &Pool, amount: u64) -> Result<u64> {
+    // The user pays the fee, so it rounds up
+    mul_div(amount, pool.effective_fee_rate() as u64, BPS_DENOMINATOR, Rounding::Up)
+}
+
+/// Halves the volatility accumulator once for every `VOLATILITY_HALF_LIFE_SLOTS`
+/// since it was last decayed.
+pub fn decay_volatility(pool: &mut Pool, current_slot: u64) {
+    // Half-lives count from the first move after a calm period
+    if pool.volatility_accumulator == 0 {
+        pool.volatility_update_slot = current_slot;
+        return;
+    }
+
+    let half_lives = current_slot.saturating_sub(pool.volatility_update_slot) / VOLATILITY_HALF_LIFE_SLOTS;
+
+    pool.volatility_accumulator = pool.volatility_accumulator.checked_shr(half_lives.min(64) as u32).unwrap_or(0);
+    // Only whole half-lives are consumed, the rest counts toward the next one
+    pool.volatility_update_slot += half_lives * VOLATILITY_HALF_LIFE_SLOTS;
+}
+
+/// `pool` as a swap at `current_slot` sees it, with the accumulator decayed.
+fn pool_at_slot(pool: &Pool, current_slot: u64) -> Pool {
+    let mut pool = pool.clone();
+    decay_volatility(&mut pool, current_slot);
+    pool
+}
+
+/// Adds the price move of a swap leaving the pool at `reserve_a_after` /
+/// `reserve_b_after` to the volatility accumulator. Call before the reserves
+/// are updated.
+pub fn record_swap_volatility(pool: &mut Pool, reserve_a_after: u64, reserve_b_after: u64, current_slot: u64) -> Result<()> {
+    decay_volatility(pool, current_slot);
+
+    let price_move_bps = calculate_price_move_bps(pool.reserve_a, pool.reserve_b, reserve_a_after, reserve_b_after)?;
+    pool.volatility_accumulator = pool.volatility_accumulator.saturating_add(price_move_bps).min(MAX_VOLATILITY_ACCUMULATOR);
+
+    Ok(())
+}
+
+/// Fails unless the pool is open to everyone or the user holds a permission for it.
+pub fn check_allowlist(pool: &Pool, has_permission: bool) -> Result<()> {
+    require!(!pool.allowlist_enabled || has_permission, MinidexError::NotAllowlisted);
+    Ok(())
+}
+
+/// Part of `fee_amount` paid to a referrer taking `referral_bps` of it.
+pub fn calculate_referral_fee(pool: &Pool, fee_amount: u64, referral_bps: u16) -> Result<u64> {
+    require!(referral_bps as u64 <= BPS_DENOMINATOR, MinidexError::InvalidReferralFee);
+    require!(referral_bps <= pool.max_referral_bps, MinidexError::ReferralFeeTooHigh);
+
+    // Whatever the referrer does not get stays with the LPs, so it rounds down
+    mul_div(fee_amount, referral_bps as u64, BPS_DENOMINATOR, Rounding::Down)
+}
+
+/// Discount on the swap fee, in bps, for the owner of `stake` swapping at
+/// `current_slot`. Stake added in the same slot does not count, so it cannot
+/// be borrowed for a single transaction.
+pub fn calculate_fee_discount_bps(config: &Config, stake: &UserStake, current_slot: u64) -> u16 {
+    if stake.last_stake_slot >= current_slot {
+        return 0;
+    }
+
+    config
+        .fee_discount_tiers
+        .iter()
+        .filter(|tier| tier.min_stake > 0 && stake.staked >= tier.min_stake)
+        .map(|tier| tier.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+/// `pool` as a swapper with `discount_bps` off the fee prices swaps at
+/// `current_slot`: its fee rate is fixed at the discounted effective rate.
+pub fn apply_fee_discount(pool: &Pool, discount_bps: u16, current_slot: u64) -> Result<Pool> {
+    let pool = pool_at_slot(pool, current_slot);
+    let discount_complement = BPS_DENOMINATOR.safe_sub(discount_bps as u64)?;
+
+    // The user pays the fee, so it rounds up
+    let fee_rate = mul_div(pool.effective_fee_rate() as u64, discount_complement, BPS_DENOMINATOR, Rounding::Up)?;
+
+    Ok(Pool {
+        fee_rate: fee_rate as u16,
+        volatility_fee_multiplier: 0,
+        ..pool
+    })
+}
+
+/// Pool reserves `(reserve_a, reserve_b)` once a swap has settled.
+pub fn calculate_reserves_after_swap(pool: &Pool, amount_in: u64, amount_out: u64, token_a_to_b: bool) -> Result<(u64, u64)> {
+    let reserve_out = if token_a_to_b { pool.reserve_b } else { pool.reserve_a };
+
+    require!(amount_out < reserve_out, MinidexError::InsufficientLiquidity);
+
+    if token_a_to_b {
+        Ok((pool.reserve_a.safe_add(amount_in)?, pool.reserve_b.safe_sub(amount_out)?))
+    } else {
+        Ok((pool.reserve_a.safe_sub(amount_out)?, pool.reserve_b.safe_add(amount_in)?))
+    }
+}
+
+/// Enforces the pool's price impact and per-slot price move limits for a swap
+/// that would leave the pool at `reserve_a_after` / `reserve_b_after`.
+pub fn check_price_limits(pool: &Pool, reserve_a_after: u64, reserve_b_after: u64, current_slot: u64) -> Result<()> {
+    if pool.max_price_impact_bps > 0 {
+        let price_impact_bps = calculate_price_move_bps(
+            pool.reserve_a,
+            pool.reserve_b,
+            reserve_a_after,
+            reserve_b_after,
+        )?;
+        require!(
+            price_impact_bps <= pool.max_price_impact_bps as u64,
+            MinidexError::PriceImpactExceeded
+        );
+    }
+
+    if pool.max_slot_price_move_bps > 0 {
+        // The first swap in a slot measures from the current reserves
+        let (slot_start_reserve_a, slot_start_reserve_b) = if pool.last_trade_slot == current_slot {
+            (pool.slot_start_reserve_a, pool.slot_start_reserve_b)
+        } else {
+            (pool.reserve_a, pool.reserve_b)
+        };
+
+        let slot_price_move_bps = calculate_price_move_bps(
+            slot_start_reserve_a,
+            slot_start_reserve_b,
+            reserve_a_after,
+            reserve_b_after,
+        )?;
+        require!(
+            slot_price_move_bps <= pool.max_slot_price_move_bps as u64,
+            MinidexError::SlotPriceMoveExceeded
+        );
+    }
+
+    Ok(())
+}
+
+/// LP tokens minted for depositing `amount_a` and `amount_b`. The first deposit
+/// locks `MINIMUM_LIQUIDITY` out of the minted amount.
+pub fn calculate_lp_tokens(pool: &Pool, amount_a: u64, amount_b: u64) -> Result<u64> {
+    // The user receives LP tokens, so every path rounds down
+    if pool.total_lp_supply == 0 {
+        let lp_amount = to_u64((amount_a as u128).safe_mul(amount_b as u128)?.integer_sqrt())?;
+
+        Ok(lp_amount
+            .checked_sub(MINIMUM_LIQUIDITY)
+            .ok_or(MinidexError::InsufficientLiquidity)?)
+    } else {
+        let lp_from_a = mul_div(amount_a, pool.total_lp_supply, pool.reserve_a, Rounding::Down)?;
+        let lp_from_b = mul_div(amount_b, pool.total_lp_supply, pool.reserve_b, Rounding::Down)?;
+
+        Ok(std::cmp::min(lp_from_a, lp_from_b))
+    }
+}
+
+/// Token amounts `(amount_a, amount_b)` paid out for burning `lp_tokens`.
+pub fn calculate_withdrawal_amounts(pool: &Pool, lp_tokens: u64) -> Result<(u64, u64)> {
+    // The user receives both amounts, so they round down
+    let amount_a = mul_div(lp_tokens, pool.reserve_a, pool.total_lp_supply, Rounding::Down)?;
+    let amount_b = mul_div(lp_tokens, pool.reserve_b, pool.total_lp_supply, Rounding::Down)?;
+
+    require!(amount_a <= pool.reserve_a, MinidexError::InsufficientLiquidity);
+    require!(amount_b <= pool.reserve_b, MinidexError::InsufficientLiquidity);
+
+    Ok((amount_a, amount_b))
+}
+
+/// Runs the same checks and math as `swap_tokens` against `pool` without
+/// moving any tokens.
+pub fn calculate_swap_quote(pool: &Pool, amount_in: u64, token_a_to_b: bool, current_slot: u64) -> Result<SwapQuote> {
+    let pool = &pool_at_slot(pool, current_slot);
+
+    require!(amount_in > 0, MinidexError::ZeroSwapAmount);
+    require!(!pool.swaps_paused(), MinidexError::SwapsPaused);
+    require!(pool.reserve_a > 0 && pool.reserve_b > 0, MinidexError::InsufficientLiquidity);
+
+    let amount_out = calculate_swap_output(pool, amount_in, token_a_to_b)?;
+    let fee_amount = calculate_swap_fee(pool, amount_in)?;
+
+    let (reserve_a_after, reserve_b_after) = calculate_reserves_after_swap(pool, amount_in, amount_out, token_a_to_b)?;
+
+    check_price_limits(pool, reserve_a_after, reserve_b_after, current_slot)?;
+
+    let price_impact_bps = calculate_price_move_bps(
+        pool.reserve_a,
+        pool.reserve_b,
+        reserve_a_after,
+        reserve_b_after,
+    )?;
+
+    Ok(SwapQuote {
+        amount_out,
+        fee_amount,
+        price_impact_bps,
+    })
+}
+
+/// Runs the same checks and math as `add_liquidity` against `pool` and returns
+/// the LP tokens it would mint.
+pub fn calculate_add_liquidity_quote(pool: &Pool, amount_a: u64, amount_b: u64) -> Result<u64> {
+    require!(amount_a > 0 && amount_b > 0, MinidexError::ZeroAmount);
+    require!(!pool.deposits_paused(), MinidexError::DepositsPaused);
+
+    let lp_tokens = calculate_lp_tokens(pool, amount_a, amount_b)?;
+
+    require!(lp_tokens > 0, MinidexError::ZeroLPTokens);
+
+    Ok(lp_tokens)
+}
+
+/// Runs the same checks and math as `remove_liquidity` against `pool` and
+/// returns the token amounts it would pay out.
+pub fn calculate_remove_liquidity_quote(pool: &Pool, lp_tokens: u64) -> Result<RemoveLiquidityQuote> {
+    require!(lp_tokens > 0, MinidexError::ZeroLPTokens);
+    require!(!pool.withdrawals_paused(), MinidexError::WithdrawalsPaused);
+    require!(pool.total_lp_supply > 0, MinidexError::EmptyPool);
+    require!(pool.reserve_a > 0 && pool.reserve_b > 0, MinidexError::InsufficientLiquidity);
+
+    let (amount_a, amount_b) = calculate_withdrawal_amounts(pool, lp_tokens)?;
+
+    Ok(RemoveLiquidityQuote { amount_a, amount_b })
+}
+
+/// Part of a single-sided deposit of `amount_in` to swap first, so that the
+/// rest and the swap output match the pool's reserve ratio after the swap.
+pub fn calculate_single_sided_swap_amount(pool: &Pool, amount_in: u64, token_is_a: bool) -> Result<u64> {
+    let reserve_in = if token_is_a { pool.reserve_a } else { pool.reserve_b };
+
+    // Solves (amount_in - s) / (reserve_in + s) = out(s) / (reserve_out - out(s)) for s:
+    // s = (sqrt(b^2 + 4 * g * F * R * x) - b) / (2 * g) with b = R * (2F - fee), g = F - fee
+    let fee_complement = BPS_DENOMINATOR.safe_sub(pool.effective_fee_rate() as u64)?;
+    require!(fee_complement > 0, MinidexError::InvalidFeeRate);
+    let b = U256::from(reserve_in) * U256::from(fee_complement + BPS_DENOMINATOR);
+    let discriminant = b * b
+        + U256::from(4 * fee_complement * BPS_DENOMINATOR) * U256::from(reserve_in) * U256::from(amount_in);
+
+    // Rounding down leaves the deposit a little heavy on the input side, the
+    // pool keeps that remainder
+    let swap_amount = (discriminant.integer_sqrt() - b) / U256::from(2 * fee_complement);
+
+    Ok(std::cmp::min(swap_amount.as_u64(), amount_in))
+}
+
+/// Runs the same checks and math as `add_liquidity_single_sided` against
+/// `pool`: swaps part of `amount_in` through the pool and deposits the rest
+/// together with the swap output.
+pub fn calculate_single_sided_deposit_quote(
+    pool: &Pool,
+    amount_in: u64,
+    token_is_a: bool,
+    current_slot: u64,
+) -> Result<SingleSidedDepositQuote> {
+    let pool = &pool_at_slot(pool, current_slot);
+
+    require!(amount_in > 0, MinidexError::ZeroAmount);
+    require!(!pool.deposits_paused(), MinidexError::DepositsPaused);
+    require!(!pool.swaps_paused(), MinidexError::SwapsPaused);
+    require!(pool.reserve_a > 0 && pool.reserve_b > 0, MinidexError::InsufficientLiquidity);
+
+    let swap_amount = calculate_single_sided_swap_amount(pool, amount_in, token_is_a)?;
+    let swap_output = calculate_swap_output(pool, swap_amount, token_is_a)?;
+    let fee_amount = calculate_swap_fee(pool, swap_amount)?;
+
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(pool, swap_amount, swap_output, token_is_a)?;
+    check_price_limits(pool, reserve_a, reserve_b, current_slot)?;
+
+    let after_swap = Pool { reserve_a, reserve_b, ..pool.clone() };
+    let deposit = amount_in.safe_sub(swap_amount)?;
+    let (amount_a, amount_b) = if token_is_a { (deposit, swap_output) } else { (swap_output, deposit) };
+
+    let lp_tokens = calculate_lp_tokens(&after_swap, amount_a, amount_b)?;
+    require!(lp_tokens > 0, MinidexError::ZeroLPTokens);
+
+    Ok(SingleSidedDepositQuote {
+        swap_amount,
+        swap_output,
+        fee_amount,
+        lp_tokens,
+    })
+}
+
+/// Runs the same checks and math as `remove_liquidity_single_sided` against
+/// `pool`: withdraws `lp_tokens` proportionally and swaps the side not wanted
+/// back through the pool.
+pub fn calculate_single_sided_withdrawal_quote(
+    pool: &Pool,
+    lp_tokens: u64,
+    want_token_a: bool,
+    current_slot: u64,
+) -> Result<SingleSidedWithdrawalQuote> {
+    let pool = &pool_at_slot(pool, current_slot);
+
+    let RemoveLiquidityQuote { amount_a, amount_b } = calculate_remove_liquidity_quote(pool, lp_tokens)?;
+    require!(!pool.swaps_paused(), MinidexError::SwapsPaused);
+
+    let after_withdrawal = Pool {
+        reserve_a: pool.reserve_a.safe_sub(amount_a)?,
+        reserve_b: pool.reserve_b.safe_sub(amount_b)?,
+        total_lp_supply: pool.total_lp_supply.safe_sub(lp_tokens)?,
+        ..pool.clone()
+    };
+    require!(
+        after_withdrawal.reserve_a > 0 && after_withdrawal.reserve_b > 0,
+        MinidexError::InsufficientLiquidity
+    );
+
+    let (swap_amount, wanted) = if want_token_a { (amount_b, amount_a) } else { (amount_a, amount_b) };
+    let token_a_to_b = !want_token_a;
+
+    let swap_output = calculate_swap_output(&after_withdrawal, swap_amount, token_a_to_b)?;
+    let fee_amount = calculate_swap_fee(&after_withdrawal, swap_amount)?;
+
+    let (reserve_a, reserve_b) =
+        calculate_reserves_after_swap(&after_withdrawal, swap_amount, swap_output, token_a_to_b)?;
+    check_price_limits(&after_withdrawal, reserve_a, reserve_b, current_slot)?;
+
+    Ok(SingleSidedWithdrawalQuote {
+        amount_a,
+        amount_b,
+        swap_output,
+        fee_amount,
+        amount_out: wanted.safe_add(swap_output)?,
+    })
+}
+
+/// Adds the rewards each stream emitted since its last update, up to `now`,
+/// to the farm's reward-per-share accumulators.
+pub fn update_farm_rewards(farm: &mut Farm, now: i64) -> Result<()> {
+    let total_staked = farm.total_staked;
+
+    for reward in farm.rewards.iter_mut().filter(|reward| reward.mint != Pubkey::default()) {
+        let from = reward.last_update_time.max(reward.start_time);
+        let to = now.min(reward.end_time);
+
+        if to > from {
+            if total_staked > 0 {
+                let emitted = (reward.emission_rate as u128).safe_mul((to - from) as u128)?;
+                let per_share = mul_div_u128(emitted, REWARD_PER_SHARE_SCALE, total_staked as u128, Rounding::Down)?;
+                reward.reward_per_share = reward.reward_per_share.safe_add(per_share)?;
+            } else {
+                // Nobody is staked to earn it, so the rest of the schedule starts over from now
+                reward.end_time = reward.end_time.saturating_add(now - from);
+            }
+        }
+
+        reward.last_update_time = reward.last_update_time.max(now);
+    }
+
+    Ok(())
+}
+
+/// Books what `position` earned since it was last settled as pending. The
+/// farm must be updated first.
+pub fn settle_position_rewards(farm: &Farm, position: &mut FarmPosition) -> Result<()> {
+    for (reward, earned) in farm.rewards.iter().zip(position.rewards.iter_mut()) {
+        let delta = reward.reward_per_share.safe_sub(earned.reward_per_share_paid)?;
+        let amount = to_u64(mul_div_u128(position.staked as u128, delta, REWARD_PER_SHARE_SCALE, Rounding::Down)?)?;
+
+        earned.pending = earned.pending.safe_add(amount)?;
+        earned.reward_per_share_paid = reward.reward_per_share;
+    }
+
+    Ok(())
+}
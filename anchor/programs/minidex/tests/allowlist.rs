@@ -0,0 +1,224 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use minidex_sdk::instructions;
+use minidex_sdk::{find_dca_address, find_limit_order_address, find_permission_address, Dca, LimitOrder, MinidexError, PoolKeys, PoolPermission};
+
+/// A 1:4 pool with the allowlist enabled and nobody granted access yet.
+fn setup() -> (TestPool, User) {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let authority = test.authority;
+    test.set_allowlist(&authority, true).unwrap();
+
+    (test, provider)
+}
+
+fn grant(test: &mut TestPool, user: &User) {
+    let authority = test.authority;
+    test.grant_access(&authority, &user.key).unwrap();
+}
+
+/// The permission account of `user` on the pool, whether or not it exists.
+fn permission_of(keys: &PoolKeys, user: &User) -> Option<Pubkey> {
+    Some(find_permission_address(&keys.pool, &user.key).0)
+}
+
+fn swap(keys: &PoolKeys, user: &User, amount_in: u64, permission: Option<Pubkey>) -> Instruction {
+    instructions::swap_tokens(&user.key, keys, &user.token_a, &user.token_b, amount_in, 0, true, permission)
+}
+
+fn add_liquidity(keys: &PoolKeys, user: &User, amount_a: u64, amount_b: u64, permission: Option<Pubkey>) -> Instruction {
+    instructions::add_liquidity(&user.key, keys, &user.token_a, &user.token_b, amount_a, amount_b, 0, permission)
+}
+
+fn remove_liquidity(keys: &PoolKeys, user: &User, lp_tokens: u64, permission: Option<Pubkey>) -> Instruction {
+    instructions::remove_liquidity(&user.key, keys, &user.token_a, &user.token_b, lp_tokens, 0, 0, permission)
+}
+
+fn place_order(keys: &PoolKeys, user: &User, permission: Option<Pubkey>) -> Instruction {
+    instructions::place_limit_order(&user.key, keys, &user.token_a, &user.token_b, 1, true, 1_000, 1, 0, permission)
+}
+
+fn open_dca(keys: &PoolKeys, user: &User, permission: Option<Pubkey>) -> Instruction {
+    instructions::open_dca(&user.key, keys, &user.token_a, &user.token_b, 1, true, 1_000, 2, 60, 1, permission)
+}
+
+/// Fills `maker`'s order from `keeper`, who holds the input token.
+fn fill(test: &TestPool, maker: &User, keeper: &User, permission: Option<Pubkey>) -> Instruction {
+    let order_address = find_limit_order_address(&test.keys.pool, &maker.key, 1).0;
+    let order: LimitOrder = test.svm.anchor_account(&order_address).unwrap();
+    instructions::fill_limit_order(&keeper.key, &test.keys, &order_address, &order, &keeper.token_a, permission)
+}
+
+fn execute_slice(test: &TestPool, owner: &User, cranker: &User, permission: Option<Pubkey>) -> Instruction {
+    let dca_address = find_dca_address(&test.keys.pool, &owner.key, 1).0;
+    let dca: Dca = test.svm.anchor_account(&dca_address).unwrap();
+    instructions::execute_dca_slice(&cranker.key, &test.keys, &dca_address, &dca, permission)
+}
+
+#[test]
+fn wallets_without_access_cannot_trade_or_provide_liquidity() {
+    let (mut test, provider) = setup();
+    let stranger = test.create_user(100_000, 400_000);
+    let lp_tokens = test.lp_balance(&provider);
+
+    assert_minidex_error(test.swap(&stranger, 1_000, 0, true), MinidexError::NotAllowlisted);
+    assert_minidex_error(test.add_liquidity(&stranger, 1_000, 4_000, 0), MinidexError::NotAllowlisted);
+    assert_minidex_error(test.add_liquidity_single_sided(&stranger, 1_000, true, 0), MinidexError::NotAllowlisted);
+
+    // Liquidity added before the allowlist was enabled stays locked until access is granted
+    assert_minidex_error(test.remove_liquidity(&provider, lp_tokens, 0, 0), MinidexError::NotAllowlisted);
+    assert_minidex_error(test.remove_liquidity_single_sided(&provider, 1_000, true, 0), MinidexError::NotAllowlisted);
+
+    assert_minidex_error(test.send(place_order(&test.keys, &stranger, None), &stranger.key), MinidexError::NotAllowlisted);
+    assert_minidex_error(test.send(open_dca(&test.keys, &stranger, None), &stranger.key), MinidexError::NotAllowlisted);
+}
+
+#[test]
+fn granted_wallets_trade_and_provide_liquidity_as_usual() {
+    let (mut test, provider) = setup();
+    let keys = test.keys;
+    let trader = test.create_user(100_000, 400_000);
+    grant(&mut test, &trader);
+    grant(&mut test, &provider);
+
+    let permission: PoolPermission = test.svm.anchor_account(&find_permission_address(&test.keys.pool, &trader.key).0).unwrap();
+    assert_eq!((permission.pool, permission.user), (test.keys.pool, trader.key));
+
+    test.send(swap(&keys, &trader, 10_000, permission_of(&keys, &trader)), &trader.key).unwrap();
+    test.send(add_liquidity(&keys, &trader, 10_000, 40_000, permission_of(&keys, &trader)), &trader.key).unwrap();
+
+    let lp_tokens = test.lp_balance(&trader);
+    assert!(lp_tokens > 0);
+    test.send(remove_liquidity(&keys, &trader, lp_tokens, permission_of(&keys, &trader)), &trader.key).unwrap();
+    assert_eq!(test.lp_balance(&trader), 0);
+
+    let provider_lp_tokens = test.lp_balance(&provider);
+    test.send(remove_liquidity(&keys, &provider, provider_lp_tokens / 2, permission_of(&keys, &provider)), &provider.key).unwrap();
+}
+
+#[test]
+fn a_permission_only_covers_its_own_wallet() {
+    let (mut test, _) = setup();
+    let granted = test.create_user(0, 0);
+    let stranger = test.create_user(100_000, 0);
+    grant(&mut test, &granted);
+
+    let instruction = swap(&test.keys, &stranger, 1_000, permission_of(&test.keys, &granted));
+
+    assert_error(
+        test.send(instruction, &stranger.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds),
+    );
+}
+
+#[test]
+fn revoking_access_closes_the_permission() {
+    let (mut test, _) = setup();
+    let keys = test.keys;
+    let authority = test.authority;
+    let trader = test.create_user(100_000, 0);
+    grant(&mut test, &trader);
+
+    let permission = find_permission_address(&test.keys.pool, &trader.key).0;
+    let rent = test.svm.lamports(&permission);
+    let authority_lamports = test.svm.lamports(&authority);
+
+    test.send(instructions::revoke_access(&authority, &test.keys.pool, &trader.key), &authority).unwrap();
+
+    assert!(test.svm.account(&permission).is_none());
+    assert_eq!(test.svm.lamports(&authority), authority_lamports + rent);
+
+    // Without the account the trader is back to a stranger
+    assert_minidex_error(test.swap(&trader, 1_000, 0, true), MinidexError::NotAllowlisted);
+    assert!(test.send(swap(&keys, &trader, 1_000, permission_of(&keys, &trader)), &trader.key).is_err());
+}
+
+#[test]
+fn disabling_the_allowlist_reopens_the_pool() {
+    let (mut test, _) = setup();
+    let authority = test.authority;
+    let trader = test.create_user(100_000, 0);
+
+    test.set_allowlist(&authority, false).unwrap();
+    assert!(!test.pool().allowlist_enabled);
+
+    test.swap(&trader, 1_000, 0, true).unwrap();
+}
+
+#[test]
+fn only_the_pool_authority_manages_the_allowlist() {
+    let (mut test, _) = setup();
+    let stranger = test.create_user(0, 0);
+    let user = Pubkey::new_unique();
+
+    assert_minidex_error(test.set_allowlist(&stranger.key, false), MinidexError::Unauthorized);
+    assert_minidex_error(test.grant_access(&stranger.key, &stranger.key), MinidexError::Unauthorized);
+
+    let authority = test.authority;
+    test.grant_access(&authority, &user).unwrap();
+
+    let revoke = instructions::revoke_access(&stranger.key, &test.keys.pool, &user);
+    assert_minidex_error(test.send(revoke, &stranger.key), MinidexError::Unauthorized);
+}
+
+#[test]
+fn keepers_need_the_access_of_the_wallet_they_trade_for() {
+    let (mut test, _) = setup();
+    let keys = test.keys;
+    let authority = test.authority;
+    let owner = test.create_user(100_000, 0);
+    let keeper = test.create_user(0, 0);
+    grant(&mut test, &owner);
+
+    test.send(place_order(&keys, &owner, permission_of(&keys, &owner)), &owner.key).unwrap();
+    test.send(open_dca(&keys, &owner, permission_of(&keys, &owner)), &owner.key).unwrap();
+
+    // A keeper's own access does not stand in for the owner's
+    grant(&mut test, &keeper);
+    let instruction = fill(&test, &owner, &keeper, permission_of(&keys, &keeper));
+    assert_error(
+        test.send(instruction, &keeper.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds),
+    );
+
+    let instruction = execute_slice(&test, &owner, &keeper, permission_of(&keys, &owner));
+    test.send(instruction, &keeper.key).unwrap();
+
+    // Once revoked, the owner's order and schedule stop executing
+    test.send(instructions::revoke_access(&authority, &keys.pool, &owner.key), &authority).unwrap();
+
+    assert_minidex_error(test.send(fill(&test, &owner, &keeper, None), &keeper.key), MinidexError::NotAllowlisted);
+    let instruction = fill(&test, &owner, &keeper, permission_of(&keys, &owner));
+    assert!(test.send(instruction, &keeper.key).is_err());
+
+    test.svm.set_unix_timestamp(60);
+    assert_minidex_error(test.send(execute_slice(&test, &owner, &keeper, None), &keeper.key), MinidexError::NotAllowlisted);
+    let instruction = execute_slice(&test, &owner, &keeper, permission_of(&keys, &owner));
+    assert!(test.send(instruction, &keeper.key).is_err());
+}
+
+#[test]
+fn orders_placed_before_the_allowlist_wait_for_access() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let keys = test.keys;
+    let authority = test.authority;
+    let owner = test.create_user(100_000, 0);
+    let keeper = test.create_user(0, 0);
+
+    test.send(place_order(&keys, &owner, None), &owner.key).unwrap();
+    test.send(open_dca(&keys, &owner, None), &owner.key).unwrap();
+    test.set_allowlist(&authority, true).unwrap();
+
+    assert_minidex_error(test.send(fill(&test, &owner, &keeper, None), &keeper.key), MinidexError::NotAllowlisted);
+    assert_minidex_error(test.send(execute_slice(&test, &owner, &keeper, None), &keeper.key), MinidexError::NotAllowlisted);
+
+    grant(&mut test, &owner);
+
+    let instruction = fill(&test, &owner, &keeper, permission_of(&keys, &owner));
+    test.send(instruction, &keeper.key).unwrap();
+    let instruction = execute_slice(&test, &owner, &keeper, permission_of(&keys, &owner));
+    test.send(instruction, &keeper.key).unwrap();
+}
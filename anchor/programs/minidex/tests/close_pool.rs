@@ -0,0 +1,123 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use minidex_sdk::constants::MINIMUM_LIQUIDITY;
+use minidex_sdk::instructions;
+use minidex_sdk::MinidexError;
+use minidex_svm::TransactionResult;
+
+/// The authority's token accounts for both pool mints.
+fn authority_accounts(test: &mut TestPool) -> (Pubkey, Pubkey) {
+    let authority = test.authority;
+    let token_a = test.svm.create_associated_token_account(&test.keys.token_a_mint, &authority, 0);
+    let token_b = test.svm.create_associated_token_account(&test.keys.token_b_mint, &authority, 0);
+
+    (token_a, token_b)
+}
+
+fn close_pool(test: &mut TestPool, signer: &Pubkey, accounts: (Pubkey, Pubkey)) -> TransactionResult {
+    let instruction = instructions::close_pool(signer, &test.keys, &accounts.0, &accounts.1);
+    test.send(instruction, signer)
+}
+
+#[test]
+fn closing_an_emptied_pool_returns_the_rent_and_the_locked_residue() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let accounts = authority_accounts(&mut test);
+    let authority = test.authority;
+
+    let lp_tokens = test.lp_balance(&provider);
+    test.remove_liquidity(&provider, lp_tokens, 0, 0).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(pool.total_lp_supply, MINIMUM_LIQUIDITY);
+    let residue_a = test.svm.token_balance(&test.keys.token_a_vault);
+    let residue_b = test.svm.token_balance(&test.keys.token_b_vault);
+    assert_eq!((residue_a, residue_b), (pool.reserve_a, pool.reserve_b));
+    assert!(residue_a > 0 && residue_b > 0);
+
+    let rent = test.svm.lamports(&test.keys.pool)
+        + test.svm.lamports(&test.keys.token_a_vault)
+        + test.svm.lamports(&test.keys.token_b_vault);
+    let lamports_before = test.svm.lamports(&authority);
+
+    close_pool(&mut test, &authority, accounts).unwrap();
+
+    assert_eq!(test.svm.lamports(&authority), lamports_before + rent);
+    assert_eq!(test.svm.token_balance(&accounts.0), residue_a);
+    assert_eq!(test.svm.token_balance(&accounts.1), residue_b);
+    assert!(test.svm.account(&test.keys.pool).is_none());
+    assert!(test.svm.account(&test.keys.token_a_vault).is_none());
+    assert!(test.svm.account(&test.keys.token_b_vault).is_none());
+    // SPL Token mints cannot be closed
+    assert_eq!(test.svm.mint(&test.keys.lp_mint).unwrap().supply, 0);
+}
+
+#[test]
+fn a_pool_that_was_never_seeded_can_be_closed() {
+    let mut test = TestPool::new();
+    let accounts = authority_accounts(&mut test);
+    let authority = test.authority;
+
+    close_pool(&mut test, &authority, accounts).unwrap();
+
+    assert!(test.svm.account(&test.keys.pool).is_none());
+    assert_eq!(test.svm.token_balance(&accounts.0), 0);
+}
+
+#[test]
+fn pools_with_outstanding_lp_tokens_stay_open() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let accounts = authority_accounts(&mut test);
+    let authority = test.authority;
+
+    let lp_tokens = test.lp_balance(&provider);
+    test.remove_liquidity(&provider, lp_tokens - 1, 0, 0).unwrap();
+
+    let result = close_pool(&mut test, &authority, accounts);
+    assert_minidex_error(result, MinidexError::PoolNotEmpty);
+    assert_eq!(test.pool().total_lp_supply, MINIMUM_LIQUIDITY + 1);
+}
+
+#[test]
+fn only_the_authority_can_close_a_pool() {
+    let mut test = TestPool::new();
+    let stranger = test.create_user(0, 0);
+
+    let result = close_pool(&mut test, &stranger.key, (stranger.token_a, stranger.token_b));
+    assert_minidex_error(result, MinidexError::Unauthorized);
+    assert!(test.svm.account(&test.keys.pool).is_some());
+}
+
+#[test]
+fn a_closed_pool_can_be_created_again_with_its_lp_mint() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let accounts = authority_accounts(&mut test);
+    let authority = test.authority;
+
+    let lp_tokens = test.lp_balance(&provider);
+    test.remove_liquidity(&provider, lp_tokens, 0, 0).unwrap();
+    close_pool(&mut test, &authority, accounts).unwrap();
+
+    // The LP mint survives the pool and the usual setup takes it over
+    let lp_mint = test.svm.account(&test.keys.lp_mint).unwrap();
+    let recreate = instructions::create_pool(&authority, &test.keys, DEFAULT_FEE_RATE);
+    test.svm.process_transaction(&recreate, &[authority]).unwrap();
+    assert_eq!(test.svm.account(&test.keys.lp_mint).unwrap(), lp_mint);
+
+    let pool = test.pool();
+    assert_eq!((pool.reserve_a, pool.reserve_b, pool.total_lp_supply), (0, 0, 0));
+
+    test.add_liquidity(&provider, 500_000, 2_000_000, 0).unwrap();
+    assert_eq!(test.pool().total_lp_supply, test.lp_balance(&provider) + MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn the_lp_mint_of_a_live_pool_cannot_be_taken_over() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let authority = test.authority;
+
+    let result = test.send(instructions::initialize_lp_mint(&authority, &test.keys), &authority);
+    assert_minidex_error(result, MinidexError::PoolNotEmpty);
+}
@@ -0,0 +1,232 @@
+//! Shared setup for the integration tests.
+
+#![allow(dead_code)]
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::AnchorDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use minidex_sdk::{instructions, MinidexError, Pool, PoolKeys};
+use minidex_svm::{Svm, TransactionMetadata, TransactionResult};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const DEFAULT_FEE_RATE: u16 = 30;
+
+pub struct User {
+    pub key: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+}
+
+/// A pool created end to end by `authority`, without liquidity.
+pub struct TestPool {
+    pub svm: Svm,
+    pub authority: Pubkey,
+    pub keys: PoolKeys,
+}
+
+/// Creates two fresh mints and a funded authority.
+pub fn setup() -> (Svm, Pubkey, Pubkey, Pubkey) {
+    let mut svm = Svm::new();
+    let authority = Pubkey::new_unique();
+    svm.airdrop(&authority, 10 * LAMPORTS_PER_SOL);
+
+    let mint_a = svm.create_mint(&authority, 6);
+    let mint_b = svm.create_mint(&authority, 6);
+
+    (svm, authority, mint_a, mint_b)
+}
+
+impl TestPool {
+    pub fn new() -> Self {
+        Self::with_fee_rate(DEFAULT_FEE_RATE)
+    }
+
+    pub fn with_fee_rate(fee_rate: u16) -> Self {
+        let (mut svm, authority, mint_a, mint_b) = setup();
+        let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+        svm.process_transaction(&instructions::create_pool(&authority, &keys, fee_rate), &[authority])
+            .expect("pool creation succeeds");
+
+        Self { svm, authority, keys }
+    }
+
+    /// A pool seeded by `provider` with `amount_a` and `amount_b`.
+    pub fn with_liquidity(amount_a: u64, amount_b: u64) -> (Self, User) {
+        let mut pool = Self::new();
+        let provider = pool.create_user(amount_a, amount_b);
+
+        pool.add_liquidity(&provider, amount_a, amount_b, 0)
+            .expect("initial deposit succeeds");
+
+        (pool, provider)
+    }
+
+    pub fn pool(&self) -> Pool {
+        self.svm.anchor_account(&self.keys.pool).expect("pool account exists")
+    }
+
+    /// A funded user holding `amount_a` and `amount_b` in associated token accounts.
+    pub fn create_user(&mut self, amount_a: u64, amount_b: u64) -> User {
+        let key = Pubkey::new_unique();
+        self.svm.airdrop(&key, LAMPORTS_PER_SOL);
+
+        User {
+            key,
+            token_a: self.svm.create_associated_token_account(&self.keys.token_a_mint, &key, amount_a),
+            token_b: self.svm.create_associated_token_account(&self.keys.token_b_mint, &key, amount_b),
+        }
+    }
+
+    pub fn lp_account(&self, user: &User) -> Pubkey {
+        get_associated_token_address(&user.key, &self.keys.lp_mint)
+    }
+
+    pub fn lp_balance(&self, user: &User) -> u64 {
+        self.svm.token_balance(&self.lp_account(user))
+    }
+
+    pub fn send(&mut self, instruction: Instruction, signer: &Pubkey) -> TransactionResult {
+        self.svm.process_transaction(&[instruction], &[*signer])
+    }
+
+    pub fn add_liquidity(&mut self, user: &User, amount_a: u64, amount_b: u64, min_lp_tokens: u64) -> TransactionResult {
+        let instruction = instructions::add_liquidity(
+            &user.key,
+            &self.keys,
+            &user.token_a,
+            &user.token_b,
+            amount_a,
+            amount_b,
+            min_lp_tokens,
+            None,
+        );
+        self.send(instruction, &user.key)
+    }
+
+    pub fn add_liquidity_single_sided(&mut self, user: &User, amount_in: u64, token_is_a: bool, min_lp_tokens: u64) -> TransactionResult {
+        let instruction = instructions::add_liquidity_single_sided(
+            &user.key,
+            &self.keys,
+            &user.token_a,
+            &user.token_b,
+            amount_in,
+            token_is_a,
+            min_lp_tokens,
+            None,
+        );
+        self.send(instruction, &user.key)
+    }
+
+    pub fn remove_liquidity(&mut self, user: &User, lp_tokens: u64, min_amount_a: u64, min_amount_b: u64) -> TransactionResult {
+        let instruction = instructions::remove_liquidity(
+            &user.key,
+            &self.keys,
+            &user.token_a,
+            &user.token_b,
+            lp_tokens,
+            min_amount_a,
+            min_amount_b,
+            None,
+        );
+        self.send(instruction, &user.key)
+    }
+
+    pub fn remove_liquidity_single_sided(&mut self, user: &User, lp_tokens: u64, want_token_a: bool, min_amount_out: u64) -> TransactionResult {
+        let instruction = instructions::remove_liquidity_single_sided(
+            &user.key,
+            &self.keys,
+            &user.token_a,
+            &user.token_b,
+            lp_tokens,
+            want_token_a,
+            min_amount_out,
+            None,
+        );
+        self.send(instruction, &user.key)
+    }
+
+    pub fn swap(&mut self, user: &User, amount_in: u64, min_amount_out: u64, token_a_to_b: bool) -> TransactionResult {
+        let instruction = instructions::swap_tokens(
+            &user.key,
+            &self.keys,
+            &user.token_a,
+            &user.token_b,
+            amount_in,
+            min_amount_out,
+            token_a_to_b,
+            None,
+        );
+        self.send(instruction, &user.key)
+    }
+
+    pub fn set_pool_status(&mut self, signer: &Pubkey, status: u8) -> TransactionResult {
+        let instruction = instructions::set_pool_status(signer, &self.keys.pool, status);
+        self.send(instruction, signer)
+    }
+
+    pub fn set_guardian(&mut self, signer: &Pubkey, guardian: &Pubkey) -> TransactionResult {
+        let instruction = instructions::set_guardian(signer, &self.keys.pool, guardian);
+        self.send(instruction, signer)
+    }
+
+    pub fn set_price_limits(&mut self, signer: &Pubkey, max_price_impact_bps: u16, max_slot_price_move_bps: u16) -> TransactionResult {
+        let instruction = instructions::set_price_limits(signer, &self.keys.pool, max_price_impact_bps, max_slot_price_move_bps);
+        self.send(instruction, signer)
+    }
+
+    pub fn set_max_referral_bps(&mut self, signer: &Pubkey, max_referral_bps: u16) -> TransactionResult {
+        let instruction = instructions::set_max_referral_bps(signer, &self.keys.pool, max_referral_bps);
+        self.send(instruction, signer)
+    }
+
+    pub fn set_allowlist(&mut self, signer: &Pubkey, enabled: bool) -> TransactionResult {
+        let instruction = instructions::set_allowlist(signer, &self.keys.pool, enabled);
+        self.send(instruction, signer)
+    }
+
+    pub fn grant_access(&mut self, signer: &Pubkey, user: &Pubkey) -> TransactionResult {
+        let instruction = instructions::grant_access(signer, &self.keys.pool, user);
+        self.send(instruction, signer)
+    }
+
+    /// Simulates a view instruction and decodes its return data.
+    pub fn view<T: AnchorDeserialize>(&self, instruction: Instruction) -> Result<T, InstructionError> {
+        let metadata = self
+            .svm
+            .simulate_transaction(&[instruction], &[])
+            .map_err(|failed| failed.error)?;
+
+        decode_return_data(&metadata)
+    }
+}
+
+pub fn decode_return_data<T: AnchorDeserialize>(metadata: &TransactionMetadata) -> Result<T, InstructionError> {
+    let (program_id, data) = metadata.return_data.as_ref().expect("instruction returned data");
+    assert_eq!(*program_id, minidex::ID);
+
+    Ok(T::deserialize(&mut data.as_slice()).expect("return data decodes"))
+}
+
+pub fn minidex_error(error: MinidexError) -> InstructionError {
+    InstructionError::Custom(error as u32 + ERROR_CODE_OFFSET)
+}
+
+pub fn anchor_error(error: anchor_lang::error::ErrorCode) -> InstructionError {
+    InstructionError::Custom(error as u32)
+}
+
+#[track_caller]
+pub fn assert_error(result: TransactionResult, expected: InstructionError) {
+    match result {
+        Ok(metadata) => panic!("expected {expected:?}, transaction succeeded: {:#?}", metadata.logs),
+        Err(failed) => assert_eq!(failed.error, expected, "{:#?}", failed.logs),
+    }
+}
+
+#[track_caller]
+pub fn assert_minidex_error(result: TransactionResult, expected: MinidexError) {
+    assert_error(result, minidex_error(expected));
+}
@@ -0,0 +1,230 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use common::*;
+use minidex_sdk::constants::POOL_SWAPS_PAUSED;
+use minidex_sdk::math::calculate_swap_output;
+use minidex_sdk::{find_dca_address, find_dca_escrow_address, instructions, Dca, MinidexError};
+use minidex_svm::TransactionResult;
+
+const DCA_ID: u64 = 3;
+const START: i64 = 1_000;
+const INTERVAL: i64 = 3_600;
+
+/// A 1:1 pool, an owner with 100,000 of each token and the clock at `START`.
+fn setup() -> (TestPool, User) {
+    let (mut test, _) = TestPool::with_liquidity(10_000_000, 10_000_000);
+    let owner = test.create_user(100_000, 100_000);
+    test.svm.set_unix_timestamp(START);
+
+    (test, owner)
+}
+
+fn open(test: &mut TestPool, owner: &User, amount_per_slice: u64, slice_count: u32, interval: i64, min_amount_out: u64) -> TransactionResult {
+    let instruction = instructions::open_dca(
+        &owner.key,
+        &test.keys,
+        &owner.token_a,
+        &owner.token_b,
+        DCA_ID,
+        true,
+        amount_per_slice,
+        slice_count,
+        interval,
+        min_amount_out,
+        None,
+    );
+    test.send(instruction, &owner.key)
+}
+
+fn dca_address(test: &TestPool, owner: &User) -> Pubkey {
+    find_dca_address(&test.keys.pool, &owner.key, DCA_ID).0
+}
+
+fn dca(test: &TestPool, owner: &User) -> Option<Dca> {
+    test.svm.anchor_account(&dca_address(test, owner))
+}
+
+/// Runs the next slice from a fresh cranker.
+fn crank(test: &mut TestPool, owner: &User) -> TransactionResult {
+    let cranker = Pubkey::new_unique();
+    test.svm.airdrop(&cranker, LAMPORTS_PER_SOL);
+
+    let dca = dca(test, owner).expect("schedule exists");
+    let instruction = instructions::execute_dca_slice(&cranker, &test.keys, &dca_address(test, owner), &dca, None);
+    test.send(instruction, &cranker)
+}
+
+/// Closes the finished schedule from a fresh cranker.
+fn close(test: &mut TestPool, owner: &User) -> TransactionResult {
+    let cranker = Pubkey::new_unique();
+    test.svm.airdrop(&cranker, LAMPORTS_PER_SOL);
+
+    let dca = dca(test, owner).expect("schedule exists");
+    let instruction = instructions::close_dca(&cranker, &test.keys, &dca_address(test, owner), &dca);
+    test.send(instruction, &cranker)
+}
+
+#[test]
+fn opening_escrows_the_whole_schedule() {
+    let (mut test, owner) = setup();
+
+    open(&mut test, &owner, 10_000, 4, INTERVAL, 9_000).unwrap();
+
+    let dca = dca(&test, &owner).unwrap();
+    let escrow = find_dca_escrow_address(&dca_address(&test, &owner)).0;
+    assert_eq!((dca.pool, dca.owner, dca.dca_id), (test.keys.pool, owner.key, DCA_ID));
+    assert!(dca.sell_token_a);
+    assert_eq!((dca.amount_per_slice, dca.slice_count, dca.slices_executed), (10_000, 4, 0));
+    assert_eq!((dca.interval, dca.next_slice_time), (INTERVAL, START));
+    assert_eq!(dca.min_amount_out_per_slice, 9_000);
+    assert_eq!((dca.escrow, dca.receive_account), (escrow, owner.token_b));
+
+    assert_eq!(test.svm.token_balance(&escrow), 40_000);
+    assert_eq!(test.svm.token_balance(&owner.token_a), 60_000);
+}
+
+#[test]
+fn slices_run_once_per_interval_until_the_schedule_ends() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 2, INTERVAL, 1).unwrap();
+
+    let first = calculate_swap_output(&test.pool(), 10_000, true).unwrap();
+    crank(&mut test, &owner).unwrap();
+    assert_eq!(test.svm.token_balance(&owner.token_b), 100_000 + first);
+    assert_eq!(dca(&test, &owner).unwrap().next_slice_time, START + INTERVAL);
+
+    test.svm.set_unix_timestamp(START + INTERVAL - 1);
+    assert_minidex_error(crank(&mut test, &owner), MinidexError::DcaSliceNotDue);
+
+    let dca = dca_address(&test, &owner);
+    let escrow = find_dca_escrow_address(&dca).0;
+    let rent = test.svm.lamports(&dca) + test.svm.lamports(&escrow);
+    let owner_lamports = test.svm.lamports(&owner.key);
+
+    test.svm.set_unix_timestamp(START + INTERVAL);
+    let second = calculate_swap_output(&test.pool(), 10_000, true).unwrap();
+    crank(&mut test, &owner).unwrap();
+
+    assert_eq!(test.svm.token_balance(&owner.token_b), 100_000 + first + second);
+    assert_eq!((test.pool().reserve_a, test.pool().reserve_b), (10_020_000, 10_000_000 - first - second));
+
+    test.svm.set_unix_timestamp(START + 2 * INTERVAL);
+    assert_minidex_error(crank(&mut test, &owner), MinidexError::DcaCompleted);
+
+    // Closing the finished schedule returns its rent to the owner
+    close(&mut test, &owner).unwrap();
+    assert!(test.svm.account(&dca).is_none());
+    assert!(test.svm.account(&escrow).is_none());
+    assert_eq!(test.svm.lamports(&owner.key), owner_lamports + rent);
+}
+
+#[test]
+fn only_finished_schedules_close() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 2, INTERVAL, 1).unwrap();
+    crank(&mut test, &owner).unwrap();
+
+    assert_minidex_error(close(&mut test, &owner), MinidexError::DcaNotCompleted);
+}
+
+#[test]
+fn tokens_donated_to_the_escrow_do_not_block_closing() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 1, INTERVAL, 1).unwrap();
+    crank(&mut test, &owner).unwrap();
+
+    // Dust sent to the escrow would keep it from closing
+    let donor = test.create_user(1, 0);
+    let escrow = find_dca_escrow_address(&dca_address(&test, &owner)).0;
+    let donation = spl_token::instruction::transfer(&spl_token::ID, &donor.token_a, &escrow, &donor.key, &[], 1).unwrap();
+    test.send(donation, &donor.key).unwrap();
+
+    close(&mut test, &owner).unwrap();
+
+    assert_eq!(test.svm.token_balance(&owner.token_a), 90_001);
+    assert!(test.svm.account(&escrow).is_none());
+}
+
+#[test]
+fn late_cranks_do_not_catch_up_in_a_burst() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 4, INTERVAL, 1).unwrap();
+
+    test.svm.set_unix_timestamp(START + 3 * INTERVAL);
+    crank(&mut test, &owner).unwrap();
+    assert_minidex_error(crank(&mut test, &owner), MinidexError::DcaSliceNotDue);
+    assert_eq!(dca(&test, &owner).unwrap().next_slice_time, START + 4 * INTERVAL);
+}
+
+#[test]
+fn slices_below_the_slippage_bound_wait() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 2, INTERVAL, 9_980).unwrap();
+
+    // The 0.3% fee alone leaves less than 9,980
+    assert_minidex_error(crank(&mut test, &owner), MinidexError::SlippageExceeded);
+    assert_eq!(dca(&test, &owner).unwrap().slices_executed, 0);
+
+    // Until token B gets cheaper
+    let trader = test.create_user(0, 100_000);
+    test.swap(&trader, 100_000, 0, false).unwrap();
+    crank(&mut test, &owner).unwrap();
+    assert_eq!(dca(&test, &owner).unwrap().slices_executed, 1);
+}
+
+#[test]
+fn slices_obey_the_pool_status() {
+    let (mut test, owner) = setup();
+    let authority = test.authority;
+    open(&mut test, &owner, 10_000, 2, INTERVAL, 1).unwrap();
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED).unwrap();
+    assert_minidex_error(crank(&mut test, &owner), MinidexError::SwapsPaused);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    crank(&mut test, &owner).unwrap();
+}
+
+#[test]
+fn owners_cancel_and_reclaim_the_unswapped_input() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 4, INTERVAL, 1).unwrap();
+    crank(&mut test, &owner).unwrap();
+
+    let dca = dca_address(&test, &owner);
+    let escrow = find_dca_escrow_address(&dca).0;
+    let rent = test.svm.lamports(&dca) + test.svm.lamports(&escrow);
+    let owner_lamports = test.svm.lamports(&owner.key);
+
+    let instruction = instructions::cancel_dca(&owner.key, &test.keys.pool, DCA_ID, &owner.token_a);
+    test.send(instruction, &owner.key).unwrap();
+
+    assert_eq!(test.svm.token_balance(&owner.token_a), 90_000);
+    assert_eq!(test.svm.lamports(&owner.key), owner_lamports + rent);
+    assert!(test.svm.account(&dca).is_none());
+    assert!(test.svm.account(&escrow).is_none());
+}
+
+#[test]
+fn only_the_owner_cancels() {
+    let (mut test, owner) = setup();
+    open(&mut test, &owner, 10_000, 4, INTERVAL, 1).unwrap();
+
+    let stranger = test.create_user(0, 0);
+    let mut instruction = instructions::cancel_dca(&owner.key, &test.keys.pool, DCA_ID, &stranger.token_a);
+    instruction.accounts[0].pubkey = stranger.key;
+
+    assert_minidex_error(test.send(instruction, &stranger.key), MinidexError::Unauthorized);
+}
+
+#[test]
+fn rejects_invalid_schedules() {
+    let (mut test, owner) = setup();
+
+    assert_minidex_error(open(&mut test, &owner, 10_000, 0, INTERVAL, 1), MinidexError::InvalidDcaSchedule);
+    assert_minidex_error(open(&mut test, &owner, 10_000, 4, 0, 1), MinidexError::InvalidDcaSchedule);
+    assert_minidex_error(open(&mut test, &owner, 0, 4, INTERVAL, 1), MinidexError::ZeroAmount);
+    assert_minidex_error(open(&mut test, &owner, 25_001, 4, INTERVAL, 1), MinidexError::InsufficientUserBalance);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::*;
+use minidex_sdk::constants::{MAX_FEE_RATE, VOLATILITY_HALF_LIFE_SLOTS};
+use minidex_sdk::events::{parse_logs, MinidexEvent};
+use minidex_sdk::math::{calculate_price_move_bps, calculate_reserves_after_swap, calculate_swap_output, calculate_swap_quote};
+use minidex_sdk::{instructions, MinidexError};
+
+const START_SLOT: u64 = 1_000;
+
+/// A 1:1 pool at `START_SLOT`, in the dynamic fee mode when `multiplier` is not zero.
+fn setup(min_fee_rate: u16, multiplier: u16) -> TestPool {
+    let (mut test, _) = TestPool::with_liquidity(10_000_000, 10_000_000);
+    let authority = test.authority;
+    test.svm.warp_to_slot(START_SLOT);
+
+    if multiplier > 0 {
+        let instruction = instructions::set_dynamic_fee(&authority, &test.keys.pool, min_fee_rate, multiplier);
+        test.send(instruction, &authority).unwrap();
+    }
+
+    test
+}
+
+/// Swaps `amount_in` of token A and returns the fee it paid.
+fn swap_fee(test: &mut TestPool, amount_in: u64) -> u64 {
+    let trader = test.create_user(amount_in, 0);
+    let metadata = test.swap(&trader, amount_in, 0, true).unwrap();
+
+    match parse_logs(&metadata.logs).as_slice() {
+        [MinidexEvent::Swap(swap)] => swap.fee_amount,
+        events => panic!("unexpected events {events:?}"),
+    }
+}
+
+/// Price move of swapping `amount_in` of token A against the pool as it is.
+fn price_move_bps(test: &TestPool, amount_in: u64) -> u64 {
+    let pool = test.pool();
+    let amount_out = calculate_swap_output(&pool, amount_in, true).unwrap();
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(&pool, amount_in, amount_out, true).unwrap();
+
+    calculate_price_move_bps(pool.reserve_a, pool.reserve_b, reserve_a, reserve_b).unwrap()
+}
+
+#[test]
+fn static_pools_track_volatility_but_keep_their_fee_rate() {
+    let mut test = setup(0, 0);
+    let price_move = price_move_bps(&test, 100_000);
+
+    assert_eq!(swap_fee(&mut test, 100_000), 300);
+    assert_eq!(test.pool().volatility_accumulator, price_move);
+    assert_eq!(swap_fee(&mut test, 100_000), 300);
+}
+
+#[test]
+fn volatility_raises_the_fee_above_the_floor() {
+    let mut test = setup(10, 1_000);
+
+    // A calm pool charges the floor
+    let price_move = price_move_bps(&test, 100_000);
+    assert_eq!(swap_fee(&mut test, 100_000), 100);
+    assert_eq!(test.pool().volatility_accumulator, price_move);
+
+    // Then a tenth of a bps more for every bps the price moved
+    let fee_rate = 10 + price_move / 10;
+    assert_eq!(test.pool().effective_fee_rate() as u64, fee_rate);
+    let quote = calculate_swap_quote(&test.pool(), 100_000, true, START_SLOT).unwrap();
+    assert_eq!(quote.fee_amount, fee_rate * 10);
+    assert_eq!(swap_fee(&mut test, 100_000), quote.fee_amount);
+}
+
+#[test]
+fn volatility_halves_every_half_life() {
+    let mut test = setup(10, 1_000);
+    swap_fee(&mut test, 1_000_000);
+    let accumulator = test.pool().volatility_accumulator;
+
+    // Part of a half-life changes nothing
+    test.svm.warp_to_slot(START_SLOT + VOLATILITY_HALF_LIFE_SLOTS - 1);
+    let quote = calculate_swap_quote(&test.pool(), 10_000, true, START_SLOT + VOLATILITY_HALF_LIFE_SLOTS - 1).unwrap();
+    assert_eq!(quote.fee_amount, 10 + accumulator / 10);
+
+    let slot = START_SLOT + 2 * VOLATILITY_HALF_LIFE_SLOTS;
+    test.svm.warp_to_slot(slot);
+    let pool = test.pool();
+    let quote = calculate_swap_quote(&pool, 10_000, true, slot).unwrap();
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(&pool, 10_000, quote.amount_out, true).unwrap();
+    let price_move = calculate_price_move_bps(pool.reserve_a, pool.reserve_b, reserve_a, reserve_b).unwrap();
+    assert_eq!(quote.fee_amount, 10 + accumulator / 4 / 10);
+    assert_eq!(swap_fee(&mut test, 10_000), quote.fee_amount);
+
+    let pool = test.pool();
+    assert_eq!(pool.volatility_accumulator, accumulator / 4 + price_move);
+    assert_eq!(pool.volatility_update_slot, slot);
+
+    // Long quiet periods bring the fee back to the floor
+    let slot = slot + 64 * VOLATILITY_HALF_LIFE_SLOTS;
+    test.svm.warp_to_slot(slot);
+    assert_eq!(calculate_swap_quote(&test.pool(), 10_000, true, slot).unwrap().fee_amount, 10);
+}
+
+#[test]
+fn the_fee_never_exceeds_the_maximum() {
+    let mut test = setup(10, u16::MAX);
+
+    swap_fee(&mut test, 1_000_000);
+    assert_eq!(test.pool().effective_fee_rate(), MAX_FEE_RATE);
+    assert_eq!(swap_fee(&mut test, 10_000), 1_000);
+}
+
+#[test]
+fn turning_the_mode_off_restores_the_fee_rate() {
+    let mut test = setup(10, u16::MAX);
+    let authority = test.authority;
+    swap_fee(&mut test, 1_000_000);
+
+    let instruction = instructions::set_dynamic_fee(&authority, &test.keys.pool, 10, 0);
+    test.send(instruction, &authority).unwrap();
+    assert_eq!(swap_fee(&mut test, 10_000), 30);
+}
+
+#[test]
+fn only_the_authority_configures_dynamic_fees() {
+    let mut test = setup(0, 0);
+    let authority = test.authority;
+    let stranger = test.create_user(0, 0);
+
+    let instruction = instructions::set_dynamic_fee(&stranger.key, &test.keys.pool, 10, 1_000);
+    assert_minidex_error(test.send(instruction, &stranger.key), MinidexError::Unauthorized);
+
+    let instruction = instructions::set_dynamic_fee(&authority, &test.keys.pool, MAX_FEE_RATE + 1, 1_000);
+    assert_minidex_error(test.send(instruction, &authority), MinidexError::InvalidFeeRate);
+
+    let instruction = instructions::set_dynamic_fee(&authority, &test.keys.pool, 10, 1_000);
+    test.send(instruction, &authority).unwrap();
+    assert_eq!((test.pool().min_fee_rate, test.pool().volatility_fee_multiplier), (10, 1_000));
+}
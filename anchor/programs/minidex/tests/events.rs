@@ -0,0 +1,119 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use common::*;
+use minidex_sdk::constants::MINIMUM_LIQUIDITY;
+use minidex_sdk::events::{parse_logs, AddLiquidityEvent, MinidexEvent, RemoveLiquidityEvent, SwapEvent};
+use minidex_sdk::math::{calculate_swap_fee, calculate_swap_output};
+
+#[test]
+fn swaps_emit_the_trade_and_the_new_reserves() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let trader = test.create_user(10_000, 0);
+    let amount_out = calculate_swap_output(&test.pool(), 10_000, true).unwrap();
+    let fee_amount = calculate_swap_fee(&test.pool(), 10_000).unwrap();
+
+    let metadata = test.swap(&trader, 10_000, 0, true).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(
+        parse_logs(&metadata.logs),
+        vec![MinidexEvent::Swap(SwapEvent {
+            pool: test.keys.pool,
+            user: trader.key,
+            token_a_to_b: true,
+            amount_in: 10_000,
+            amount_out,
+            fee_amount,
+            referrer: Pubkey::default(),
+            referral_amount: 0,
+            reserve_a: pool.reserve_a,
+            reserve_b: pool.reserve_b,
+            total_lp_supply: pool.total_lp_supply,
+        })]
+    );
+}
+
+#[test]
+fn deposits_emit_the_minted_lp_tokens() {
+    let mut test = TestPool::new();
+    let provider = test.create_user(1_000_000, 4_000_000);
+
+    let metadata = test.add_liquidity(&provider, 1_000_000, 4_000_000, 0).unwrap();
+
+    // The first deposit's event counts the locked minimum liquidity in the supply only
+    let pool = test.pool();
+    assert_eq!(
+        parse_logs(&metadata.logs),
+        vec![MinidexEvent::AddLiquidity(AddLiquidityEvent {
+            pool: test.keys.pool,
+            user: provider.key,
+            amount_a: 1_000_000,
+            amount_b: 4_000_000,
+            lp_tokens: test.lp_balance(&provider),
+            reserve_a: 1_000_000,
+            reserve_b: 4_000_000,
+            total_lp_supply: test.lp_balance(&provider) + MINIMUM_LIQUIDITY,
+        })]
+    );
+    assert_eq!(pool.total_lp_supply, test.lp_balance(&provider) + MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn withdrawals_emit_the_returned_tokens() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let lp_tokens = test.lp_balance(&provider) / 2;
+
+    let metadata = test.remove_liquidity(&provider, lp_tokens, 0, 0).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(
+        parse_logs(&metadata.logs),
+        vec![MinidexEvent::RemoveLiquidity(RemoveLiquidityEvent {
+            pool: test.keys.pool,
+            user: provider.key,
+            lp_tokens,
+            amount_a: test.svm.token_balance(&provider.token_a),
+            amount_b: test.svm.token_balance(&provider.token_b),
+            reserve_a: pool.reserve_a,
+            reserve_b: pool.reserve_b,
+            total_lp_supply: pool.total_lp_supply,
+        })]
+    );
+}
+
+#[test]
+fn ignores_event_data_logged_by_other_programs() {
+    let event = SwapEvent {
+        pool: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        token_a_to_b: false,
+        amount_in: 1,
+        amount_out: 1,
+        fee_amount: 0,
+        referrer: Pubkey::default(),
+        referral_amount: 0,
+        reserve_a: 1,
+        reserve_b: 1,
+        total_lp_supply: 1,
+    };
+    let data = BASE64.encode(event.data());
+    let other = Pubkey::new_unique();
+    let minidex = minidex::ID;
+
+    let logs = [
+        format!("Program {other} invoke [1]"),
+        format!("Program data: {data}"),
+        format!("Program {minidex} invoke [2]"),
+        format!("Program data: {data}"),
+        format!("Program {minidex} consumed 5000 of 200000 compute units"),
+        format!("Program {minidex} success"),
+        format!("Program data: {data}"),
+        format!("Program {other} success"),
+    ];
+
+    assert_eq!(parse_logs(&logs), vec![MinidexEvent::Swap(event)]);
+}
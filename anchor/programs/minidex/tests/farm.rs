@@ -0,0 +1,311 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountSerialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::*;
+use minidex_sdk::constants::MAX_FARM_REWARDS;
+use minidex_sdk::{find_farm_address, find_farm_lp_vault_address, find_farm_position_address, instructions};
+use minidex_sdk::{Farm, FarmPosition, MinidexError};
+use minidex_svm::TransactionResult;
+
+const START: i64 = 1_000;
+
+/// A pool with a farm, the provider holding all the LP tokens and the clock at `START`.
+fn setup_farm() -> (TestPool, User) {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+
+    test.svm.set_unix_timestamp(START);
+    test.send(instructions::initialize_farm(&authority, &test.keys), &authority)
+        .unwrap();
+
+    (test, provider)
+}
+
+/// A new reward mint with `amount` in the authority's associated token account.
+fn reward_mint(test: &mut TestPool, amount: u64) -> (Pubkey, Pubkey) {
+    let authority = test.authority;
+    let mint = test.svm.create_mint(&authority, 6);
+    let account = test.svm.create_associated_token_account(&mint, &authority, amount);
+
+    (mint, account)
+}
+
+fn fund(test: &mut TestPool, mint: &Pubkey, account: &Pubkey, start_time: i64, end_time: i64, amount: u64) -> TransactionResult {
+    let authority = test.authority;
+    let instruction = instructions::fund_farm_reward(&authority, &test.keys.pool, mint, account, start_time, end_time, amount);
+    test.send(instruction, &authority)
+}
+
+fn stake(test: &mut TestPool, user: &User, amount: u64) -> TransactionResult {
+    let instruction = instructions::stake_lp(&user.key, &test.keys, amount);
+    test.send(instruction, &user.key)
+}
+
+fn unstake(test: &mut TestPool, user: &User, amount: u64) -> TransactionResult {
+    let instruction = instructions::unstake_lp(&user.key, &test.keys, amount);
+    test.send(instruction, &user.key)
+}
+
+/// Claims the `reward_index`-th reward into the user's account and returns the amount paid.
+fn claim(test: &mut TestPool, user: &User, mint: &Pubkey, reward_index: u8) -> u64 {
+    let account = get_associated_token_address(&user.key, mint);
+    if test.svm.token_account(&account).is_none() {
+        test.svm.create_associated_token_account(mint, &user.key, 0);
+    }
+
+    let before = test.svm.token_balance(&account);
+    let instruction = instructions::claim_rewards(&user.key, &test.keys.pool, mint, &account, reward_index);
+    test.send(instruction, &user.key).unwrap();
+
+    test.svm.token_balance(&account) - before
+}
+
+/// A second user holding `lp_tokens` LP tokens, sent over by the provider.
+fn lp_holder(test: &mut TestPool, provider: &User, lp_tokens: u64) -> User {
+    let user = test.create_user(0, 0);
+    let lp_account = test.lp_account(&user);
+    test.svm.create_associated_token_account(&test.keys.lp_mint, &user.key, 0);
+
+    let transfer = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &test.lp_account(provider),
+        &lp_account,
+        &provider.key,
+        &[],
+        lp_tokens,
+    )
+    .unwrap();
+    test.send(transfer, &provider.key).unwrap();
+
+    user
+}
+
+fn farm(test: &TestPool) -> Farm {
+    test.svm.anchor_account(&find_farm_address(&test.keys.pool).0).unwrap()
+}
+
+fn position(test: &TestPool, user: &User) -> FarmPosition {
+    let farm = find_farm_address(&test.keys.pool).0;
+    test.svm.anchor_account(&find_farm_position_address(&farm, &user.key).0).unwrap()
+}
+
+#[test]
+fn initializes_an_empty_farm() {
+    let (test, _) = setup_farm();
+    let farm = farm(&test);
+
+    assert_eq!(farm.pool, test.keys.pool);
+    assert_eq!(farm.lp_vault, find_farm_lp_vault_address(&find_farm_address(&test.keys.pool).0).0);
+    assert_eq!(farm.total_staked, 0);
+    assert!(farm.rewards.iter().all(|reward| reward.mint == Pubkey::default()));
+}
+
+#[test]
+fn only_the_pool_authority_manages_the_farm() {
+    let (mut test, provider) = setup_farm();
+    let (mint, _) = reward_mint(&mut test, 0);
+    let stranger_account = test.svm.create_associated_token_account(&mint, &provider.key, 1_000);
+
+    let instruction = instructions::fund_farm_reward(&provider.key, &test.keys.pool, &mint, &stranger_account, START, START + 100, 1_000);
+    assert_minidex_error(test.send(instruction, &provider.key), MinidexError::Unauthorized);
+
+    let (mut other, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let instruction = instructions::initialize_farm(&provider.key, &other.keys);
+    other.svm.airdrop(&provider.key, LAMPORTS_PER_SOL);
+    assert_minidex_error(other.send(instruction, &provider.key), MinidexError::Unauthorized);
+}
+
+#[test]
+fn a_single_staker_earns_the_whole_emission() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+    let lp_tokens = 500_000;
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+    assert_eq!(farm(&test).rewards[0].emission_rate, 1_000);
+
+    let lp_balance = test.lp_balance(&provider);
+    stake(&mut test, &provider, lp_tokens).unwrap();
+    assert_eq!(test.lp_balance(&provider), lp_balance - lp_tokens);
+    assert_eq!(farm(&test).total_staked, lp_tokens);
+
+    test.svm.set_unix_timestamp(START + 400);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 400_000);
+
+    // Nothing accrues past the end of the schedule
+    test.svm.set_unix_timestamp(START + 5_000);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 600_000);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 0);
+
+    unstake(&mut test, &provider, lp_tokens).unwrap();
+    assert_eq!(test.lp_balance(&provider), lp_balance);
+    assert_eq!(farm(&test).total_staked, 0);
+}
+
+#[test]
+fn stakers_share_rewards_by_stake_and_time() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+    let other = lp_holder(&mut test, &provider, 600_000);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+
+    stake(&mut test, &provider, 200_000).unwrap();
+
+    // The provider earns alone for the first half, then half of it
+    test.svm.set_unix_timestamp(START + 500);
+    stake(&mut test, &other, 200_000).unwrap();
+
+    test.svm.set_unix_timestamp(START + 750);
+    stake(&mut test, &other, 400_000).unwrap();
+
+    // And a quarter from three quarters on
+    test.svm.set_unix_timestamp(START + 1_000);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 500_000 + 125_000 + 62_500);
+    assert_eq!(claim(&mut test, &other, &mint, 0), 125_000 + 187_500);
+}
+
+#[test]
+fn rewards_stop_after_unstaking_and_stay_claimable() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+    stake(&mut test, &provider, 100_000).unwrap();
+
+    test.svm.set_unix_timestamp(START + 250);
+    assert_minidex_error(unstake(&mut test, &provider, 100_001), MinidexError::InsufficientStake);
+    unstake(&mut test, &provider, 100_000).unwrap();
+    assert_eq!(position(&test, &provider).rewards[0].pending, 250_000);
+
+    test.svm.set_unix_timestamp(START + 750);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 250_000);
+}
+
+#[test]
+fn pays_several_reward_mints_at_once() {
+    let (mut test, provider) = setup_farm();
+    let mints: Vec<_> = (0..MAX_FARM_REWARDS).map(|_| reward_mint(&mut test, 1_000_000)).collect();
+
+    for (index, (mint, account)) in mints.iter().enumerate() {
+        let duration = 1_000 * (index as i64 + 1);
+        fund(&mut test, mint, account, START, START + duration, 1_000_000).unwrap();
+    }
+    stake(&mut test, &provider, 100_000).unwrap();
+
+    let (extra, extra_account) = reward_mint(&mut test, 1_000_000);
+    assert_minidex_error(
+        fund(&mut test, &extra, &extra_account, START, START + 100, 1_000_000),
+        MinidexError::TooManyFarmRewards,
+    );
+
+    test.svm.set_unix_timestamp(START + 500);
+    let claimed: Vec<_> = mints
+        .iter()
+        .enumerate()
+        .map(|(index, (mint, _))| claim(&mut test, &provider, mint, index as u8))
+        .collect();
+
+    // The last schedule emits 333 a second, the remainder stays with the authority
+    assert_eq!(claimed, vec![500_000, 250_000, 166_500]);
+    let vault = farm(&test).rewards[2].vault;
+    assert_eq!(test.svm.token_balance(&vault), 999_000 - 166_500);
+    assert_eq!(test.svm.token_balance(&mints[2].1), 1_000);
+}
+
+#[test]
+fn rewards_wait_while_nothing_is_staked() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+
+    // The schedule runs 400 seconds later than planned
+    test.svm.set_unix_timestamp(START + 400);
+    stake(&mut test, &provider, 100_000).unwrap();
+    assert_eq!(farm(&test).rewards[0].end_time, START + 1_400);
+
+    test.svm.set_unix_timestamp(START + 1_000);
+    unstake(&mut test, &provider, 100_000).unwrap();
+    test.svm.set_unix_timestamp(START + 2_000);
+    stake(&mut test, &provider, 100_000).unwrap();
+
+    test.svm.set_unix_timestamp(START + 10_000);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 1_000_000);
+}
+
+#[test]
+fn emergency_unstaking_works_when_rewards_cannot_update() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+    let lp_balance = test.lp_balance(&provider);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+    stake(&mut test, &provider, 100_000).unwrap();
+    test.svm.set_unix_timestamp(START + 500);
+
+    // An accumulator about to overflow fails every reward update
+    let farm_address = find_farm_address(&test.keys.pool).0;
+    let mut state = farm(&test);
+    state.rewards[0].reward_per_share = u128::MAX;
+    let mut account = test.svm.account(&farm_address).unwrap();
+    account.data.clear();
+    state.try_serialize(&mut account.data).unwrap();
+    test.svm.set_account(farm_address, account);
+
+    assert_minidex_error(unstake(&mut test, &provider, 100_000), MinidexError::MathOverflow);
+
+    let instruction = instructions::emergency_unstake_lp(&provider.key, &test.keys);
+    test.send(instruction, &provider.key).unwrap();
+
+    // The LP tokens come back, the pending rewards are forfeited
+    assert_eq!(test.lp_balance(&provider), lp_balance);
+    assert_eq!(farm(&test).total_staked, 0);
+    assert_eq!(position(&test, &provider).staked, 0);
+    assert_eq!(position(&test, &provider).rewards[0].pending, 0);
+
+    let instruction = instructions::emergency_unstake_lp(&provider.key, &test.keys);
+    assert_minidex_error(test.send(instruction, &provider.key), MinidexError::InsufficientStake);
+}
+
+#[test]
+fn refunding_rolls_the_undistributed_rewards_into_the_new_schedule() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 2_000_000);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+    stake(&mut test, &provider, 100_000).unwrap();
+
+    // 500,000 left over plus 1,000,000 new, over the next 500 seconds
+    test.svm.set_unix_timestamp(START + 500);
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000_000).unwrap();
+
+    let reward = farm(&test).rewards[0];
+    assert_eq!(reward.emission_rate, 3_000);
+    assert_eq!((reward.start_time, reward.end_time), (START + 500, START + 1_000));
+
+    test.svm.set_unix_timestamp(START + 1_000);
+    assert_eq!(claim(&mut test, &provider, &mint, 0), 2_000_000);
+}
+
+#[test]
+fn rejects_invalid_schedules_and_claims() {
+    let (mut test, provider) = setup_farm();
+    let (mint, account) = reward_mint(&mut test, 1_000_000);
+
+    assert_minidex_error(fund(&mut test, &mint, &account, START, START - 1, 1_000), MinidexError::InvalidRewardSchedule);
+    // Less than one token per second
+    assert_minidex_error(fund(&mut test, &mint, &account, START, START + 1_000, 999), MinidexError::InvalidRewardSchedule);
+
+    fund(&mut test, &mint, &account, START, START + 1_000, 1_000).unwrap();
+    stake(&mut test, &provider, 1_000).unwrap();
+
+    let user_account = test.svm.create_associated_token_account(&mint, &provider.key, 0);
+    for reward_index in [1, MAX_FARM_REWARDS as u8] {
+        let instruction = instructions::claim_rewards(&provider.key, &test.keys.pool, &mint, &user_account, reward_index);
+        assert_minidex_error(test.send(instruction, &provider.key), MinidexError::InvalidRewardIndex);
+    }
+}
@@ -0,0 +1,279 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use common::*;
+use minidex_sdk::constants::GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS;
+use minidex_sdk::instructions::{self, SwapOptions};
+use minidex_sdk::math::{apply_fee_discount, calculate_swap_fee, calculate_swap_output};
+use minidex_sdk::{find_config_address, find_user_stake_address, Config, FeeDiscountTier, MinidexError, UserStake};
+use minidex_svm::TransactionResult;
+
+/// A 1:4 pool with a config whose schedule takes 20% off the fee from 1_000
+/// staked and 50% from 10_000.
+struct Setup {
+    test: TestPool,
+    admin: Pubkey,
+    governance_mint: Pubkey,
+}
+
+fn tier(min_stake: u64, discount_bps: u16) -> FeeDiscountTier {
+    FeeDiscountTier { min_stake, discount_bps }
+}
+
+fn setup() -> Setup {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let admin = test.authority;
+    let governance_mint = test.svm.create_mint(&admin, 6);
+
+    test.svm.set_upgrade_authority(Some(admin));
+    test.send(instructions::initialize_config(&admin, &governance_mint), &admin).unwrap();
+    test.send(instructions::set_fee_discounts(&admin, vec![tier(1_000, 2_000), tier(10_000, 5_000)]), &admin)
+        .unwrap();
+
+    Setup { test, admin, governance_mint }
+}
+
+impl Setup {
+    fn config(&self) -> Config {
+        self.test.svm.anchor_account(&find_config_address().0).expect("config account exists")
+    }
+
+    fn user_stake(&self, user: &User) -> Option<UserStake> {
+        self.test.svm.anchor_account(&find_user_stake_address(&user.key).0)
+    }
+
+    /// A trader holding `amount_a` token A and `governance` governance tokens.
+    fn create_trader(&mut self, amount_a: u64, governance: u64) -> (User, Pubkey) {
+        let user = self.test.create_user(amount_a, 0);
+        let governance_account = self.test.svm.create_associated_token_account(&self.governance_mint, &user.key, governance);
+
+        (user, governance_account)
+    }
+
+    fn stake(&mut self, user: &User, governance_account: &Pubkey, amount: u64) -> TransactionResult {
+        let instruction = instructions::stake_governance(&user.key, &self.governance_mint, governance_account, amount);
+        self.test.send(instruction, &user.key)
+    }
+
+    fn unstake(&mut self, user: &User, governance_account: &Pubkey, amount: u64) -> TransactionResult {
+        self.test.send(instructions::unstake_governance(&user.key, governance_account, amount), &user.key)
+    }
+
+    fn swap_with_discount(&mut self, user: &User, amount_in: u64) -> TransactionResult {
+        let instruction = instructions::swap_tokens_with_options(
+            &user.key,
+            &self.test.keys,
+            &user.token_a,
+            &user.token_b,
+            amount_in,
+            0,
+            true,
+            SwapOptions {
+                fee_discount: true,
+                ..SwapOptions::default()
+            },
+        );
+        self.test.send(instruction, &user.key)
+    }
+
+    fn next_slot(&mut self) {
+        self.skip_slots(1);
+    }
+
+    fn skip_slots(&mut self, slots: u64) {
+        let slot = self.test.svm.clock().slot;
+        self.test.svm.warp_to_slot(slot + slots);
+    }
+}
+
+#[test]
+fn only_the_upgrade_authority_can_create_the_config() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let admin = test.authority;
+    let governance_mint = test.svm.create_mint(&admin, 6);
+    let stranger = test.create_user(0, 0);
+
+    // An immutable program has nobody to create it
+    let result = test.send(instructions::initialize_config(&admin, &governance_mint), &admin);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+
+    test.svm.set_upgrade_authority(Some(admin));
+    let result = test.send(instructions::initialize_config(&stranger.key, &governance_mint), &stranger.key);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+
+    test.send(instructions::initialize_config(&admin, &governance_mint), &admin).unwrap();
+
+    let config: Config = test.svm.anchor_account(&find_config_address().0).unwrap();
+    assert_eq!((config.admin, config.governance_mint), (admin, governance_mint));
+    assert_eq!(config.fee_discount_tiers, [FeeDiscountTier::default(); 4]);
+
+    // There is only one config
+    assert!(test.send(instructions::initialize_config(&admin, &governance_mint), &admin).is_err());
+}
+
+#[test]
+fn the_discount_schedule_is_validated() {
+    let mut setup = setup();
+    let admin = setup.admin;
+    let stranger = setup.test.create_user(0, 0);
+
+    let result = setup.test.send(instructions::set_fee_discounts(&stranger.key, vec![tier(1, 1)]), &stranger.key);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+
+    let invalid = [
+        // Too many tiers
+        vec![tier(1, 1), tier(2, 2), tier(3, 3), tier(4, 4), tier(5, 5)],
+        // A tier without a threshold
+        vec![tier(0, 100)],
+        // Thresholds must increase
+        vec![tier(1_000, 100), tier(1_000, 200)],
+        // Discounts may not shrink
+        vec![tier(1_000, 200), tier(2_000, 100)],
+        // More than the whole fee
+        vec![tier(1_000, 10_001)],
+    ];
+    for tiers in invalid {
+        let result = setup.test.send(instructions::set_fee_discounts(&admin, tiers), &admin);
+        assert_minidex_error(result, MinidexError::InvalidFeeDiscounts);
+    }
+
+    setup.test.send(instructions::set_fee_discounts(&admin, vec![tier(500, 10_000)]), &admin).unwrap();
+    assert_eq!(setup.config().fee_discount_tiers, [tier(500, 10_000), tier(0, 0), tier(0, 0), tier(0, 0)]);
+
+    setup.test.send(instructions::set_fee_discounts(&admin, Vec::new()), &admin).unwrap();
+    assert_eq!(setup.config().fee_discount_tiers, [FeeDiscountTier::default(); 4]);
+}
+
+#[test]
+fn stakers_pay_the_fee_of_the_highest_tier_they_reach() {
+    let mut setup = setup();
+    let (trader, governance_account) = setup.create_trader(100_000, 20_000);
+
+    setup.stake(&trader, &governance_account, 12_000).unwrap();
+    setup.next_slot();
+
+    let stake = setup.user_stake(&trader).unwrap();
+    assert_eq!((stake.owner, stake.staked), (trader.key, 12_000));
+    assert_eq!(setup.test.svm.token_balance(&governance_account), 8_000);
+    assert_eq!(setup.test.svm.token_balance(&stake.vault), 12_000);
+
+    let slot = setup.test.svm.clock().slot;
+    let discounted = apply_fee_discount(&setup.test.pool(), 5_000, slot).unwrap();
+    assert_eq!(discounted.fee_rate, 15);
+
+    let amount_out = calculate_swap_output(&discounted, 100_000, true).unwrap();
+    assert!(amount_out > calculate_swap_output(&setup.test.pool(), 100_000, true).unwrap());
+
+    setup.swap_with_discount(&trader, 100_000).unwrap();
+
+    assert_eq!(setup.test.svm.token_balance(&trader.token_b), amount_out);
+    assert_eq!(calculate_swap_fee(&discounted, 100_000).unwrap(), 150);
+}
+
+#[test]
+fn stake_added_in_the_swap_slot_earns_no_discount() {
+    let mut setup = setup();
+    let (trader, governance_account) = setup.create_trader(100_000, 10_000);
+    let full_fee_out = calculate_swap_output(&setup.test.pool(), 100_000, true).unwrap();
+
+    // Staking and swapping in one slot, as a flash loan of the governance token would
+    setup.stake(&trader, &governance_account, 10_000).unwrap();
+    setup.swap_with_discount(&trader, 100_000).unwrap();
+
+    assert_eq!(setup.test.svm.token_balance(&trader.token_b), full_fee_out);
+}
+
+#[test]
+fn swaps_without_the_stake_accounts_pay_the_full_fee() {
+    let mut setup = setup();
+    let (trader, governance_account) = setup.create_trader(100_000, 10_000);
+
+    setup.stake(&trader, &governance_account, 10_000).unwrap();
+    setup.next_slot();
+
+    let full_fee_out = calculate_swap_output(&setup.test.pool(), 100_000, true).unwrap();
+    setup.test.swap(&trader, 100_000, 0, true).unwrap();
+
+    assert_eq!(setup.test.svm.token_balance(&trader.token_b), full_fee_out);
+}
+
+#[test]
+fn unstaking_returns_the_tokens_and_drops_the_discount() {
+    let mut setup = setup();
+    let (trader, governance_account) = setup.create_trader(100_000, 5_000);
+
+    setup.stake(&trader, &governance_account, 5_000).unwrap();
+    setup.skip_slots(GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS);
+
+    let result = setup.unstake(&trader, &governance_account, 5_001);
+    assert_minidex_error(result, MinidexError::InsufficientStake);
+
+    setup.unstake(&trader, &governance_account, 4_500).unwrap();
+    assert_eq!(setup.user_stake(&trader).unwrap().staked, 500);
+    assert_eq!(setup.test.svm.token_balance(&governance_account), 4_500);
+    setup.next_slot();
+
+    // 500 is below the first tier
+    let full_fee_out = calculate_swap_output(&setup.test.pool(), 100_000, true).unwrap();
+    setup.swap_with_discount(&trader, 100_000).unwrap();
+
+    assert_eq!(setup.test.svm.token_balance(&trader.token_b), full_fee_out);
+}
+
+#[test]
+fn stake_stays_locked_for_the_cooldown() {
+    let mut setup = setup();
+    let (trader, governance_account) = setup.create_trader(100_000, 10_001);
+    let full_fee_out = calculate_swap_output(&setup.test.pool(), 100_000, true).unwrap();
+
+    // Staking, swapping and unstaking in consecutive slots, as a loan of the token over a few slots would
+    setup.stake(&trader, &governance_account, 10_000).unwrap();
+    setup.next_slot();
+    setup.swap_with_discount(&trader, 100_000).unwrap();
+    assert!(setup.test.svm.token_balance(&trader.token_b) > full_fee_out);
+    setup.next_slot();
+
+    assert_minidex_error(setup.unstake(&trader, &governance_account, 10_000), MinidexError::StakeLocked);
+
+    // Staking again restarts the cooldown for the whole stake
+    setup.skip_slots(GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS - 2);
+    setup.stake(&trader, &governance_account, 1).unwrap();
+    setup.skip_slots(GOVERNANCE_UNSTAKE_COOLDOWN_SLOTS - 1);
+    assert_minidex_error(setup.unstake(&trader, &governance_account, 10_001), MinidexError::StakeLocked);
+
+    setup.next_slot();
+    setup.unstake(&trader, &governance_account, 10_001).unwrap();
+    assert_eq!(setup.test.svm.token_balance(&governance_account), 10_001);
+}
+
+#[test]
+fn another_users_stake_cannot_be_borrowed() {
+    let mut setup = setup();
+    let (whale, governance_account) = setup.create_trader(0, 10_000);
+    let (trader, _) = setup.create_trader(100_000, 0);
+
+    setup.stake(&whale, &governance_account, 10_000).unwrap();
+    setup.next_slot();
+
+    let mut instruction = instructions::swap_tokens_with_options(
+        &trader.key,
+        &setup.test.keys,
+        &trader.token_a,
+        &trader.token_b,
+        100_000,
+        0,
+        true,
+        SwapOptions {
+            fee_discount: true,
+            ..SwapOptions::default()
+        },
+    );
+    // The stake account comes right before the permission and the token program
+    let index = instruction.accounts.len() - 3;
+    instruction.accounts[index] = AccountMeta::new_readonly(find_user_stake_address(&whale.key).0, false);
+
+    assert_error(
+        setup.test.send(instruction, &trader.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds),
+    );
+}
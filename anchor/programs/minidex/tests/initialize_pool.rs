@@ -0,0 +1,134 @@
+mod common;
+
+use anchor_lang::solana_program::instruction::InstructionError;
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token::state::AccountState;
+use common::*;
+use minidex_sdk::constants::MAX_FEE_RATE;
+use minidex_sdk::{find_pool_address, instructions, MinidexError, PoolKeys};
+
+#[test]
+fn creates_pool_vaults_and_lp_mint() {
+    let test = TestPool::with_fee_rate(25);
+    let pool = test.pool();
+    let keys = test.keys;
+
+    assert_eq!(pool.authority, test.authority);
+    assert_eq!(pool.token_a_mint, keys.token_a_mint);
+    assert_eq!(pool.token_b_mint, keys.token_b_mint);
+    assert_eq!(pool.token_a_vault, keys.token_a_vault);
+    assert_eq!(pool.token_b_vault, keys.token_b_vault);
+    assert_eq!(pool.lp_mint, keys.lp_mint);
+    assert_eq!(pool.fee_rate, 25);
+    assert_eq!(pool.bump, find_pool_address(&keys.token_a_mint, &keys.token_b_mint).1);
+    assert_eq!((pool.reserve_a, pool.reserve_b, pool.total_lp_supply), (0, 0, 0));
+    assert_eq!(pool.guardian, Pubkey::default());
+    assert_eq!(pool.status, 0);
+    assert_eq!((pool.max_price_impact_bps, pool.max_slot_price_move_bps), (0, 0));
+
+    for (vault, mint) in [
+        (keys.token_a_vault, keys.token_a_mint),
+        (keys.token_b_vault, keys.token_b_mint),
+    ] {
+        let vault = test.svm.token_account(&vault).expect("vault exists");
+        assert_eq!(vault.mint, mint);
+        assert_eq!(vault.owner, keys.pool);
+        assert_eq!(vault.amount, 0);
+        assert_eq!(vault.state, AccountState::Initialized);
+    }
+
+    let lp_mint = test.svm.mint(&keys.lp_mint).expect("LP mint exists");
+    assert_eq!(lp_mint.mint_authority, Some(keys.pool).into());
+    assert_eq!(lp_mint.decimals, 6);
+    assert_eq!(lp_mint.supply, 0);
+}
+
+#[test]
+fn authority_pays_rent_for_every_account() {
+    let (mut svm, authority, mint_a, mint_b) = setup();
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+    let balance_before = svm.lamports(&authority);
+
+    svm.process_transaction(&instructions::create_pool(&authority, &keys, DEFAULT_FEE_RATE), &[authority])
+        .unwrap();
+
+    let rent: u64 = [keys.pool, keys.token_a_vault, keys.token_b_vault, keys.lp_mint]
+        .iter()
+        .map(|address| {
+            let account = svm.account(address).unwrap();
+            assert!(svm.rent().is_exempt(account.lamports, account.data.len()));
+            account.lamports
+        })
+        .sum();
+
+    assert_eq!(svm.lamports(&authority), balance_before - rent);
+}
+
+#[test]
+fn accepts_the_maximum_fee_rate() {
+    let test = TestPool::with_fee_rate(MAX_FEE_RATE);
+
+    assert_eq!(test.pool().fee_rate, MAX_FEE_RATE);
+}
+
+#[test]
+fn rejects_fee_rate_above_maximum() {
+    let (mut svm, authority, mint_a, mint_b) = setup();
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+    let result = svm.process_transaction(
+        &[instructions::initialize_pool(&authority, &keys, MAX_FEE_RATE + 1)],
+        &[authority],
+    );
+
+    assert_minidex_error(result, MinidexError::InvalidFeeRate);
+}
+
+#[test]
+fn rejects_identical_mints() {
+    let (mut svm, authority, mint_a, _) = setup();
+    let keys = PoolKeys::derive(&mint_a, &mint_a);
+
+    let result = svm.process_transaction(
+        &[instructions::initialize_pool(&authority, &keys, DEFAULT_FEE_RATE)],
+        &[authority],
+    );
+
+    assert_minidex_error(result, MinidexError::IdenticalMints);
+}
+
+#[test]
+fn rejects_a_second_pool_for_the_same_mints() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let instruction = instructions::initialize_pool(&authority, &test.keys, DEFAULT_FEE_RATE);
+
+    // `init` fails inside the system program's create_account
+    assert_error(test.send(instruction, &authority), InstructionError::Custom(0));
+}
+
+#[test]
+fn requires_the_authority_signature() {
+    let (mut svm, authority, mint_a, mint_b) = setup();
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+    let result = svm.process_transaction(&[instructions::initialize_pool(&authority, &keys, DEFAULT_FEE_RATE)], &[]);
+
+    assert_error(result, InstructionError::MissingRequiredSignature);
+}
+
+#[test]
+fn failed_setup_leaves_no_accounts_behind() {
+    let (mut svm, authority, mint_a, mint_b) = setup();
+    let keys = PoolKeys::derive(&mint_a, &mint_b);
+
+    let mut setup_instructions = instructions::create_pool(&authority, &keys, DEFAULT_FEE_RATE);
+    setup_instructions[0] = instructions::initialize_pool(&authority, &keys, DEFAULT_FEE_RATE);
+    setup_instructions.push(instructions::initialize_vault_a(&authority, &keys));
+
+    let result = svm.process_transaction(&setup_instructions, &[authority]);
+
+    assert_error(result, InstructionError::Custom(0));
+    assert!(svm.account(&keys.pool).is_none());
+    assert!(svm.account(&keys.token_a_vault).is_none());
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use common::*;
+use minidex_sdk::constants::POOL_SWAPS_PAUSED;
+use minidex_sdk::math::calculate_swap_output;
+use minidex_sdk::{find_limit_order_address, find_limit_order_escrow_address, instructions, LimitOrder, MinidexError};
+use minidex_svm::TransactionResult;
+
+const ORDER_ID: u64 = 7;
+const KEEPER_FEE: u64 = 1_000;
+
+struct Keeper {
+    key: Pubkey,
+    token_a: Pubkey,
+}
+
+/// A 1:1 pool and a maker with 100,000 of each token.
+fn setup() -> (TestPool, User) {
+    let (mut test, _) = TestPool::with_liquidity(10_000_000, 10_000_000);
+    let maker = test.create_user(100_000, 100_000);
+
+    (test, maker)
+}
+
+fn place(test: &mut TestPool, maker: &User, amount_in: u64, min_amount_out: u64) -> TransactionResult {
+    let instruction = instructions::place_limit_order(
+        &maker.key,
+        &test.keys,
+        &maker.token_a,
+        &maker.token_b,
+        ORDER_ID,
+        true,
+        amount_in,
+        min_amount_out,
+        KEEPER_FEE,
+        None,
+    );
+    test.send(instruction, &maker.key)
+}
+
+fn order_address(test: &TestPool, maker: &User) -> Pubkey {
+    find_limit_order_address(&test.keys.pool, &maker.key, ORDER_ID).0
+}
+
+fn order(test: &TestPool, maker: &User) -> Option<LimitOrder> {
+    test.svm.anchor_account(&order_address(test, maker))
+}
+
+fn keeper(test: &mut TestPool) -> Keeper {
+    let key = Pubkey::new_unique();
+    test.svm.airdrop(&key, LAMPORTS_PER_SOL);
+    let token_a = test.svm.create_associated_token_account(&test.keys.token_a_mint, &key, 0);
+
+    Keeper { key, token_a }
+}
+
+fn fill(test: &mut TestPool, maker: &User, keeper: &Keeper) -> TransactionResult {
+    let order = order(test, maker).expect("order exists");
+    let instruction = instructions::fill_limit_order(&keeper.key, &test.keys, &order_address(test, maker), &order, &keeper.token_a, None);
+    test.send(instruction, &keeper.key)
+}
+
+/// Buys token A with `amount_b`, raising its price.
+fn buy_token_a(test: &mut TestPool, amount_b: u64) {
+    let trader = test.create_user(0, amount_b);
+    test.swap(&trader, amount_b, 0, false).unwrap();
+}
+
+#[test]
+fn placing_an_order_escrows_the_input_and_keeper_fee() {
+    let (mut test, maker) = setup();
+
+    place(&mut test, &maker, 50_000, 55_000).unwrap();
+
+    let order = order(&test, &maker).unwrap();
+    let escrow = find_limit_order_escrow_address(&order_address(&test, &maker)).0;
+    assert_eq!(order.pool, test.keys.pool);
+    assert_eq!(order.maker, maker.key);
+    assert_eq!((order.order_id, order.sell_token_a), (ORDER_ID, true));
+    assert_eq!((order.amount_in, order.min_amount_out, order.keeper_fee), (50_000, 55_000, KEEPER_FEE));
+    assert_eq!(order.escrow, escrow);
+    assert_eq!(order.receive_account, maker.token_b);
+
+    assert_eq!(test.svm.token_balance(&escrow), 51_000);
+    assert_eq!(test.svm.token_balance(&maker.token_a), 49_000);
+}
+
+#[test]
+fn keepers_fill_once_the_pool_pays_the_limit_price() {
+    let (mut test, maker) = setup();
+    let keeper = keeper(&mut test);
+    place(&mut test, &maker, 50_000, 55_000).unwrap();
+
+    assert_minidex_error(fill(&mut test, &maker, &keeper), MinidexError::LimitPriceNotReached);
+
+    buy_token_a(&mut test, 1_500_000);
+    let amount_out = calculate_swap_output(&test.pool(), 50_000, true).unwrap();
+    assert!(amount_out >= 55_000);
+
+    let order = order_address(&test, &maker);
+    let escrow = find_limit_order_escrow_address(&order).0;
+    let rent = test.svm.lamports(&order) + test.svm.lamports(&escrow);
+    let maker_lamports = test.svm.lamports(&maker.key);
+    let reserves = (test.pool().reserve_a, test.pool().reserve_b);
+
+    fill(&mut test, &maker, &keeper).unwrap();
+
+    assert_eq!(test.svm.token_balance(&maker.token_b), 100_000 + amount_out);
+    assert_eq!(test.svm.token_balance(&keeper.token_a), KEEPER_FEE);
+    assert_eq!((test.pool().reserve_a, test.pool().reserve_b), (reserves.0 + 50_000, reserves.1 - amount_out));
+
+    // The order and its escrow are closed, their rent goes back to the maker
+    assert!(test.svm.account(&order).is_none());
+    assert!(test.svm.account(&escrow).is_none());
+    assert_eq!(test.svm.lamports(&maker.key), maker_lamports + rent);
+}
+
+#[test]
+fn tokens_donated_to_the_escrow_do_not_block_the_fill() {
+    let (mut test, maker) = setup();
+    let keeper = keeper(&mut test);
+    place(&mut test, &maker, 50_000, 55_000).unwrap();
+    buy_token_a(&mut test, 1_500_000);
+
+    // Dust sent to the escrow would keep it from closing
+    let donor = test.create_user(1, 0);
+    let escrow = find_limit_order_escrow_address(&order_address(&test, &maker)).0;
+    let donation = spl_token::instruction::transfer(&spl_token::ID, &donor.token_a, &escrow, &donor.key, &[], 1).unwrap();
+    test.send(donation, &donor.key).unwrap();
+    assert_eq!(test.svm.token_balance(&escrow), 51_001);
+
+    fill(&mut test, &maker, &keeper).unwrap();
+
+    // The maker gets the dust back, the keeper only its fee
+    assert_eq!(test.svm.token_balance(&maker.token_a), 49_001);
+    assert_eq!(test.svm.token_balance(&keeper.token_a), KEEPER_FEE);
+    assert!(test.svm.account(&escrow).is_none());
+}
+
+#[test]
+fn fills_obey_the_pool_status() {
+    let (mut test, maker) = setup();
+    let authority = test.authority;
+    let keeper = keeper(&mut test);
+    place(&mut test, &maker, 50_000, 1).unwrap();
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED).unwrap();
+    assert_minidex_error(fill(&mut test, &maker, &keeper), MinidexError::SwapsPaused);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    fill(&mut test, &maker, &keeper).unwrap();
+}
+
+#[test]
+fn makers_cancel_and_reclaim_their_escrow() {
+    let (mut test, maker) = setup();
+    place(&mut test, &maker, 50_000, 55_000).unwrap();
+
+    let order = order_address(&test, &maker);
+    let escrow = find_limit_order_escrow_address(&order).0;
+    let rent = test.svm.lamports(&order) + test.svm.lamports(&escrow);
+    let maker_lamports = test.svm.lamports(&maker.key);
+
+    let instruction = instructions::cancel_limit_order(&maker.key, &test.keys.pool, ORDER_ID, &maker.token_a);
+    test.send(instruction, &maker.key).unwrap();
+
+    assert_eq!(test.svm.token_balance(&maker.token_a), 100_000);
+    assert_eq!(test.svm.lamports(&maker.key), maker_lamports + rent);
+    assert!(test.svm.account(&order).is_none());
+
+    // The order id is free again
+    place(&mut test, &maker, 10_000, 10_000).unwrap();
+}
+
+#[test]
+fn only_the_maker_cancels() {
+    let (mut test, maker) = setup();
+    place(&mut test, &maker, 50_000, 55_000).unwrap();
+
+    let stranger = test.create_user(0, 0);
+    let mut instruction = instructions::cancel_limit_order(&maker.key, &test.keys.pool, ORDER_ID, &stranger.token_a);
+    instruction.accounts[0].pubkey = stranger.key;
+
+    assert_minidex_error(test.send(instruction, &stranger.key), MinidexError::Unauthorized);
+}
+
+#[test]
+fn rejects_orders_the_maker_cannot_fund() {
+    let (mut test, maker) = setup();
+
+    assert_minidex_error(place(&mut test, &maker, 100_000, 1), MinidexError::InsufficientUserBalance);
+    assert_minidex_error(place(&mut test, &maker, 0, 1), MinidexError::ZeroAmount);
+    assert_minidex_error(place(&mut test, &maker, 1_000, 0), MinidexError::ZeroAmount);
+}
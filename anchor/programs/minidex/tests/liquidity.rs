@@ -0,0 +1,191 @@
+mod common;
+
+use anchor_lang::solana_program::instruction::InstructionError;
+use common::*;
+use minidex_sdk::constants::MINIMUM_LIQUIDITY;
+use minidex_sdk::math::{calculate_lp_tokens, calculate_withdrawal_amounts};
+use minidex_sdk::MinidexError;
+
+#[test]
+fn first_deposit_mints_geometric_mean_minus_locked_liquidity() {
+    let mut test = TestPool::new();
+    let provider = test.create_user(1_000_000, 4_000_000);
+
+    test.add_liquidity(&provider, 1_000_000, 4_000_000, 1_999_000).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(test.lp_balance(&provider), 2_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(pool.total_lp_supply, 2_000_000);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000, 4_000_000));
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), 1_000_000);
+    assert_eq!(test.svm.token_balance(&test.keys.token_b_vault), 4_000_000);
+    assert_eq!(test.svm.token_balance(&provider.token_a), 0);
+    assert_eq!(test.svm.token_balance(&provider.token_b), 0);
+}
+
+#[test]
+fn later_deposits_mint_in_proportion_to_the_smaller_side() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let user = test.create_user(500_000, 3_000_000);
+    let expected = calculate_lp_tokens(&test.pool(), 500_000, 3_000_000).unwrap();
+
+    test.add_liquidity(&user, 500_000, 3_000_000, expected).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(expected, 1_000_000);
+    assert_eq!(test.lp_balance(&user), expected);
+    assert_eq!(pool.total_lp_supply, 3_000_000);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_500_000, 7_000_000));
+    assert_eq!(test.svm.mint(&test.keys.lp_mint).unwrap().supply, 2_999_000);
+}
+
+#[test]
+fn deposit_reuses_an_existing_lp_account() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let lp_before = test.lp_balance(&provider);
+
+    test.svm.create_token_account_at(provider.token_a, &test.keys.token_a_mint, &provider.key, 1_000);
+    test.svm.create_token_account_at(provider.token_b, &test.keys.token_b_mint, &provider.key, 1_000);
+    test.add_liquidity(&provider, 1_000, 1_000, 0).unwrap();
+
+    assert_eq!(test.lp_balance(&provider), lp_before + 1_000);
+}
+
+#[test]
+fn rejects_zero_deposit_amounts() {
+    let mut test = TestPool::new();
+    let user = test.create_user(1_000, 1_000);
+
+    assert_minidex_error(test.add_liquidity(&user, 0, 1_000, 0), MinidexError::ZeroAmount);
+    assert_minidex_error(test.add_liquidity(&user, 1_000, 0, 0), MinidexError::ZeroAmount);
+}
+
+#[test]
+fn rejects_first_deposit_below_minimum_liquidity() {
+    let mut test = TestPool::new();
+    let user = test.create_user(1_000, 1_000);
+
+    assert_minidex_error(test.add_liquidity(&user, 999, 1_000, 0), MinidexError::InsufficientLiquidity);
+    assert_minidex_error(test.add_liquidity(&user, 1_000, 1_000, 0), MinidexError::ZeroLPTokens);
+}
+
+#[test]
+fn rejects_deposit_below_min_lp_tokens() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let user = test.create_user(1_000, 1_000);
+
+    assert_minidex_error(test.add_liquidity(&user, 1_000, 1_000, 1_001), MinidexError::SlippageExceeded);
+}
+
+#[test]
+fn rejects_deposit_too_small_to_mint() {
+    let mut test = TestPool::new();
+    let provider = test.create_user(1_000_000_000, 1_000);
+    test.add_liquidity(&provider, 1_000_000_000, 1_000, 0).unwrap();
+
+    let user = test.create_user(1, 1);
+
+    assert_minidex_error(test.add_liquidity(&user, 1, 1, 0), MinidexError::ZeroLPTokens);
+}
+
+#[test]
+fn rejects_deposit_whose_lp_amount_overflows() {
+    let mut test = TestPool::new();
+    let provider = test.create_user(1, 1_000_000_000_000);
+    test.add_liquidity(&provider, 1, 1_000_000_000_000, 0).unwrap();
+
+    let user = test.create_user(u64::MAX / 2, 1);
+
+    assert_minidex_error(test.add_liquidity(&user, u64::MAX / 2, 1, 0), MinidexError::MathOverflow);
+}
+
+#[test]
+fn rejects_deposit_above_the_user_balance() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let user = test.create_user(1_000, 1_000);
+
+    // SPL Token's InsufficientFunds
+    assert_error(test.add_liquidity(&user, 1_001, 1_000, 0), InstructionError::Custom(1));
+    assert_eq!(test.svm.token_balance(&user.token_a), 1_000);
+    assert_eq!(test.pool().reserve_a, 1_000_000);
+}
+
+#[test]
+fn withdrawal_pays_out_a_share_of_both_reserves() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let (expected_a, expected_b) = calculate_withdrawal_amounts(&test.pool(), 500_000).unwrap();
+
+    test.remove_liquidity(&provider, 500_000, expected_a, expected_b).unwrap();
+
+    let pool = test.pool();
+    assert_eq!((expected_a, expected_b), (250_000, 1_000_000));
+    assert_eq!(test.svm.token_balance(&provider.token_a), 250_000);
+    assert_eq!(test.svm.token_balance(&provider.token_b), 1_000_000);
+    assert_eq!(test.lp_balance(&provider), 1_499_000);
+    assert_eq!(pool.total_lp_supply, 1_500_000);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (750_000, 3_000_000));
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), 750_000);
+    assert_eq!(test.svm.token_balance(&test.keys.token_b_vault), 3_000_000);
+}
+
+#[test]
+fn withdrawing_everything_leaves_the_locked_liquidity() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let lp_tokens = test.lp_balance(&provider);
+
+    test.remove_liquidity(&provider, lp_tokens, 0, 0).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(pool.total_lp_supply, MINIMUM_LIQUIDITY);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_000, 1_000));
+    assert_eq!(test.svm.token_balance(&provider.token_a), 999_000);
+}
+
+#[test]
+fn rejects_zero_lp_tokens() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+
+    assert_minidex_error(test.remove_liquidity(&provider, 0, 0, 0), MinidexError::ZeroLPTokens);
+}
+
+#[test]
+fn rejects_withdrawal_from_an_empty_pool() {
+    let mut test = TestPool::new();
+    let user = test.create_user(0, 0);
+    let lp_mint = test.keys.lp_mint;
+    test.svm.create_associated_token_account(&lp_mint, &user.key, 0);
+
+    assert_minidex_error(test.remove_liquidity(&user, 1, 0, 0), MinidexError::EmptyPool);
+}
+
+#[test]
+fn rejects_burning_more_lp_tokens_than_held() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let lp_tokens = test.lp_balance(&provider);
+
+    assert_minidex_error(
+        test.remove_liquidity(&provider, lp_tokens + 1, 0, 0),
+        MinidexError::InsufficientLPTokens,
+    );
+}
+
+#[test]
+fn rejects_withdrawal_below_minimum_amounts() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+
+    assert_minidex_error(test.remove_liquidity(&provider, 500_000, 250_001, 0), MinidexError::SlippageExceeded);
+    assert_minidex_error(test.remove_liquidity(&provider, 500_000, 0, 1_000_001), MinidexError::SlippageExceeded);
+    test.remove_liquidity(&provider, 500_000, 250_000, 1_000_000).unwrap();
+}
+
+#[test]
+fn rejects_another_users_token_accounts() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let mut thief = test.create_user(0, 0);
+    thief.token_a = provider.token_a;
+
+    assert_error(
+        test.add_liquidity(&thief, 1_000, 1_000, 0),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintTokenOwner),
+    );
+}
@@ -0,0 +1,277 @@
+//! Property tests for the pool math over the whole u64 domain.
+
+use minidex::constants::{MAX_FEE_RATE, MAX_VOLATILITY_ACCUMULATOR, MINIMUM_LIQUIDITY};
+use minidex::math::IntegerSqrt;
+use minidex::state::Pool;
+use minidex::utils::*;
+use minidex_sdk::test_utils::PoolTestDefault;
+use proptest::prelude::*;
+
+fn pool(reserve_a: u64, reserve_b: u64, total_lp_supply: u64, fee_rate: u16) -> Pool {
+    Pool {
+        fee_rate,
+        reserve_a,
+        reserve_b,
+        total_lp_supply,
+        ..Pool::test_default()
+    }
+}
+
+/// Token amounts spread over small, mid-sized and extreme values.
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![1..=10_000u64, 1..=u32::MAX as u64, 1..=u64::MAX]
+}
+
+fn fee_rate() -> impl Strategy<Value = u16> {
+    0..=MAX_FEE_RATE
+}
+
+/// Applies a deposit the way `add_liquidity` does and returns the LP tokens
+/// paid to the depositor.
+fn deposit(pool: &mut Pool, amount_a: u64, amount_b: u64) -> Option<u64> {
+    let lp_tokens = calculate_lp_tokens(pool, amount_a, amount_b).ok().filter(|lp| *lp > 0)?;
+    let minted = if pool.total_lp_supply == 0 { lp_tokens + MINIMUM_LIQUIDITY } else { lp_tokens };
+
+    pool.reserve_a = pool.reserve_a.checked_add(amount_a)?;
+    pool.reserve_b = pool.reserve_b.checked_add(amount_b)?;
+    pool.total_lp_supply = pool.total_lp_supply.checked_add(minted)?;
+
+    Some(lp_tokens)
+}
+
+/// Applies a swap the way `swap_tokens` does and returns the amount paid out.
+fn swap(pool: &mut Pool, amount_in: u64, token_a_to_b: bool) -> Option<u64> {
+    let amount_out = calculate_swap_output(pool, amount_in, token_a_to_b).ok()?;
+    let (reserve_a, reserve_b) = calculate_reserves_after_swap(pool, amount_in, amount_out, token_a_to_b).ok()?;
+
+    pool.reserve_a = reserve_a;
+    pool.reserve_b = reserve_b;
+
+    Some(amount_out)
+}
+
+proptest! {
+    #[test]
+    fn swap_never_decreases_k(
+        reserve_a in amount(),
+        reserve_b in amount(),
+        amount_in in amount(),
+        fee_rate in fee_rate(),
+        token_a_to_b: bool,
+    ) {
+        let mut pool = pool(reserve_a, reserve_b, 1, fee_rate);
+
+        if swap(&mut pool, amount_in, token_a_to_b).is_some() {
+            let k_before = reserve_a as u128 * reserve_b as u128;
+            let k_after = pool.reserve_a as u128 * pool.reserve_b as u128;
+
+            prop_assert!(k_after >= k_before);
+            prop_assert!(pool.reserve_a > 0 && pool.reserve_b > 0);
+        }
+    }
+
+    #[test]
+    fn swap_output_stays_below_the_fee_free_price(
+        reserve_a in amount(),
+        reserve_b in amount(),
+        amount_in in amount(),
+        fee_rate in fee_rate(),
+        token_a_to_b: bool,
+    ) {
+        let (reserve_in, reserve_out) = if token_a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+
+        if let Ok(amount_out) = calculate_swap_output(&pool(reserve_a, reserve_b, 1, fee_rate), amount_in, token_a_to_b) {
+            let fee_free = amount_in as u128 * reserve_out as u128 / (reserve_in as u128 + amount_in as u128);
+
+            prop_assert!(amount_out as u128 <= fee_free);
+            prop_assert!(amount_out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn round_trip_swap_never_profits(
+        reserve_a in amount(),
+        reserve_b in amount(),
+        amount_in in amount(),
+        fee_rate in fee_rate(),
+    ) {
+        let mut pool = pool(reserve_a, reserve_b, 1, fee_rate);
+
+        if let Some(amount_b) = swap(&mut pool, amount_in, true).filter(|out| *out > 0) {
+            if let Some(amount_a) = swap(&mut pool, amount_b, false) {
+                prop_assert!(amount_a <= amount_in);
+            }
+        }
+    }
+
+    #[test]
+    fn first_deposit_mints_the_geometric_mean(amount_a in amount(), amount_b in amount()) {
+        let product = amount_a as u128 * amount_b as u128;
+
+        match calculate_lp_tokens(&pool(0, 0, 0, 30), amount_a, amount_b) {
+            Ok(lp_tokens) => prop_assert_eq!(lp_tokens as u128 + MINIMUM_LIQUIDITY as u128, product.integer_sqrt()),
+            Err(_) => prop_assert!(product.integer_sqrt() < MINIMUM_LIQUIDITY as u128),
+        }
+    }
+
+    #[test]
+    fn deposit_never_mints_more_than_its_share(
+        reserve_a in amount(),
+        reserve_b in amount(),
+        total_lp_supply in amount(),
+        amount_a in amount(),
+        amount_b in amount(),
+    ) {
+        let pool = pool(reserve_a, reserve_b, total_lp_supply, 30);
+
+        if let Ok(lp_tokens) = calculate_lp_tokens(&pool, amount_a, amount_b) {
+            let lp_tokens = lp_tokens as u128;
+
+            prop_assert!(lp_tokens * reserve_a as u128 <= amount_a as u128 * total_lp_supply as u128);
+            prop_assert!(lp_tokens * reserve_b as u128 <= amount_b as u128 * total_lp_supply as u128);
+        }
+    }
+
+    #[test]
+    fn withdrawal_never_pays_more_than_its_share(
+        reserve_a in amount(),
+        reserve_b in amount(),
+        total_lp_supply in amount(),
+        lp_tokens in amount(),
+    ) {
+        let lp_tokens = lp_tokens.min(total_lp_supply);
+        let pool = pool(reserve_a, reserve_b, total_lp_supply, 30);
+
+        let (amount_a, amount_b) = calculate_withdrawal_amounts(&pool, lp_tokens).unwrap();
+
+        prop_assert!(amount_a <= reserve_a && amount_b <= reserve_b);
+        prop_assert!(amount_a as u128 * total_lp_supply as u128 <= lp_tokens as u128 * reserve_a as u128);
+        prop_assert!(amount_b as u128 * total_lp_supply as u128 <= lp_tokens as u128 * reserve_b as u128);
+    }
+
+    #[test]
+    fn add_then_remove_never_returns_more_than_deposited(
+        existing in proptest::option::of((amount(), amount(), amount())),
+        amount_a in amount(),
+        amount_b in amount(),
+    ) {
+        let mut pool = match existing {
+            Some((reserve_a, reserve_b, total_lp_supply)) => pool(reserve_a, reserve_b, total_lp_supply, 30),
+            None => pool(0, 0, 0, 30),
+        };
+
+        if let Some(lp_tokens) = deposit(&mut pool, amount_a, amount_b) {
+            let (withdrawn_a, withdrawn_b) = calculate_withdrawal_amounts(&pool, lp_tokens).unwrap();
+
+            prop_assert!(withdrawn_a <= amount_a);
+            prop_assert!(withdrawn_b <= amount_b);
+        }
+    }
+
+    #[test]
+    fn single_sided_split_beats_swapping_more_or_less(
+        reserve_a in 1_000_000..=1_000_000_000_000_000u64,
+        reserve_b in 1_000_000..=1_000_000_000_000_000u64,
+        share in 1..=100u64,
+        fee_rate in fee_rate(),
+        token_is_a: bool,
+    ) {
+        let pool = pool(reserve_a, reserve_b, reserve_a.min(reserve_b), fee_rate);
+        let amount_in = if token_is_a { reserve_a } else { reserve_b } / 100 * share;
+
+        // LP tokens for depositing `amount_in` after swapping `swap_amount` of it
+        let lp_tokens = |swap_amount: u64| {
+            let mut pool = pool.clone();
+            let swap_output = swap(&mut pool, swap_amount, token_is_a).unwrap();
+            let deposit = amount_in - swap_amount;
+            let (amount_a, amount_b) = if token_is_a { (deposit, swap_output) } else { (swap_output, deposit) };
+
+            calculate_lp_tokens(&pool, amount_a, amount_b).unwrap()
+        };
+
+        let quote = calculate_single_sided_deposit_quote(&pool, amount_in, token_is_a, 0).unwrap();
+
+        prop_assert_eq!(quote.lp_tokens, lp_tokens(quote.swap_amount));
+        prop_assert!(quote.lp_tokens >= lp_tokens(quote.swap_amount / 100 * 99));
+        prop_assert!(quote.lp_tokens >= lp_tokens(quote.swap_amount / 100 * 101));
+    }
+
+    #[test]
+    fn math_never_panics(
+        reserve_a: u64,
+        reserve_b: u64,
+        total_lp_supply: u64,
+        amount_a: u64,
+        amount_b: u64,
+        fee_rate: u16,
+        slot: u64,
+        token_a_to_b: bool,
+        referral_bps: u16,
+        volatility_fee_multiplier: u16,
+        volatility_accumulator: u64,
+        volatility_update_slot: u64,
+    ) {
+        let pool = Pool {
+            max_referral_bps: referral_bps,
+            volatility_fee_multiplier,
+            min_fee_rate: fee_rate,
+            volatility_accumulator,
+            volatility_update_slot,
+            ..pool(reserve_a, reserve_b, total_lp_supply, fee_rate)
+        };
+
+        let _ = calculate_swap_output(&pool, amount_a, token_a_to_b);
+        let _ = calculate_swap_fee(&pool, amount_a);
+        let _ = calculate_reserves_after_swap(&pool, amount_a, amount_b, token_a_to_b);
+        let _ = calculate_price_move_bps(reserve_a, reserve_b, amount_a, amount_b);
+        let _ = check_price_limits(&pool, amount_a, amount_b, slot);
+        let _ = calculate_lp_tokens(&pool, amount_a, amount_b);
+        let _ = calculate_withdrawal_amounts(&pool, amount_a);
+        let _ = calculate_swap_quote(&pool, amount_a, token_a_to_b, slot);
+        let _ = calculate_add_liquidity_quote(&pool, amount_a, amount_b);
+        let _ = calculate_remove_liquidity_quote(&pool, amount_a);
+        let _ = calculate_single_sided_deposit_quote(&pool, amount_a, token_a_to_b, slot);
+        let _ = calculate_single_sided_withdrawal_quote(&pool, amount_a, token_a_to_b, slot);
+        let _ = calculate_referral_fee(&pool, amount_a, referral_bps);
+
+        let mut pool = pool;
+        decay_volatility(&mut pool, slot);
+        let _ = record_swap_volatility(&mut pool, amount_a, amount_b, slot);
+    }
+
+    #[test]
+    fn dynamic_fee_stays_between_the_floor_and_the_maximum(
+        min_fee_rate in fee_rate(),
+        volatility_fee_multiplier in 1..=u16::MAX,
+        volatility_accumulator in 0..=MAX_VOLATILITY_ACCUMULATOR,
+        slot: u64,
+    ) {
+        let pool = Pool {
+            min_fee_rate,
+            volatility_fee_multiplier,
+            volatility_accumulator,
+            ..pool(1, 1, 1, 30)
+        };
+
+        let fee_rate = pool.effective_fee_rate();
+        prop_assert!(fee_rate >= min_fee_rate && fee_rate <= MAX_FEE_RATE);
+
+        // Decay only ever lowers it
+        let mut decayed = pool.clone();
+        decay_volatility(&mut decayed, slot);
+        prop_assert!(decayed.effective_fee_rate() >= min_fee_rate && decayed.effective_fee_rate() <= fee_rate);
+    }
+
+    #[test]
+    fn integer_sqrt_is_exact(n: u128) {
+        let root = n.integer_sqrt();
+
+        prop_assert!(root * root <= n);
+        prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > n));
+    }
+
+    #[test]
+    fn integer_sqrt_of_a_square_is_its_root(root: u64) {
+        prop_assert_eq!((root as u128 * root as u128).integer_sqrt(), root as u128);
+    }
+}
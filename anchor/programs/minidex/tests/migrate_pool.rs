@@ -0,0 +1,98 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{Discriminator, Space};
+use common::*;
+use minidex_sdk::constants::POOL_STATUS_MASK;
+use minidex_sdk::{instructions, MinidexError, Pool};
+use minidex_svm::Account;
+
+/// Discriminator, the six keys, fee rate, bump, both reserves and the LP
+/// supply: `Pool` before any field was appended to it.
+const ORIGINAL_POOL_LEN: usize = 8 + 6 * 32 + 2 + 1 + 3 * 8;
+
+const POOL_LEN: usize = Pool::DISCRIMINATOR.len() + Pool::INIT_SPACE;
+
+/// Cuts the pool account back to `len` bytes, as a pool created by an older
+/// program version would be.
+fn truncate_pool(test: &mut TestPool, len: usize) {
+    let mut account = test.svm.account(&test.keys.pool).unwrap().clone();
+    account.data.truncate(len);
+    account.lamports = test.svm.rent().minimum_balance(len);
+
+    test.svm.set_account(test.keys.pool, account);
+}
+
+#[test]
+fn pools_in_the_original_layout_are_migrated() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let authority = test.authority;
+    let trader = test.create_user(10_000, 0);
+    let payer = test.create_user(0, 0);
+
+    // Non-zero appended fields must not survive the round trip
+    test.set_guardian(&authority, &Pubkey::new_unique()).unwrap();
+    test.set_pool_status(&authority, POOL_STATUS_MASK).unwrap();
+    let before = test.pool();
+    truncate_pool(&mut test, ORIGINAL_POOL_LEN);
+
+    assert_error(test.swap(&trader, 10_000, 0, true), anchor_error(ErrorCode::AccountDidNotDeserialize));
+
+    let payer_lamports = test.svm.lamports(&payer.key);
+    test.send(instructions::migrate_pool(&payer.key, &test.keys.pool), &payer.key).unwrap();
+
+    let account = test.svm.account(&test.keys.pool).unwrap();
+    assert_eq!(account.data.len(), POOL_LEN);
+    assert_eq!(account.lamports, test.svm.rent().minimum_balance(POOL_LEN));
+    assert_eq!(
+        test.svm.lamports(&payer.key),
+        payer_lamports - (test.svm.rent().minimum_balance(POOL_LEN) - test.svm.rent().minimum_balance(ORIGINAL_POOL_LEN))
+    );
+
+    let pool = test.pool();
+    assert_eq!((pool.authority, pool.lp_mint, pool.fee_rate, pool.bump), (before.authority, before.lp_mint, before.fee_rate, before.bump));
+    assert_eq!((pool.reserve_a, pool.reserve_b, pool.total_lp_supply), (before.reserve_a, before.reserve_b, before.total_lp_supply));
+    assert_eq!((pool.guardian, pool.status), (Pubkey::default(), 0));
+    assert!(!pool.allowlist_enabled);
+
+    test.swap(&trader, 10_000, 0, true).unwrap();
+}
+
+#[test]
+fn pools_in_the_current_layout_are_left_alone() {
+    let mut test = TestPool::new();
+    let payer = test.create_user(0, 0);
+
+    let result = test.send(instructions::migrate_pool(&payer.key, &test.keys.pool), &payer.key);
+    assert_minidex_error(result, MinidexError::PoolAlreadyMigrated);
+
+    // A pool migrated once is current
+    truncate_pool(&mut test, ORIGINAL_POOL_LEN);
+    test.send(instructions::migrate_pool(&payer.key, &test.keys.pool), &payer.key).unwrap();
+    let result = test.send(instructions::migrate_pool(&payer.key, &test.keys.pool), &payer.key);
+    assert_minidex_error(result, MinidexError::PoolAlreadyMigrated);
+}
+
+#[test]
+fn only_pool_accounts_are_migrated() {
+    let mut test = TestPool::new();
+    let payer = test.create_user(0, 0);
+
+    let result = test.send(instructions::migrate_pool(&payer.key, &test.keys.token_a_vault), &payer.key);
+    assert_error(result, anchor_error(ErrorCode::ConstraintOwner));
+
+    let impostor = Pubkey::new_unique();
+    test.svm.set_account(
+        impostor,
+        Account {
+            lamports: test.svm.rent().minimum_balance(ORIGINAL_POOL_LEN),
+            data: vec![1; ORIGINAL_POOL_LEN],
+            owner: minidex::ID,
+            executable: false,
+        },
+    );
+
+    let result = test.send(instructions::migrate_pool(&payer.key, &impostor), &payer.key);
+    assert_error(result, anchor_error(ErrorCode::AccountDiscriminatorMismatch));
+}
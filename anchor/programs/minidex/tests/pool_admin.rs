@@ -0,0 +1,192 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use minidex_sdk::constants::{
+    BPS_DENOMINATOR, POOL_DEPOSITS_PAUSED, POOL_STATUS_MASK, POOL_SWAPS_PAUSED, POOL_WITHDRAWALS_PAUSED,
+};
+use minidex_sdk::MinidexError;
+
+#[test]
+fn authority_sets_and_clears_status_flags() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+
+    test.set_pool_status(&authority, POOL_STATUS_MASK).unwrap();
+    assert_eq!(test.pool().status, POOL_STATUS_MASK);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    assert_eq!(test.pool().status, 0);
+}
+
+#[test]
+fn guardian_can_change_status() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let guardian = Pubkey::new_unique();
+
+    test.set_guardian(&authority, &guardian).unwrap();
+    assert_eq!(test.pool().guardian, guardian);
+
+    test.set_pool_status(&guardian, POOL_SWAPS_PAUSED).unwrap();
+    assert_eq!(test.pool().status, POOL_SWAPS_PAUSED);
+}
+
+#[test]
+fn guardian_cannot_unpause() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let guardian = Pubkey::new_unique();
+    test.set_guardian(&authority, &guardian).unwrap();
+
+    test.set_pool_status(&guardian, POOL_SWAPS_PAUSED).unwrap();
+    test.set_pool_status(&guardian, POOL_SWAPS_PAUSED | POOL_DEPOSITS_PAUSED).unwrap();
+
+    assert_minidex_error(test.set_pool_status(&guardian, POOL_SWAPS_PAUSED), MinidexError::Unauthorized);
+    assert_minidex_error(test.set_pool_status(&guardian, 0), MinidexError::Unauthorized);
+    assert_eq!(test.pool().status, POOL_SWAPS_PAUSED | POOL_DEPOSITS_PAUSED);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    assert_eq!(test.pool().status, 0);
+}
+
+#[test]
+fn rejects_status_change_by_a_stranger() {
+    let mut test = TestPool::new();
+
+    assert_minidex_error(
+        test.set_pool_status(&Pubkey::new_unique(), POOL_SWAPS_PAUSED),
+        MinidexError::Unauthorized,
+    );
+}
+
+#[test]
+fn replaced_guardian_loses_access() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let guardian = Pubkey::new_unique();
+
+    test.set_guardian(&authority, &guardian).unwrap();
+    test.set_guardian(&authority, &Pubkey::new_unique()).unwrap();
+
+    assert_minidex_error(test.set_pool_status(&guardian, POOL_SWAPS_PAUSED), MinidexError::Unauthorized);
+}
+
+#[test]
+fn rejects_unknown_status_flags() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+
+    assert_minidex_error(
+        test.set_pool_status(&authority, POOL_STATUS_MASK + 1),
+        MinidexError::InvalidPoolStatus,
+    );
+}
+
+#[test]
+fn only_the_authority_sets_the_guardian() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let guardian = Pubkey::new_unique();
+    test.set_guardian(&authority, &guardian).unwrap();
+
+    assert_minidex_error(test.set_guardian(&guardian, &guardian), MinidexError::Unauthorized);
+    assert_minidex_error(
+        test.set_guardian(&Pubkey::new_unique(), &Pubkey::new_unique()),
+        MinidexError::Unauthorized,
+    );
+}
+
+#[test]
+fn paused_swaps_are_rejected_until_resumed() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let trader = test.create_user(1_000, 0);
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED).unwrap();
+    assert_minidex_error(test.swap(&trader, 1_000, 0, true), MinidexError::SwapsPaused);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    test.swap(&trader, 1_000, 0, true).unwrap();
+}
+
+#[test]
+fn paused_deposits_are_rejected() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let user = test.create_user(1_000, 1_000);
+
+    test.set_pool_status(&authority, POOL_DEPOSITS_PAUSED).unwrap();
+
+    assert_minidex_error(test.add_liquidity(&user, 1_000, 1_000, 0), MinidexError::DepositsPaused);
+    // Withdrawals still work, so liquidity providers can leave
+    test.remove_liquidity(&provider, 1_000, 0, 0).unwrap();
+}
+
+#[test]
+fn paused_withdrawals_are_rejected() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+
+    test.set_pool_status(&authority, POOL_WITHDRAWALS_PAUSED).unwrap();
+
+    assert_minidex_error(test.remove_liquidity(&provider, 1_000, 0, 0), MinidexError::WithdrawalsPaused);
+}
+
+#[test]
+fn authority_sets_price_limits() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+
+    test.set_price_limits(&authority, 100, BPS_DENOMINATOR as u16).unwrap();
+
+    let pool = test.pool();
+    assert_eq!((pool.max_price_impact_bps, pool.max_slot_price_move_bps), (100, 10_000));
+}
+
+#[test]
+fn rejects_price_limits_above_one_hundred_percent() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+
+    assert_minidex_error(test.set_price_limits(&authority, 10_001, 0), MinidexError::InvalidPriceLimit);
+    assert_minidex_error(test.set_price_limits(&authority, 0, 10_001), MinidexError::InvalidPriceLimit);
+}
+
+#[test]
+fn only_the_authority_sets_price_limits() {
+    let mut test = TestPool::new();
+    let authority = test.authority;
+    let guardian = Pubkey::new_unique();
+    test.set_guardian(&authority, &guardian).unwrap();
+
+    assert_minidex_error(test.set_price_limits(&guardian, 100, 100), MinidexError::Unauthorized);
+}
+
+#[test]
+fn rejects_swap_above_max_price_impact() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let trader = test.create_user(10_000, 0);
+
+    test.set_price_limits(&authority, 100, 0).unwrap();
+
+    assert_minidex_error(test.swap(&trader, 10_000, 0, true), MinidexError::PriceImpactExceeded);
+    test.swap(&trader, 1_000, 0, true).unwrap();
+}
+
+#[test]
+fn rejects_cumulative_price_move_within_a_slot() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let trader = test.create_user(30_000, 0);
+
+    test.set_price_limits(&authority, 0, 300).unwrap();
+    test.svm.warp_to_slot(10);
+
+    test.swap(&trader, 10_000, 0, true).unwrap();
+    assert_minidex_error(test.swap(&trader, 10_000, 0, true), MinidexError::SlotPriceMoveExceeded);
+
+    test.svm.warp_to_slot(11);
+    test.swap(&trader, 10_000, 0, true).unwrap();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+use minidex_sdk::constants::{POOL_DEPOSITS_PAUSED, POOL_SWAPS_PAUSED, POOL_WITHDRAWALS_PAUSED};
+use minidex_sdk::{instructions, MinidexError, RemoveLiquidityQuote, SwapQuote};
+
+#[test]
+fn swap_quote_matches_the_executed_swap() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let trader = test.create_user(10_000, 0);
+
+    let quote: SwapQuote = test.view(instructions::quote_swap(&test.keys.pool, 10_000, true)).unwrap();
+    test.swap(&trader, 10_000, quote.amount_out, true).unwrap();
+
+    assert_eq!(quote.amount_out, test.svm.token_balance(&trader.token_b));
+    assert_eq!(quote.fee_amount, 30);
+    assert!(quote.price_impact_bps > 0);
+}
+
+#[test]
+fn add_liquidity_quote_matches_the_minted_amount() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let user = test.create_user(500_000, 3_000_000);
+
+    let quote: u64 = test
+        .view(instructions::quote_add_liquidity(&test.keys.pool, 500_000, 3_000_000))
+        .unwrap();
+    test.add_liquidity(&user, 500_000, 3_000_000, quote).unwrap();
+
+    assert_eq!(quote, test.lp_balance(&user));
+}
+
+#[test]
+fn remove_liquidity_quote_matches_the_payout() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+
+    let quote: RemoveLiquidityQuote = test
+        .view(instructions::quote_remove_liquidity(&test.keys.pool, 500_000))
+        .unwrap();
+    test.remove_liquidity(&provider, 500_000, quote.amount_a, quote.amount_b).unwrap();
+
+    assert_eq!(quote.amount_a, test.svm.token_balance(&provider.token_a));
+    assert_eq!(quote.amount_b, test.svm.token_balance(&provider.token_b));
+}
+
+#[test]
+fn quotes_do_not_change_the_pool() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let pool = test.keys.pool;
+    let before = test.svm.account(&pool).unwrap();
+
+    test.svm
+        .process_transaction(
+            &[
+                instructions::quote_swap(&pool, 1_000, true),
+                instructions::quote_add_liquidity(&pool, 1_000, 1_000),
+                instructions::quote_remove_liquidity(&pool, 1_000),
+            ],
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(test.svm.account(&test.keys.pool).unwrap(), before);
+}
+
+#[test]
+fn quotes_fail_like_the_instructions_they_mirror() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let pool = test.keys.pool;
+
+    assert_eq!(
+        test.view::<SwapQuote>(instructions::quote_swap(&pool, 0, true)),
+        Err(minidex_error(MinidexError::ZeroSwapAmount))
+    );
+    assert_eq!(
+        test.view::<u64>(instructions::quote_add_liquidity(&pool, 0, 1)),
+        Err(minidex_error(MinidexError::ZeroAmount))
+    );
+    assert_eq!(
+        test.view::<RemoveLiquidityQuote>(instructions::quote_remove_liquidity(&pool, 0)),
+        Err(minidex_error(MinidexError::ZeroLPTokens))
+    );
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED | POOL_DEPOSITS_PAUSED | POOL_WITHDRAWALS_PAUSED)
+        .unwrap();
+
+    assert_eq!(
+        test.view::<SwapQuote>(instructions::quote_swap(&pool, 1_000, true)),
+        Err(minidex_error(MinidexError::SwapsPaused))
+    );
+    assert_eq!(
+        test.view::<u64>(instructions::quote_add_liquidity(&pool, 1_000, 1_000)),
+        Err(minidex_error(MinidexError::DepositsPaused))
+    );
+    assert_eq!(
+        test.view::<RemoveLiquidityQuote>(instructions::quote_remove_liquidity(&pool, 1_000)),
+        Err(minidex_error(MinidexError::WithdrawalsPaused))
+    );
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use common::*;
+use minidex_sdk::events::{parse_logs, MinidexEvent};
+use minidex_sdk::instructions;
+use minidex_sdk::math::{calculate_swap_fee, calculate_swap_output};
+use minidex_sdk::{MinidexError, PROGRAM_ID};
+use minidex_svm::TransactionResult;
+
+/// A 1:4 pool paying referrers up to half of the swap fee.
+fn setup() -> TestPool {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let authority = test.authority;
+    test.set_max_referral_bps(&authority, 5_000).unwrap();
+
+    test
+}
+
+/// A referrer and its token account for `mint`.
+fn referrer_account(test: &mut TestPool, mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let referrer = Pubkey::new_unique();
+    (referrer, test.svm.create_associated_token_account(mint, &referrer, 0))
+}
+
+fn swap_with_referral(
+    test: &mut TestPool,
+    user: &User,
+    amount_in: u64,
+    token_a_to_b: bool,
+    (referrer, referrer_token_account): &(Pubkey, Pubkey),
+    referral_bps: u16,
+) -> TransactionResult {
+    let instruction = instructions::swap_tokens_with_referral(
+        &user.key,
+        &test.keys,
+        &user.token_a,
+        &user.token_b,
+        amount_in,
+        0,
+        token_a_to_b,
+        referrer,
+        referrer_token_account,
+        referral_bps,
+        None,
+    );
+    test.send(instruction, &user.key)
+}
+
+#[test]
+fn referrers_get_their_share_of_the_fee_in_the_input_token() {
+    let mut test = setup();
+    let token_a_mint = test.keys.token_a_mint;
+    let referrer = referrer_account(&mut test, &token_a_mint);
+    let trader = test.create_user(100_000, 0);
+
+    let amount_out = calculate_swap_output(&test.pool(), 100_000, true).unwrap();
+    let fee_amount = calculate_swap_fee(&test.pool(), 100_000).unwrap();
+    assert_eq!(fee_amount, 300);
+
+    let metadata = swap_with_referral(&mut test, &trader, 100_000, true, &referrer, 5_000).unwrap();
+
+    // The trader gets the same output as without a referrer
+    let pool = test.pool();
+    assert_eq!(test.svm.token_balance(&referrer.1), 150);
+    assert_eq!(test.svm.token_balance(&trader.token_a), 0);
+    assert_eq!(test.svm.token_balance(&trader.token_b), amount_out);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_099_850, 4_000_000 - amount_out));
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), pool.reserve_a);
+
+    match parse_logs(&metadata.logs).as_slice() {
+        [MinidexEvent::Swap(swap)] => {
+            assert_eq!((swap.fee_amount, swap.referrer, swap.referral_amount), (fee_amount, referrer.1, 150));
+        }
+        events => panic!("unexpected events {events:?}"),
+    }
+}
+
+#[test]
+fn the_rest_of_the_fee_still_grows_the_constant_product() {
+    let mut test = setup();
+    let token_b_mint = test.keys.token_b_mint;
+    let referrer = referrer_account(&mut test, &token_b_mint);
+    let trader = test.create_user(0, 400_000);
+
+    let before = test.pool();
+    swap_with_referral(&mut test, &trader, 400_000, false, &referrer, 5_000).unwrap();
+    let after = test.pool();
+
+    assert_eq!(test.svm.token_balance(&referrer.1), 600);
+    assert!(after.reserve_a as u128 * after.reserve_b as u128 > before.reserve_a as u128 * before.reserve_b as u128);
+}
+
+#[test]
+fn referral_fees_are_capped_by_the_pool() {
+    let mut test = setup();
+    let authority = test.authority;
+    let token_a_mint = test.keys.token_a_mint;
+    let referrer = referrer_account(&mut test, &token_a_mint);
+    let trader = test.create_user(100_000, 0);
+
+    assert_minidex_error(
+        swap_with_referral(&mut test, &trader, 10_000, true, &referrer, 5_001),
+        MinidexError::ReferralFeeTooHigh,
+    );
+
+    test.set_max_referral_bps(&authority, 0).unwrap();
+    assert_minidex_error(
+        swap_with_referral(&mut test, &trader, 10_000, true, &referrer, 1),
+        MinidexError::ReferralFeeTooHigh,
+    );
+
+    // A referrer with no share is paid nothing
+    swap_with_referral(&mut test, &trader, 10_000, true, &referrer, 0).unwrap();
+    assert_eq!(test.svm.token_balance(&referrer.1), 0);
+}
+
+#[test]
+fn rejects_referrals_without_a_matching_referrer() {
+    let mut test = setup();
+    let token_b_mint = test.keys.token_b_mint;
+    let wrong_mint = referrer_account(&mut test, &token_b_mint);
+    let trader = test.create_user(100_000, 0);
+
+    assert_error(
+        swap_with_referral(&mut test, &trader, 10_000, true, &wrong_mint, 1_000),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintTokenMint),
+    );
+
+    // Asking for a share with the referrer left out
+    let (referrer, referrer_token_account) = wrong_mint;
+    let mut instruction = instructions::swap_tokens_with_referral(
+        &trader.key,
+        &test.keys,
+        &trader.token_a,
+        &trader.token_b,
+        10_000,
+        0,
+        true,
+        &referrer,
+        &referrer_token_account,
+        1_000,
+        None,
+    );
+    instruction.accounts[6] = AccountMeta::new_readonly(PROGRAM_ID, false);
+    instruction.accounts[7] = AccountMeta::new_readonly(PROGRAM_ID, false);
+    assert_minidex_error(test.send(instruction, &trader.key), MinidexError::MissingReferrer);
+}
+
+#[test]
+fn the_referrer_must_own_the_referrer_token_account() {
+    let mut test = setup();
+    let token_a_mint = test.keys.token_a_mint;
+    let (_, referrer_token_account) = referrer_account(&mut test, &token_a_mint);
+    let trader = test.create_user(100_000, 0);
+
+    // Routing the referral to someone else's account under the trader's name
+    assert_error(
+        swap_with_referral(&mut test, &trader, 10_000, true, &(trader.key, referrer_token_account), 1_000),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintTokenOwner),
+    );
+
+    // A referrer token account without its owner
+    let mut instruction = instructions::swap_tokens_with_referral(
+        &trader.key,
+        &test.keys,
+        &trader.token_a,
+        &trader.token_b,
+        10_000,
+        0,
+        true,
+        &trader.key,
+        &referrer_token_account,
+        1_000,
+        None,
+    );
+    instruction.accounts[7] = AccountMeta::new_readonly(PROGRAM_ID, false);
+    assert_error(
+        test.send(instruction, &trader.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintAccountIsNone),
+    );
+    assert_eq!(test.svm.token_balance(&referrer_token_account), 0);
+}
+
+#[test]
+fn only_the_authority_sets_a_valid_cap() {
+    let mut test = setup();
+    let authority = test.authority;
+    let stranger = test.create_user(0, 0);
+
+    assert_minidex_error(test.set_max_referral_bps(&stranger.key, 1_000), MinidexError::Unauthorized);
+    assert_minidex_error(test.set_max_referral_bps(&authority, 10_001), MinidexError::InvalidReferralFee);
+
+    test.set_max_referral_bps(&authority, 10_000).unwrap();
+    assert_eq!(test.pool().max_referral_bps, 10_000);
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use common::*;
+use minidex_sdk::constants::POOL_SWAPS_PAUSED;
+use minidex_sdk::events::{parse_logs, MinidexEvent};
+use minidex_sdk::math::{
+    calculate_single_sided_deposit_quote, calculate_single_sided_withdrawal_quote, calculate_withdrawal_amounts,
+};
+use minidex_sdk::MinidexError;
+
+#[test]
+fn deposit_of_token_a_swaps_the_balancing_share() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000_000, 4_000_000_000);
+    let user = test.create_user(100_000_000, 0);
+    let quote = calculate_single_sided_deposit_quote(&test.pool(), 100_000_000, true, 1).unwrap();
+
+    test.add_liquidity_single_sided(&user, 100_000_000, true, quote.lp_tokens).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(test.lp_balance(&user), quote.lp_tokens);
+    assert_eq!(test.svm.token_balance(&user.token_a), 0);
+    assert_eq!(test.svm.token_balance(&user.token_b), 0);
+
+    // All of the deposit stays in the pool, nothing of token B left it
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_100_000_000, 4_000_000_000));
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), pool.reserve_a);
+    assert_eq!(test.svm.token_balance(&test.keys.token_b_vault), pool.reserve_b);
+
+    // Close to half of the deposit is swapped, and what is deposited matches
+    // the pool's ratio, so burning the LP tokens returns it to within a millionth
+    assert!(quote.swap_amount > 48_000_000 && quote.swap_amount < 50_000_000, "{quote:?}");
+    let (amount_a, amount_b) = calculate_withdrawal_amounts(&pool, quote.lp_tokens).unwrap();
+    let (deposit_a, deposit_b) = (100_000_000 - quote.swap_amount, quote.swap_output);
+    assert!(amount_a <= deposit_a && deposit_a - amount_a <= deposit_a / 1_000_000, "{amount_a} of {deposit_a}");
+    assert!(amount_b <= deposit_b && deposit_b - amount_b <= deposit_b / 1_000_000, "{amount_b} of {deposit_b}");
+}
+
+#[test]
+fn deposit_of_token_b_mints_what_the_quote_says() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let user = test.create_user(0, 1_000_000);
+    let quote = calculate_single_sided_deposit_quote(&test.pool(), 1_000_000, false, 1).unwrap();
+
+    let metadata = test.add_liquidity_single_sided(&user, 1_000_000, false, quote.lp_tokens).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(test.lp_balance(&user), quote.lp_tokens);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000, 5_000_000));
+    assert_eq!(test.svm.mint(&test.keys.lp_mint).unwrap().supply + 1_000, pool.total_lp_supply);
+
+    // The internal swap is logged on its own, with its fee
+    match parse_logs(&metadata.logs).as_slice() {
+        [MinidexEvent::Swap(swap), MinidexEvent::AddLiquidity(deposit)] => {
+            assert!(!swap.token_a_to_b);
+            assert_eq!((swap.amount_in, swap.amount_out, swap.fee_amount), (quote.swap_amount, quote.swap_output, quote.fee_amount));
+            assert_eq!((deposit.amount_a, deposit.amount_b), (quote.swap_output, 1_000_000 - quote.swap_amount));
+            assert_eq!(deposit.lp_tokens, quote.lp_tokens);
+        }
+        events => panic!("unexpected events {events:?}"),
+    }
+}
+
+#[test]
+fn rejects_single_sided_deposits_below_the_minimum() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let user = test.create_user(10_000, 0);
+    let quote = calculate_single_sided_deposit_quote(&test.pool(), 10_000, true, 1).unwrap();
+
+    assert_minidex_error(
+        test.add_liquidity_single_sided(&user, 10_000, true, quote.lp_tokens + 1),
+        MinidexError::SlippageExceeded,
+    );
+    assert_minidex_error(test.add_liquidity_single_sided(&user, 0, true, 0), MinidexError::ZeroAmount);
+    assert_minidex_error(test.add_liquidity_single_sided(&user, 20_000, true, 0), MinidexError::InsufficientUserBalance);
+}
+
+#[test]
+fn single_sided_deposits_need_swaps_and_liquidity() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let user = test.create_user(10_000, 0);
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED).unwrap();
+    assert_minidex_error(test.add_liquidity_single_sided(&user, 10_000, true, 0), MinidexError::SwapsPaused);
+
+    test.set_pool_status(&authority, 0).unwrap();
+    test.set_price_limits(&authority, 10, 0).unwrap();
+    assert_minidex_error(test.add_liquidity_single_sided(&user, 10_000, true, 0), MinidexError::PriceImpactExceeded);
+
+    let mut empty = TestPool::new();
+    let user = empty.create_user(10_000, 0);
+    assert_minidex_error(empty.add_liquidity_single_sided(&user, 10_000, true, 0), MinidexError::InsufficientLiquidity);
+}
+
+#[test]
+fn withdrawal_of_token_a_swaps_the_token_b_share_back() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000_000, 4_000_000_000);
+    let lp_tokens = test.lp_balance(&provider) / 10;
+    let quote = calculate_single_sided_withdrawal_quote(&test.pool(), lp_tokens, true, 1).unwrap();
+
+    test.remove_liquidity_single_sided(&provider, lp_tokens, true, quote.amount_out).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(test.svm.token_balance(&provider.token_a), quote.amount_out);
+    assert_eq!(test.svm.token_balance(&provider.token_b), 0);
+
+    // Token B never left the pool, token A paid for both sides
+    assert_eq!(pool.reserve_b, 4_000_000_000);
+    assert_eq!(pool.reserve_a, 1_000_000_000 - quote.amount_out);
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), pool.reserve_a);
+    assert_eq!(test.svm.token_balance(&test.keys.token_b_vault), pool.reserve_b);
+    assert_eq!(test.svm.mint(&test.keys.lp_mint).unwrap().supply + 1_000, pool.total_lp_supply);
+
+    // A tenth of the pool is worth about a fifth of its token A, less the
+    // fee and price impact of selling the token B half
+    assert!(quote.amount_out > 180_000_000 && quote.amount_out < 200_000_000, "{quote:?}");
+}
+
+#[test]
+fn withdrawal_of_token_b_pays_what_the_quote_says() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let quote = calculate_single_sided_withdrawal_quote(&test.pool(), 500_000, false, 1).unwrap();
+
+    let metadata = test.remove_liquidity_single_sided(&provider, 500_000, false, quote.amount_out).unwrap();
+
+    assert_eq!(test.svm.token_balance(&provider.token_a), 0);
+    assert_eq!(test.svm.token_balance(&provider.token_b), quote.amount_out);
+
+    match parse_logs(&metadata.logs).as_slice() {
+        [MinidexEvent::RemoveLiquidity(withdrawal), MinidexEvent::Swap(swap)] => {
+            assert_eq!((withdrawal.lp_tokens, withdrawal.amount_a, withdrawal.amount_b), (500_000, quote.amount_a, quote.amount_b));
+            assert!(swap.token_a_to_b);
+            assert_eq!((swap.amount_in, swap.amount_out, swap.fee_amount), (quote.amount_a, quote.swap_output, quote.fee_amount));
+            assert_eq!(quote.amount_out, quote.amount_b + quote.swap_output);
+        }
+        events => panic!("unexpected events {events:?}"),
+    }
+}
+
+#[test]
+fn rejects_single_sided_withdrawals_below_the_minimum() {
+    let (mut test, provider) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let authority = test.authority;
+    let lp_tokens = test.lp_balance(&provider);
+    let quote = calculate_single_sided_withdrawal_quote(&test.pool(), 10_000, true, 1).unwrap();
+
+    assert_minidex_error(
+        test.remove_liquidity_single_sided(&provider, 10_000, true, quote.amount_out + 1),
+        MinidexError::SlippageExceeded,
+    );
+    assert_minidex_error(test.remove_liquidity_single_sided(&provider, 0, true, 0), MinidexError::ZeroLPTokens);
+    assert_minidex_error(
+        test.remove_liquidity_single_sided(&provider, lp_tokens + 1, true, 0),
+        MinidexError::InsufficientLPTokens,
+    );
+
+    test.set_pool_status(&authority, POOL_SWAPS_PAUSED).unwrap();
+    assert_minidex_error(test.remove_liquidity_single_sided(&provider, 10_000, true, 0), MinidexError::SwapsPaused);
+}
+
+#[test]
+fn zapping_in_and_out_costs_the_fees() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000_000, 1_000_000_000);
+    let user = test.create_user(10_000_000, 0);
+
+    test.add_liquidity_single_sided(&user, 10_000_000, true, 0).unwrap();
+    let lp_tokens = test.lp_balance(&user);
+    test.remove_liquidity_single_sided(&user, lp_tokens, true, 0).unwrap();
+
+    // Both internal swaps charge 0.3% on about half the amount
+    let returned = test.svm.token_balance(&user.token_a);
+    assert!(returned < 10_000_000 && returned > 9_960_000, "{returned}");
+    assert_eq!(test.lp_balance(&user), 0);
+}
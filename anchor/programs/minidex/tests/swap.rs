@@ -0,0 +1,158 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::InstructionError;
+use common::*;
+use minidex_sdk::instructions;
+use minidex_sdk::math::calculate_swap_output;
+use minidex_sdk::MinidexError;
+
+#[test]
+fn swaps_token_a_for_token_b() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let trader = test.create_user(10_000, 0);
+    let expected = calculate_swap_output(&test.pool(), 10_000, true).unwrap();
+
+    test.swap(&trader, 10_000, expected, true).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(expected, 39_486);
+    assert_eq!(test.svm.token_balance(&trader.token_a), 0);
+    assert_eq!(test.svm.token_balance(&trader.token_b), expected);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_010_000, 4_000_000 - expected));
+    assert_eq!(test.svm.token_balance(&test.keys.token_a_vault), pool.reserve_a);
+    assert_eq!(test.svm.token_balance(&test.keys.token_b_vault), pool.reserve_b);
+}
+
+#[test]
+fn swaps_token_b_for_token_a() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let trader = test.create_user(0, 40_000);
+    let expected = calculate_swap_output(&test.pool(), 40_000, false).unwrap();
+
+    test.swap(&trader, 40_000, expected, false).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(test.svm.token_balance(&trader.token_a), expected);
+    assert_eq!(test.svm.token_balance(&trader.token_b), 0);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000 - expected, 4_040_000));
+}
+
+#[test]
+fn fees_grow_the_constant_product() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(100_000, 100_000);
+    let mut k = 1_000_000u128 * 1_000_000;
+
+    for (amount_in, token_a_to_b) in [(50_000, true), (20_000, false), (70_000, false), (1, true)] {
+        test.swap(&trader, amount_in, 0, token_a_to_b).unwrap();
+
+        let pool = test.pool();
+        let k_after = pool.reserve_a as u128 * pool.reserve_b as u128;
+        assert!(k_after >= k);
+        k = k_after;
+    }
+}
+
+#[test]
+fn records_the_slot_of_the_first_trade() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(20_000, 0);
+
+    test.svm.warp_to_slot(42);
+    test.swap(&trader, 10_000, 0, true).unwrap();
+    test.swap(&trader, 10_000, 0, true).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(pool.last_trade_slot, 42);
+    assert_eq!((pool.slot_start_reserve_a, pool.slot_start_reserve_b), (1_000_000, 1_000_000));
+}
+
+#[test]
+fn rejects_zero_swap_amount() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(10_000, 0);
+
+    assert_minidex_error(test.swap(&trader, 0, 0, true), MinidexError::ZeroSwapAmount);
+}
+
+#[test]
+fn rejects_swap_against_an_empty_pool() {
+    let mut test = TestPool::new();
+    let trader = test.create_user(10_000, 0);
+
+    assert_minidex_error(test.swap(&trader, 10_000, 0, true), MinidexError::InsufficientLiquidity);
+}
+
+#[test]
+fn rejects_swap_below_min_amount_out() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 4_000_000);
+    let trader = test.create_user(10_000, 0);
+
+    assert_minidex_error(test.swap(&trader, 10_000, 39_487, true), MinidexError::SlippageExceeded);
+    assert_eq!(test.svm.token_balance(&trader.token_a), 10_000);
+}
+
+#[test]
+fn rejects_swap_above_the_user_balance() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(10_000, 10_000);
+
+    assert_minidex_error(test.swap(&trader, 10_001, 0, true), MinidexError::InsufficientUserBalance);
+    assert_minidex_error(test.swap(&trader, 10_001, 0, false), MinidexError::InsufficientUserBalance);
+}
+
+#[test]
+fn rejects_swap_that_would_overflow_the_reserve() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(u64::MAX - 1_000_000, 0);
+
+    assert_minidex_error(test.swap(&trader, u64::MAX - 999_999, 0, true), MinidexError::MathOverflow);
+}
+
+#[test]
+fn rejects_an_unsigned_swap() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(10_000, 0);
+    let instruction = instructions::swap_tokens(&trader.key, &test.keys, &trader.token_a, &trader.token_b, 10_000, 0, true, None);
+
+    assert_error(
+        test.svm.process_transaction(&[instruction], &[]),
+        InstructionError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn rejects_a_vault_that_is_not_the_pools() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(10_000, 0);
+    let mint_a = test.keys.token_a_mint;
+    let pool = test.keys.pool;
+    let mut keys = test.keys;
+    keys.token_a_vault = test.svm.create_token_account(&mint_a, &pool, 0);
+
+    let instruction = instructions::swap_tokens(&trader.key, &keys, &trader.token_a, &trader.token_b, 10_000, 0, true, None);
+
+    assert_error(
+        test.send(instruction, &trader.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintAddress),
+    );
+}
+
+#[test]
+fn rejects_a_pool_at_the_wrong_address() {
+    let (mut test, _) = TestPool::with_liquidity(1_000_000, 1_000_000);
+    let trader = test.create_user(10_000, 0);
+    let impostor = Pubkey::new_unique();
+    let pool_account = test.svm.account(&test.keys.pool).unwrap().clone();
+    test.svm.set_account(impostor, pool_account);
+
+    let mut keys = test.keys;
+    keys.pool = impostor;
+    let instruction = instructions::swap_tokens(&trader.key, &keys, &trader.token_a, &trader.token_b, 10_000, 0, true, None);
+
+    assert_error(
+        test.send(instruction, &trader.key),
+        anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds),
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_option::COption;
+use common::*;
+use minidex_sdk::instructions;
+use minidex_sdk::{find_registered_token_address, find_token_registry_address, MinidexError, PoolKeys, RegisteredToken, TokenRegistry};
+use minidex_svm::{Svm, TransactionResult};
+
+/// Keys for a pool of two fresh mints, and a config whose admin has enabled
+/// the token registry. Pools created without a registry are covered by every
+/// other test.
+struct Setup {
+    svm: Svm,
+    admin: Pubkey,
+    keys: PoolKeys,
+}
+
+fn setup() -> Setup {
+    let (mut svm, admin, mint_a, mint_b) = common::setup();
+    let governance_mint = svm.create_mint(&admin, 6);
+
+    svm.set_upgrade_authority(Some(admin));
+    svm.process_transaction(&[instructions::initialize_config(&admin, &governance_mint)], &[admin]).unwrap();
+    svm.process_transaction(&[instructions::set_token_registry(&admin, true)], &[admin]).unwrap();
+
+    Setup { svm, admin, keys: PoolKeys::derive(&mint_a, &mint_b) }
+}
+
+impl Setup {
+    fn send(&mut self, instruction: Instruction, signer: &Pubkey) -> TransactionResult {
+        self.svm.process_transaction(&[instruction], &[*signer])
+    }
+
+    fn register(&mut self, mint: &Pubkey, skip_mint_checks: bool) {
+        let admin = self.admin;
+        self.send(instructions::register_token(&admin, mint, skip_mint_checks), &admin).unwrap();
+    }
+
+    fn create_pool(&mut self, registered: bool) -> TransactionResult {
+        let admin = self.admin;
+        let instructions = if registered {
+            instructions::create_registered_pool(&admin, &self.keys, DEFAULT_FEE_RATE)
+        } else {
+            instructions::create_pool(&admin, &self.keys, DEFAULT_FEE_RATE)
+        };
+        self.svm.process_transaction(&instructions, &[admin])
+    }
+
+    fn give_freeze_authority(&mut self, mint: &Pubkey) {
+        let mut state = self.svm.mint(mint).unwrap();
+        state.freeze_authority = COption::Some(self.admin);
+        self.svm.set_mint_state(*mint, state);
+    }
+}
+
+#[test]
+fn an_enabled_registry_rejects_unregistered_mints() {
+    let mut setup = setup();
+    let mint_a = setup.keys.token_a_mint;
+
+    assert_minidex_error(setup.create_pool(false), MinidexError::MintNotRegistered);
+
+    setup.register(&mint_a, false);
+    assert_minidex_error(setup.create_pool(false), MinidexError::MintNotRegistered);
+    // Passing the other mint's registration fails as it does not exist
+    assert_error(
+        setup.create_pool(true),
+        anchor_error(anchor_lang::error::ErrorCode::AccountNotInitialized),
+    );
+}
+
+#[test]
+fn registered_mints_can_be_paired() {
+    let mut setup = setup();
+    let (mint_a, mint_b) = (setup.keys.token_a_mint, setup.keys.token_b_mint);
+    setup.register(&mint_a, false);
+    setup.register(&mint_b, false);
+
+    let registered: RegisteredToken = setup.svm.anchor_account(&find_registered_token_address(&mint_a).0).unwrap();
+    assert_eq!((registered.mint, registered.skip_mint_checks), (mint_a, false));
+
+    setup.create_pool(true).unwrap();
+}
+
+#[test]
+fn mints_with_a_freeze_authority_need_the_admin_override() {
+    let mut setup = setup();
+    let (mint_a, mint_b) = (setup.keys.token_a_mint, setup.keys.token_b_mint);
+    setup.give_freeze_authority(&mint_b);
+    setup.register(&mint_a, false);
+    setup.register(&mint_b, false);
+
+    assert_minidex_error(setup.create_pool(true), MinidexError::MintHasFreezeAuthority);
+
+    // Registering again updates the override
+    setup.register(&mint_b, true);
+    setup.create_pool(true).unwrap();
+}
+
+#[test]
+fn a_disabled_registry_stops_checking() {
+    let mut setup = setup();
+    let admin = setup.admin;
+
+    setup.send(instructions::set_token_registry(&admin, false), &admin).unwrap();
+    let registry: TokenRegistry = setup.svm.anchor_account(&find_token_registry_address().0).unwrap();
+    assert!(!registry.enabled);
+
+    setup.create_pool(false).unwrap();
+}
+
+#[test]
+fn deregistered_mints_cannot_be_paired_again() {
+    let mut setup = setup();
+    let admin = setup.admin;
+    let (mint_a, mint_b) = (setup.keys.token_a_mint, setup.keys.token_b_mint);
+    setup.register(&mint_a, false);
+    setup.register(&mint_b, false);
+
+    let registration = find_registered_token_address(&mint_b).0;
+    let rent = setup.svm.lamports(&registration);
+    let admin_lamports = setup.svm.lamports(&admin);
+
+    setup.send(instructions::deregister_token(&admin, &mint_b), &admin).unwrap();
+
+    assert!(setup.svm.account(&registration).is_none());
+    assert_eq!(setup.svm.lamports(&admin), admin_lamports + rent);
+    assert_minidex_error(setup.create_pool(false), MinidexError::MintNotRegistered);
+}
+
+#[test]
+fn only_the_config_admin_governs_the_registry() {
+    let mut setup = setup();
+    let mint_a = setup.keys.token_a_mint;
+    let stranger = Pubkey::new_unique();
+    setup.svm.airdrop(&stranger, LAMPORTS_PER_SOL);
+
+    let result = setup.send(instructions::set_token_registry(&stranger, false), &stranger);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+
+    let result = setup.send(instructions::register_token(&stranger, &mint_a, true), &stranger);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+
+    setup.register(&mint_a, false);
+    let result = setup.send(instructions::deregister_token(&stranger, &mint_a), &stranger);
+    assert_minidex_error(result, MinidexError::Unauthorized);
+}
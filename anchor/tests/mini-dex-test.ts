@@ -105,6 +105,8 @@ describe('MiniDex', () => {
           program.programId,
         )
 
+        const [tokenRegistryPda] = PublicKey.findProgramAddressSync([Buffer.from('token_registry')], program.programId)
+
         tokenAVault = tokenAVaultPda
         tokenBVault = tokenBVaultPda
         lpMint = lpMintPda
@@ -116,6 +118,9 @@ describe('MiniDex', () => {
             pool: poolPda,
             tokenAMint: tokenAMint,
             tokenBMint: tokenBMint,
+            tokenRegistry: tokenRegistryPda,
+            registeredTokenA: null,
+            registeredTokenB: null,
             systemProgram: SystemProgram.programId,
           })
           .signers([authority])
@@ -144,6 +149,8 @@ describe('MiniDex', () => {
         program.programId,
       )
 
+      const [tokenRegistryPda] = PublicKey.findProgramAddressSync([Buffer.from('token_registry')], program.programId)
+
       tokenAVault = tokenAVaultPda
       tokenBVault = tokenBVaultPda
       lpMint = lpMintPda
@@ -156,6 +163,9 @@ describe('MiniDex', () => {
           pool: poolPda,
           tokenAMint: tokenAMint,
           tokenBMint: tokenBMint,
+          tokenRegistry: tokenRegistryPda,
+          registeredTokenA: null,
+          registeredTokenB: null,
           systemProgram: SystemProgram.programId,
         })
         .signers([authority])
@@ -236,6 +246,7 @@ describe('MiniDex', () => {
           tokenAVault: tokenAVault,
           tokenBVault: tokenBVault,
           lpMint: lpMint,
+          permission: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -272,6 +283,7 @@ describe('MiniDex', () => {
           tokenAVault: tokenAVault,
           tokenBVault: tokenBVault,
           lpMint: lpMint,
+          permission: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -307,6 +319,7 @@ describe('MiniDex', () => {
             tokenAVault: tokenAVault,
             tokenBVault: tokenBVault,
             lpMint: lpMint,
+            permission: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -332,7 +345,7 @@ describe('MiniDex', () => {
       const initialTokenB = await getAccount(provider.connection, userTokenB)
 
       await program.methods
-        .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB)
+        .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB, 0)
         .accountsStrict({
           user: user.publicKey,
           pool: poolPda,
@@ -340,6 +353,11 @@ describe('MiniDex', () => {
           userTokenBAccount: userTokenB,
           tokenAVault: tokenAVault,
           tokenBVault: tokenBVault,
+          referrerTokenAccount: null,
+          referrer: null,
+          config: null,
+          userStake: null,
+          permission: null,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .signers([user])
@@ -368,7 +386,7 @@ describe('MiniDex', () => {
       const initialTokenB = await getAccount(provider.connection, userTokenB)
 
       await program.methods
-        .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB)
+        .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB, 0)
         .accountsStrict({
           user: user.publicKey,
           pool: poolPda,
@@ -376,6 +394,11 @@ describe('MiniDex', () => {
           userTokenBAccount: userTokenB,
           tokenAVault: tokenAVault,
           tokenBVault: tokenBVault,
+          referrerTokenAccount: null,
+          referrer: null,
+          config: null,
+          userStake: null,
+          permission: null,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .signers([user])
@@ -396,7 +419,7 @@ describe('MiniDex', () => {
 
       try {
         await program.methods
-          .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB)
+          .swapTokens(new anchor.BN(amountIn), new anchor.BN(minAmountOut), aToB, 0)
           .accountsStrict({
             user: user.publicKey,
             pool: poolPda,
@@ -404,6 +427,11 @@ describe('MiniDex', () => {
             userTokenBAccount: userTokenB,
             tokenAVault: tokenAVault,
             tokenBVault: tokenBVault,
+            referrerTokenAccount: null,
+            referrer: null,
+            config: null,
+            userStake: null,
+            permission: null,
             tokenProgram: TOKEN_PROGRAM_ID,
           })
           .signers([user])
@@ -416,6 +444,339 @@ describe('MiniDex', () => {
     })
   })
 
+  describe('Quote Tests', () => {
+    it('Should quote a swap that matches the executed swap', async () => {
+      const amountIn = 500
+
+      const quote = await program.methods
+        .quoteSwap(new anchor.BN(amountIn), true)
+        .accountsStrict({ pool: poolPda })
+        .view()
+
+      const initialTokenB = await getAccount(provider.connection, userTokenB)
+
+      await program.methods
+        .swapTokens(new anchor.BN(amountIn), quote.amountOut, true, 0)
+        .accountsStrict({
+          user: user.publicKey,
+          pool: poolPda,
+          userTokenAAccount: userTokenA,
+          userTokenBAccount: userTokenB,
+          tokenAVault: tokenAVault,
+          tokenBVault: tokenBVault,
+          referrerTokenAccount: null,
+          referrer: null,
+          config: null,
+          userStake: null,
+          permission: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc()
+
+      const finalTokenB = await getAccount(provider.connection, userTokenB)
+      expect(Number(finalTokenB.amount) - Number(initialTokenB.amount)).to.equal(quote.amountOut.toNumber())
+      expect(quote.feeAmount.toNumber()).to.equal(2) // 0.3% of 500, rounded up
+    })
+
+    it('Should quote liquidity deposits and withdrawals', async () => {
+      const poolAccount = await program.account.pool.fetch(poolPda)
+
+      const lpTokens = await program.methods
+        .quoteAddLiquidity(new anchor.BN(1000), new anchor.BN(4000))
+        .accountsStrict({ pool: poolPda })
+        .view()
+
+      const expectedFromA = Math.floor((1000 * poolAccount.totalLpSupply.toNumber()) / poolAccount.reserveA.toNumber())
+      const expectedFromB = Math.floor((4000 * poolAccount.totalLpSupply.toNumber()) / poolAccount.reserveB.toNumber())
+      expect(lpTokens.toNumber()).to.equal(Math.min(expectedFromA, expectedFromB))
+
+      const withdrawal = await program.methods
+        .quoteRemoveLiquidity(new anchor.BN(1000))
+        .accountsStrict({ pool: poolPda })
+        .view()
+
+      expect(withdrawal.amountA.toNumber()).to.equal(
+        Math.floor((1000 * poolAccount.reserveA.toNumber()) / poolAccount.totalLpSupply.toNumber()),
+      )
+      expect(withdrawal.amountB.toNumber()).to.equal(
+        Math.floor((1000 * poolAccount.reserveB.toNumber()) / poolAccount.totalLpSupply.toNumber()),
+      )
+    })
+  })
+
+  describe('Pool Pause Tests', () => {
+    const SWAPS_PAUSED = 1 << 0
+    const DEPOSITS_PAUSED = 1 << 1
+    const WITHDRAWALS_PAUSED = 1 << 2
+
+    let guardian: Keypair
+
+    before(async () => {
+      guardian = Keypair.generate()
+    })
+
+    it('Should fail when a non-authority sets the pool status', async () => {
+      try {
+        await program.methods
+          .setPoolStatus(SWAPS_PAUSED)
+          .accountsStrict({
+            authority: user.publicKey,
+            pool: poolPda,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with unauthorized')
+      } catch (error) {
+        expect(error.message).to.include('Unauthorized')
+      }
+    })
+
+    it('Should let the authority assign a guardian', async () => {
+      await program.methods
+        .setGuardian(guardian.publicKey)
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+
+      const poolAccount = await program.account.pool.fetch(poolPda)
+      expect(poolAccount.guardian.toString()).to.equal(guardian.publicKey.toString())
+    })
+
+    it('Should block swaps when paused by the guardian', async () => {
+      await program.methods
+        .setPoolStatus(SWAPS_PAUSED)
+        .accountsStrict({
+          authority: guardian.publicKey,
+          pool: poolPda,
+        })
+        .signers([guardian])
+        .rpc()
+
+      try {
+        await program.methods
+          .swapTokens(new anchor.BN(1000), new anchor.BN(1), true, 0)
+          .accountsStrict({
+            user: user.publicKey,
+            pool: poolPda,
+            userTokenAAccount: userTokenA,
+            userTokenBAccount: userTokenB,
+            tokenAVault: tokenAVault,
+            tokenBVault: tokenBVault,
+            referrerTokenAccount: null,
+            referrer: null,
+            config: null,
+            userStake: null,
+            permission: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with swaps paused')
+      } catch (error) {
+        expect(error.message).to.include('SwapsPaused')
+      }
+    })
+
+    it('Should block deposits but allow withdrawals when deposits are paused', async () => {
+      await program.methods
+        .setPoolStatus(DEPOSITS_PAUSED)
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+
+      try {
+        await program.methods
+          .addLiquidity(new anchor.BN(1000), new anchor.BN(4000), new anchor.BN(1))
+          .accountsStrict({
+            user: user.publicKey,
+            pool: poolPda,
+            userTokenAAccount: userTokenA,
+            userTokenBAccount: userTokenB,
+            userLpAccount: userLpToken,
+            tokenAVault: tokenAVault,
+            tokenBVault: tokenBVault,
+            lpMint: lpMint,
+            permission: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with deposits paused')
+      } catch (error) {
+        expect(error.message).to.include('DepositsPaused')
+      }
+
+      const initialLpTokens = await getAccount(provider.connection, userLpToken)
+
+      await program.methods
+        .removeLiquidity(new anchor.BN(1000), new anchor.BN(1), new anchor.BN(1))
+        .accountsStrict({
+          user: user.publicKey,
+          pool: poolPda,
+          userTokenAAccount: userTokenA,
+          userTokenBAccount: userTokenB,
+          userLpToken: userLpToken,
+          tokenAVault: tokenAVault,
+          tokenBVault: tokenBVault,
+          lpMint: lpMint,
+          permission: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc()
+
+      const finalLpTokens = await getAccount(provider.connection, userLpToken)
+      expect(Number(finalLpTokens.amount)).to.equal(Number(initialLpTokens.amount) - 1000)
+    })
+
+    it('Should block withdrawals when withdrawals are paused', async () => {
+      await program.methods
+        .setPoolStatus(WITHDRAWALS_PAUSED)
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+
+      try {
+        await program.methods
+          .removeLiquidity(new anchor.BN(1000), new anchor.BN(1), new anchor.BN(1))
+          .accountsStrict({
+            user: user.publicKey,
+            pool: poolPda,
+            userTokenAAccount: userTokenA,
+            userTokenBAccount: userTokenB,
+            userLpToken: userLpToken,
+            tokenAVault: tokenAVault,
+            tokenBVault: tokenBVault,
+            lpMint: lpMint,
+            permission: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with withdrawals paused')
+      } catch (error) {
+        expect(error.message).to.include('WithdrawalsPaused')
+      }
+
+      // Unpause so the remaining tests run against an active pool
+      await program.methods
+        .setPoolStatus(0)
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+    })
+  })
+
+  describe('Price Limit Tests', () => {
+    it('Should fail when a non-authority sets price limits', async () => {
+      try {
+        await program.methods
+          .setPriceLimits(100, 500)
+          .accountsStrict({
+            authority: user.publicKey,
+            pool: poolPda,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with unauthorized')
+      } catch (error) {
+        expect(error.message).to.include('Unauthorized')
+      }
+    })
+
+    it('Should reject swaps above the maximum price impact', async () => {
+      await program.methods
+        .setPriceLimits(100, 0) // 1% max price impact
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+
+      const poolAccount = await program.account.pool.fetch(poolPda)
+      expect(poolAccount.maxPriceImpactBps).to.equal(100)
+
+      try {
+        await program.methods
+          .swapTokens(new anchor.BN(1000), new anchor.BN(1), true, 0)
+          .accountsStrict({
+            user: user.publicKey,
+            pool: poolPda,
+            userTokenAAccount: userTokenA,
+            userTokenBAccount: userTokenB,
+            tokenAVault: tokenAVault,
+            tokenBVault: tokenBVault,
+            referrerTokenAccount: null,
+            referrer: null,
+            config: null,
+            userStake: null,
+            permission: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc()
+
+        expect.fail('Should have failed with price impact exceeded')
+      } catch (error) {
+        expect(error.message).to.include('PriceImpactExceeded')
+      }
+
+      // Small swaps stay within the limit
+      await program.methods
+        .swapTokens(new anchor.BN(10), new anchor.BN(1), true, 0)
+        .accountsStrict({
+          user: user.publicKey,
+          pool: poolPda,
+          userTokenAAccount: userTokenA,
+          userTokenBAccount: userTokenB,
+          tokenAVault: tokenAVault,
+          tokenBVault: tokenBVault,
+          referrerTokenAccount: null,
+          referrer: null,
+          config: null,
+          userStake: null,
+          permission: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc()
+
+      await program.methods
+        .setPriceLimits(0, 0)
+        .accountsStrict({
+          authority: authority.publicKey,
+          pool: poolPda,
+        })
+        .signers([authority])
+        .rpc()
+    })
+  })
+
   describe('Liquidity Removal Tests', () => {
     it('Should remove liquidity from the pool', async () => {
       const lpTokens = 10000
@@ -438,6 +799,7 @@ describe('MiniDex', () => {
           tokenAVault: tokenAVault,
           tokenBVault: tokenBVault,
           lpMint: lpMint,
+          permission: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -478,6 +840,7 @@ describe('MiniDex', () => {
             tokenAVault: tokenAVault,
             tokenBVault: tokenBVault,
             lpMint: lpMint,
+            permission: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -508,6 +871,7 @@ describe('MiniDex', () => {
             tokenAVault: tokenAVault,
             tokenBVault: tokenBVault,
             lpMint: lpMint,
+            permission: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,